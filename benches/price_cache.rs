@@ -0,0 +1,99 @@
+//! Price cache update and read latency, alone and with concurrent writers or readers
+//!
+//! Run with `cargo bench --bench price_cache`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solana_arbitrage_watcher::config::TradingPair;
+use solana_arbitrage_watcher::price::{PriceCache, PriceSource, PriceUpdate, Quote};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// Threads hammering the cache for the duration of a benchmark
+struct Background {
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Background {
+    fn spawn(count: usize, cache: &Arc<PriceCache>, work: fn(&PriceCache, usize)) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let threads = (0..count)
+            .map(|index| {
+                let cache = Arc::clone(cache);
+                let stop = Arc::clone(&stop);
+                thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        work(&cache, index);
+                    }
+                })
+            })
+            .collect();
+        Self { stop, threads }
+    }
+}
+
+impl Drop for Background {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            thread.join().ok();
+        }
+    }
+}
+
+fn update(source: PriceSource) -> PriceUpdate {
+    PriceUpdate::new(source, TradingPair::SOL_USDT, 195.0).with_quote(Quote::new(194.9, 195.1))
+}
+
+fn write(cache: &PriceCache, index: usize) {
+    let source = [PriceSource::Binance, PriceSource::Solana, PriceSource::Orca][index % 3];
+    cache.update(&update(source));
+}
+
+fn read(cache: &PriceCache, _: usize) {
+    black_box(cache.snapshot());
+}
+
+fn populated_cache() -> Arc<PriceCache> {
+    let cache = Arc::new(PriceCache::new());
+    for source in [PriceSource::Binance, PriceSource::Solana, PriceSource::Orca] {
+        cache.update(&update(source));
+    }
+    cache
+}
+
+fn bench_price_cache(c: &mut Criterion) {
+    let threads = thread::available_parallelism().map_or(2, |n| n.get().clamp(2, 4));
+    let binance = update(PriceSource::Binance);
+    let mut group = c.benchmark_group("price_cache");
+
+    let cache = populated_cache();
+    group.bench_function("update", |b| b.iter(|| cache.update(black_box(&binance))));
+    group.bench_function("snapshot", |b| b.iter(|| black_box(cache.snapshot())));
+    group.bench_function("get_price", |b| {
+        b.iter(|| black_box(cache.get_price(PriceSource::Binance)))
+    });
+
+    // Readers must not wait for writers, and writers only retry against each other
+    {
+        let _writers = Background::spawn(threads - 1, &cache, write);
+        group.bench_function("snapshot_with_writers", |b| {
+            b.iter(|| black_box(cache.snapshot()))
+        });
+        group.bench_function("update_with_writers", |b| {
+            b.iter(|| cache.update(black_box(&binance)))
+        });
+    }
+    {
+        let _readers = Background::spawn(threads - 1, &cache, read);
+        group.bench_function("update_with_readers", |b| {
+            b.iter(|| cache.update(black_box(&binance)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_price_cache);
+criterion_main!(benches);
@@ -1,5 +1,9 @@
-use crate::config::{ProfitThreshold, TradingPair};
-use crate::price::{PriceSource, ValidatedPricePair};
+use super::inventory::{Inventory, SharedInventory, Venue};
+use super::sizing;
+use crate::config::{Config, ProfitThreshold, SizingLimits, TradingPair};
+use crate::price::{PriceCache, PriceSource, SourcePrice, TradeSide, ValidatedPricePair};
+use crate::websocket::PriceCaches;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Errors that can occur during fee calculation
@@ -80,7 +84,10 @@ impl TradingFees {
     pub fn get_trading_fee(&self, source: PriceSource) -> f64 {
         match source {
             PriceSource::Binance => self.binance_spot_fee,
-            PriceSource::Solana => self.solana_dex_fee,
+            PriceSource::Solana
+            | PriceSource::Orca
+            | PriceSource::RaydiumClmm
+            | PriceSource::OpenBook => self.solana_dex_fee,
         }
     }
 }
@@ -111,6 +118,10 @@ pub struct ArbitrageOpportunity {
     pub recommended_amount: f64,
     /// Estimated total profit for recommended amount
     pub estimated_total_profit: f64,
+    /// Base tokens the DEX leg can absorb within the depth limit, if known
+    pub dex_capacity: Option<f64>,
+    /// Price impact of the DEX leg at the recommended amount (percentage), if known
+    pub dex_price_impact: Option<f64>,
 }
 
 impl ArbitrageOpportunity {
@@ -150,27 +161,76 @@ impl ArbitrageOpportunity {
     }
 
     /// Get trading pair symbol as string
-    fn trading_pair_symbol(&self) -> &'static str {
+    fn trading_pair_symbol(&self) -> String {
         crate::util::format_trading_pair(self.trading_pair)
     }
 }
 
+/// Simulated execution of one leg
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct LegFill {
+    /// Base tokens filled
+    pub amount: f64,
+    /// Average fill price
+    pub price: f64,
+    /// Trading fee in quote currency, zero when included in the price
+    pub fee: f64,
+}
+
 /// Fee calculator for arbitrage opportunities
+#[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct FeeCalculator {
     trading_fees: TradingFees,
     default_trade_amount: f64,
+    max_ticks_crossed: u32,
+    sizing: SizingLimits,
+    /// Balances trades must fit in, shared by clones
+    inventory: Option<SharedInventory>,
+    /// Caches of the monitored SOL pairs, to value fees paid in SOL on other pairs
+    sol_price_caches: Vec<(TradingPair, Arc<PriceCache>)>,
 }
 
+/// Default number of initialized ticks a concentrated liquidity trade may cross
+pub const DEFAULT_MAX_TICKS_CROSSED: u32 = 3;
+
 impl Default for FeeCalculator {
     fn default() -> Self {
         Self {
             trading_fees: TradingFees::default(),
-            default_trade_amount: 10.0, // 10 SOL default
+            default_trade_amount: 10.0, // 10 base tokens default
+            max_ticks_crossed: DEFAULT_MAX_TICKS_CROSSED,
+            sizing: SizingLimits::default(),
+            inventory: None,
+            sol_price_caches: Vec::new(),
         }
     }
 }
 
+/// Smaller of two optional size limits
+fn min_size(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Fill prices and fees for one trade size
+struct SizedTrade {
+    amount: f64,
+    buy_price: f64,
+    sell_price: f64,
+    per_unit_fees: f64,
+    per_trade_fees: f64,
+}
+
+impl SizedTrade {
+    /// Profit for the whole trade, net of per-unit and flat per-trade fees
+    fn total_profit(&self) -> f64 {
+        (self.sell_price - self.buy_price - self.per_unit_fees) * self.amount - self.per_trade_fees
+    }
+}
+
 impl FeeCalculator {
     /// Create new fee calculator with custom fees
     #[allow(dead_code)]
@@ -185,9 +245,84 @@ impl FeeCalculator {
         Ok(Self {
             trading_fees,
             default_trade_amount,
+            max_ticks_crossed: DEFAULT_MAX_TICKS_CROSSED,
+            sizing: SizingLimits::default(),
+            inventory: None,
+            sol_price_caches: Vec::new(),
         })
     }
 
+    /// Create a fee calculator with the configured fees, trade amount, sizing limits
+    /// and inventory
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            trading_fees: config.fees.clone(),
+            default_trade_amount: config.default_trade_amount,
+            max_ticks_crossed: config.max_ticks_crossed,
+            sizing: config.sizing,
+            inventory: config
+                .inventory
+                .as_ref()
+                .map(|inventory| SharedInventory::new(Inventory::from_config(inventory))),
+            sol_price_caches: Vec::new(),
+        }
+    }
+
+    /// Value fees paid in SOL on pairs without SOL at the Binance price of the
+    /// monitored SOL pair with the same quote currency
+    pub fn with_sol_price_caches(mut self, caches: &PriceCaches) -> Self {
+        self.sol_price_caches = caches
+            .iter()
+            .filter(|(pair, _)| pair.base_is_sol())
+            .map(|(&pair, cache)| (pair, Arc::clone(cache)))
+            .collect();
+        self
+    }
+
+    /// Price of SOL in the quote currency of `pair`, to value gas and transfer fees
+    ///
+    /// On SOL pairs this is `binance_price`, the pair's own price on Binance. `None`
+    /// when no monitored SOL pair with the same quote currency has a Binance price yet.
+    pub fn sol_price(&self, pair: TradingPair, binance_price: f64) -> Option<f64> {
+        if pair.base_is_sol() {
+            return Some(binance_price);
+        }
+        if pair.quote_is_sol() {
+            return Some(1.0);
+        }
+        self.sol_price_caches
+            .iter()
+            .find(|(sol_pair, _)| sol_pair.spec().quote_mint == pair.spec().quote_mint)
+            .and_then(|(_, cache)| cache.get_price(PriceSource::Binance))
+            .map(|price| price.price)
+    }
+
+    /// Size trades to the balances on each venue and charge rebalancing costs
+    #[allow(dead_code)]
+    pub fn with_inventory(mut self, inventory: SharedInventory) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Inventory trades are sized to, if any
+    pub fn inventory(&self) -> Option<&SharedInventory> {
+        self.inventory.as_ref()
+    }
+
+    /// Limit concentrated liquidity trades to crossing at most `max_ticks` initialized ticks
+    #[allow(dead_code)]
+    pub fn with_max_ticks_crossed(mut self, max_ticks: u32) -> Self {
+        self.max_ticks_crossed = max_ticks;
+        self
+    }
+
+    /// Bound trade sizes by notional and inventory limits
+    #[allow(dead_code)]
+    pub fn with_sizing_limits(mut self, sizing: SizingLimits) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
     /// Calculate arbitrage opportunity from validated price pair
     #[allow(dead_code)]
     pub fn calculate_opportunity(
@@ -195,48 +330,70 @@ impl FeeCalculator {
         prices: &ValidatedPricePair,
         trading_pair: TradingPair,
     ) -> Result<Option<ArbitrageOpportunity>, CalculatorError> {
-        // Determine buy and sell sources
-        let (buy_source, sell_source) = if prices.solana_price.price < prices.binance_price.price {
-            (PriceSource::Solana, PriceSource::Binance)
+        let dex = &prices.solana_price;
+        let binance = &prices.binance_price;
+
+        // Pick the direction at the top of book: buys lift the ask, sells hit the bid
+        let buy_on_dex =
+            binance.execution_price(TradeSide::SellBase) - dex.execution_price(TradeSide::BuyBase);
+        let sell_on_dex =
+            dex.execution_price(TradeSide::SellBase) - binance.execution_price(TradeSide::BuyBase);
+
+        // If there's no raw profit even for the first unit, no arbitrage opportunity
+        if buy_on_dex.max(sell_on_dex) <= 0.0 {
+            return Ok(None);
+        }
+        let (buy_leg, sell_leg, dex_side) = if buy_on_dex >= sell_on_dex {
+            (dex, binance, TradeSide::BuyBase)
         } else {
-            (PriceSource::Binance, PriceSource::Solana)
+            (binance, dex, TradeSide::SellBase)
         };
 
-        let buy_price = prices.get_price(buy_source).price;
-        let sell_price = prices.get_price(sell_source).price;
-
-        // Calculate raw profit before fees
-        let raw_profit_per_unit = sell_price - buy_price;
+        // Size both legs can fill
+        let dex_capacity = self.leg_capacity(dex, dex_side);
+        let size_limit = min_size(
+            dex_capacity,
+            self.leg_capacity(binance, dex_side.opposite()),
+        );
 
-        // If there's no raw profit, no arbitrage opportunity
-        if raw_profit_per_unit <= 0.0 {
+        // Solve for the most profitable size within the depth and configured limits
+        let reference_price = buy_leg.execution_price(TradeSide::BuyBase);
+        let Some(recommended_amount) = self.calculate_recommended_amount(
+            buy_leg,
+            sell_leg,
+            trading_pair,
+            reference_price,
+            size_limit,
+        ) else {
             return Ok(None);
-        }
+        };
+        let Some(trade) = self.size_trade(buy_leg, sell_leg, trading_pair, recommended_amount)
+        else {
+            return Ok(None);
+        };
 
-        // Calculate fee breakdown (per_unit_fees, per_trade_fees)
-        let (per_unit_fees, per_trade_fees) =
-            self.calculate_fee_breakdown(buy_price, sell_price, buy_source, sell_source);
+        let (buy_price, sell_price) = (trade.buy_price, trade.sell_price);
+        let raw_profit_per_unit = sell_price - buy_price;
 
-        // Calculate net profit after fees (amortize per-trade gas for per-unit view)
-        let net_profit_per_unit =
-            raw_profit_per_unit - per_unit_fees - (per_trade_fees / self.default_trade_amount);
+        // Total fees per unit (amortize per-trade gas and transfer over the trade)
+        let total_fees_per_unit = trade.per_unit_fees + (trade.per_trade_fees / recommended_amount);
+
+        // Calculate net profit after fees
+        let net_profit_per_unit = raw_profit_per_unit - total_fees_per_unit;
 
         // Calculate profit percentage based on buy price
         let profit_percentage = (net_profit_per_unit / buy_price) * 100.0;
 
-        // Calculate recommended trade amount and total profit
-        let recommended_amount = self.calculate_recommended_amount(buy_price, net_profit_per_unit);
-
-        // Accurate total profit: variable per-unit * amount minus flat per-trade
-        let estimated_total_profit =
-            (raw_profit_per_unit - per_unit_fees) * recommended_amount - per_trade_fees;
+        let estimated_total_profit = trade.total_profit();
 
-        // Total fees per unit for display (including amortized gas)
-        let total_fees_per_unit = per_unit_fees + (per_trade_fees / self.default_trade_amount);
+        let dex_price_impact = dex
+            .liquidity
+            .as_ref()
+            .and_then(|liquidity| liquidity.price_impact(dex_side, recommended_amount));
 
         Ok(Some(ArbitrageOpportunity {
-            buy_source,
-            sell_source,
+            buy_source: buy_leg.source,
+            sell_source: sell_leg.source,
             buy_price,
             sell_price,
             raw_profit_per_unit,
@@ -246,25 +403,149 @@ impl FeeCalculator {
             trading_pair,
             recommended_amount,
             estimated_total_profit,
+            dex_capacity,
+            dex_price_impact,
         }))
     }
 
+    /// Prices and fees for trading `amount` across both legs, if both can fill it and
+    /// the fees paid in SOL can be valued
+    fn size_trade(
+        &self,
+        buy_leg: &SourcePrice,
+        sell_leg: &SourcePrice,
+        trading_pair: TradingPair,
+        amount: f64,
+    ) -> Option<SizedTrade> {
+        if amount <= 0.0 {
+            return None;
+        }
+        let buy_price = self.leg_price(buy_leg, TradeSide::BuyBase, amount)?;
+        let sell_price = self.leg_price(sell_leg, TradeSide::SellBase, amount)?;
+        let binance_leg = if buy_leg.source.is_cex() {
+            buy_leg
+        } else {
+            sell_leg
+        };
+        let sol_price = self.sol_price(trading_pair, binance_leg.price)?;
+        let (per_unit_fees, per_trade_fees) =
+            self.calculate_fee_breakdown(buy_price, sell_price, buy_leg, sell_leg, sol_price);
+        let per_trade_fees = per_trade_fees
+            + self.rebalancing_cost(
+                trading_pair,
+                buy_leg.source,
+                sell_leg.source,
+                binance_leg.price,
+                sol_price,
+            );
+
+        Some(SizedTrade {
+            amount,
+            buy_price,
+            sell_price,
+            per_unit_fees,
+            per_trade_fees,
+        })
+    }
+
+    /// Average price a leg fills at for `amount` on `side`
+    ///
+    /// Walks the depth when the leg has it. Otherwise takes the quoted bid/ask, moved by
+    /// the configured linear impact model on the CEX leg. `None` if the leg cannot fill.
+    fn leg_price(&self, price: &SourcePrice, side: TradeSide, amount: f64) -> Option<f64> {
+        if let Some(liquidity) = price.liquidity.as_ref().filter(|l| l.prices_by_size()) {
+            return liquidity.average_price(side, amount);
+        }
+
+        let quoted = price.execution_price(side);
+        match self.sizing.cex_impact_bps {
+            Some(bps) if !price.source.is_dex() => {
+                // Marginal price moves `bps` per token, so the average moves half as far
+                let impact = bps / 10_000.0 * amount / 2.0;
+                Some(match side {
+                    TradeSide::BuyBase => quoted * (1.0 + impact),
+                    TradeSide::SellBase => quoted * (1.0 - impact),
+                })
+            }
+            _ => Some(quoted),
+        }
+    }
+
+    /// Base tokens a leg can fill on `side`: its depth when bounded, else the quoted size
+    fn leg_capacity(&self, price: &SourcePrice, side: TradeSide) -> Option<f64> {
+        price
+            .liquidity
+            .as_ref()
+            .and_then(|liquidity| liquidity.base_capacity(side, self.max_ticks_crossed))
+            .or_else(|| price.available_size(side))
+    }
+
+    /// Trading fee rate for a leg, zero when its fill price already includes the fee
+    fn leg_fee_rate(&self, price: &SourcePrice) -> f64 {
+        let fee_included = price
+            .liquidity
+            .as_ref()
+            .is_some_and(|liquidity| liquidity.includes_fee());
+        if fee_included {
+            0.0
+        } else {
+            self.trading_fees.get_trading_fee(price.source) / 100.0
+        }
+    }
+
     /// Calculate fee breakdown for the arbitrage trade
     fn calculate_fee_breakdown(
         &self,
         buy_price: f64,
         sell_price: f64,
-        buy_source: PriceSource,
-        sell_source: PriceSource,
+        buy_leg: &SourcePrice,
+        sell_leg: &SourcePrice,
+        sol_price: f64,
     ) -> (f64, f64) {
         // Buy fee (percentage of buy amount)
-        let buy_fee_percentage = self.trading_fees.get_trading_fee(buy_source) / 100.0;
-        let buy_fee = buy_price * buy_fee_percentage;
+        let buy_fee = buy_price * self.leg_fee_rate(buy_leg);
 
         // Sell fee (percentage of sell amount)
-        let sell_fee_percentage = self.trading_fees.get_trading_fee(sell_source) / 100.0;
-        let sell_fee = sell_price * sell_fee_percentage;
+        let sell_fee = sell_price * self.leg_fee_rate(sell_leg);
+
+        // Return (per_unit_fees, per_trade_fees)
+        (
+            buy_fee + sell_fee,
+            self.execution_fees(buy_leg.source, sell_leg.source, sol_price),
+        )
+    }
 
+    /// Cost of rebalancing the inventory after one trade on `pair`, in quote currency
+    /// with the base token at `base_price`; zero without an inventory
+    fn rebalancing_cost(
+        &self,
+        pair: TradingPair,
+        buy_source: PriceSource,
+        sell_source: PriceSource,
+        base_price: f64,
+        sol_price: f64,
+    ) -> f64 {
+        self.inventory.as_ref().map_or(0.0, |inventory| {
+            inventory.read().rebalancing().map_or(0.0, |costs| {
+                costs.trade_cost(
+                    pair,
+                    Venue::of(buy_source),
+                    Venue::of(sell_source),
+                    base_price,
+                    sol_price,
+                )
+            })
+        })
+    }
+
+    /// Flat fees for executing one arbitrage trade in quote currency: Solana gas and
+    /// transfer, with SOL valued at `sol_price`
+    pub fn execution_fees(
+        &self,
+        buy_source: PriceSource,
+        sell_source: PriceSource,
+        sol_price: f64,
+    ) -> f64 {
         // Transfer fees (if moving between different platforms): flat per trade
         let transfer_fee_per_trade = if buy_source != sell_source {
             self.trading_fees.transfer_fee
@@ -273,33 +554,96 @@ impl FeeCalculator {
         };
 
         // Gas fees (for Solana transactions): flat per trade
-        let gas_fee_usd_total =
-            if buy_source == PriceSource::Solana || sell_source == PriceSource::Solana {
-                let sol_price = if buy_source == PriceSource::Solana {
-                    buy_price
-                } else {
-                    sell_price
-                };
-                self.trading_fees.solana_gas_fee * sol_price
-            } else {
-                0.0
-            };
+        let gas_fee_usd_total = if buy_source.is_dex() || sell_source.is_dex() {
+            self.trading_fees.solana_gas_fee * sol_price
+        } else {
+            0.0
+        };
 
-        // Return (per_unit_fees, per_trade_fees)
-        (
-            buy_fee + sell_fee,
-            gas_fee_usd_total + transfer_fee_per_trade,
-        )
+        gas_fee_usd_total + transfer_fee_per_trade
     }
 
-    /// Calculate recommended trade amount based on profit and risk
-    fn calculate_recommended_amount(&self, _buy_price: f64, net_profit_per_unit: f64) -> f64 {
-        // For now, use a simple approach: default amount unless profit is very low
-        if net_profit_per_unit > 0.0 {
-            self.default_trade_amount
-        } else {
-            1.0 // Minimum trade amount
+    /// Calculate recommended trade amount: the size that maximizes total profit
+    ///
+    /// Searches between the minimum notional and the smallest of the legs' depth, the
+    /// maximum notional, the inventory caps and the base held on the sell venue. Sizes
+    /// the buy venue cannot pay for are excluded. With no bound at all (both legs flat),
+    /// the default trade amount is used as the upper end. `None` if the limits leave no
+    /// fillable size.
+    fn calculate_recommended_amount(
+        &self,
+        buy_leg: &SourcePrice,
+        sell_leg: &SourcePrice,
+        trading_pair: TradingPair,
+        reference_price: f64,
+        size_limit: Option<f64>,
+    ) -> Option<f64> {
+        let limits = &self.sizing;
+        let notional_limit = |notional: Option<f64>| notional.map(|n| n / reference_price);
+
+        // Base to sell on the sell venue and quote to pay with on the buy venue
+        let (sell_base, buy_quote) = match &self.inventory {
+            Some(inventory) => {
+                let inventory = inventory.read();
+                let balances = inventory.balances();
+                (
+                    Some(
+                        balances
+                            .get(Venue::of(sell_leg.source))
+                            .get(trading_pair.base_symbol())
+                            .max(0.0),
+                    ),
+                    Some(
+                        balances
+                            .get(Venue::of(buy_leg.source))
+                            .get(trading_pair.quote_symbol())
+                            .max(0.0),
+                    ),
+                )
+            }
+            None => (None, None),
+        };
+
+        let upper = [
+            notional_limit(limits.max_notional),
+            notional_limit(limits.max_quote_inventory),
+            limits.max_base_inventory,
+            sell_base,
+        ]
+        .into_iter()
+        .fold(size_limit, min_size)
+        .unwrap_or(self.default_trade_amount);
+        let lower = limits.min_notional / reference_price;
+        if lower > upper {
+            return None;
+        }
+
+        sizing::maximize_profit(lower, upper, |amount| {
+            let trade = self.size_trade(buy_leg, sell_leg, trading_pair, amount)?;
+            let cost = trade.buy_price * amount * (1.0 + self.leg_fee_rate(buy_leg));
+            if buy_quote.is_some_and(|quote| cost > quote) {
+                return None;
+            }
+            Some(trade.total_profit())
+        })
+        .map(|(amount, _)| amount)
+    }
+
+    /// Simulate filling up to `amount` base tokens on one leg at its current liquidity
+    ///
+    /// Fills partially when the leg's depth is shorter than `amount`. `None` if nothing
+    /// can be filled.
+    pub fn fill_leg(&self, price: &SourcePrice, side: TradeSide, amount: f64) -> Option<LegFill> {
+        let amount = min_size(Some(amount), self.leg_capacity(price, side))?;
+        if amount <= 0.0 {
+            return None;
         }
+        let fill_price = self.leg_price(price, side, amount)?;
+        Some(LegFill {
+            amount,
+            price: fill_price,
+            fee: fill_price * amount * self.leg_fee_rate(price),
+        })
     }
 
     /// Update trading fees
@@ -381,7 +725,7 @@ mod tests {
         let price_pair = create_test_price_pair();
 
         let opportunity = calculator
-            .calculate_opportunity(&price_pair, TradingPair::SolUsdt)
+            .calculate_opportunity(&price_pair, TradingPair::SOL_USDT)
             .unwrap()
             .unwrap();
 
@@ -403,7 +747,7 @@ mod tests {
         let price_pair = ValidatedPricePair::new(solana_price, binance_price);
 
         let opportunity = calculator
-            .calculate_opportunity(&price_pair, TradingPair::SolUsdt)
+            .calculate_opportunity(&price_pair, TradingPair::SOL_USDT)
             .unwrap();
 
         // Should still create opportunity but check if profitable
@@ -419,7 +763,7 @@ mod tests {
         let price_pair = create_test_price_pair();
 
         let opportunity = calculator
-            .calculate_opportunity(&price_pair, TradingPair::SolUsdt)
+            .calculate_opportunity(&price_pair, TradingPair::SOL_USDT)
             .unwrap()
             .unwrap();
 
@@ -436,7 +780,7 @@ mod tests {
         let price_pair = create_test_price_pair();
 
         let opportunity = calculator
-            .calculate_opportunity(&price_pair, TradingPair::SolUsdt)
+            .calculate_opportunity(&price_pair, TradingPair::SOL_USDT)
             .unwrap()
             .unwrap();
 
@@ -453,7 +797,7 @@ mod tests {
         let price_pair = create_test_price_pair();
 
         let opportunity = calculator
-            .calculate_opportunity(&price_pair, TradingPair::SolUsdt)
+            .calculate_opportunity(&price_pair, TradingPair::SOL_USDT)
             .unwrap()
             .unwrap();
 
@@ -476,8 +820,9 @@ mod tests {
         let (per_unit_fees, per_trade_fees) = calculator.calculate_fee_breakdown(
             buy_price,
             sell_price,
-            PriceSource::Solana,
-            PriceSource::Binance,
+            &SourcePrice::new(buy_price, PriceSource::Solana),
+            &SourcePrice::new(sell_price, PriceSource::Binance),
+            buy_price,
         );
 
         // Should include both trading fees plus gas fee for Solana
@@ -485,6 +830,63 @@ mod tests {
         assert!(per_trade_fees > 0.0);
     }
 
+    #[test]
+    fn test_sol_fees_on_other_pairs_use_the_sol_price() {
+        use crate::pair::{PairSpec, USDT_MINT};
+        use crate::price::{PriceCache, PriceUpdate};
+        use crate::websocket::PriceCaches;
+
+        let spec: PairSpec = format!(
+            "name=jup-usdt-calculator-test,base=jup,quote=usdt,base_mint={},quote_mint={},base_decimals=6,quote_decimals=6,whirlpool={}",
+            "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+            USDT_MINT,
+            "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"
+        )
+        .parse()
+        .unwrap();
+        let jup = TradingPair::define(spec).unwrap();
+        let prices = ValidatedPricePair::new(
+            SourcePrice::new(0.90, PriceSource::Orca),
+            SourcePrice::new(1.00, PriceSource::Binance),
+        );
+
+        // Gas is paid in SOL, which cannot be valued without a SOL/USDT price
+        let calculator = FeeCalculator::default();
+        assert!(calculator
+            .calculate_opportunity(&prices, jup)
+            .unwrap()
+            .is_none());
+
+        let sol_usdt = Arc::new(PriceCache::new());
+        let caches = PriceCaches::from([
+            (TradingPair::SOL_USDT, Arc::clone(&sol_usdt)),
+            (jup, Arc::new(PriceCache::new())),
+        ]);
+        let calculator = calculator.with_sol_price_caches(&caches);
+        assert_eq!(calculator.sol_price(jup, 0.90), None);
+        sol_usdt.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            200.0,
+        ));
+        assert_eq!(calculator.sol_price(jup, 0.90), Some(200.0));
+        assert_eq!(
+            calculator.sol_price(TradingPair::SOL_USDT, 190.0),
+            Some(190.0)
+        );
+
+        // 0.001 SOL of gas at 200 USDT, not at the JUP price
+        let opp = calculator
+            .calculate_opportunity(&prices, jup)
+            .unwrap()
+            .unwrap();
+        let per_unit_fees = 0.90 * 0.0025 + 1.00 * 0.001;
+        let gas = 0.001 * 200.0;
+        assert!(
+            (opp.total_fees_per_unit - (per_unit_fees + gas / opp.recommended_amount)).abs() < 1e-9
+        );
+    }
+
     #[test]
     fn test_trading_fee_getter() {
         let fees = TradingFees::default();
@@ -507,4 +909,345 @@ mod tests {
 
         assert!(calculator.set_default_trade_amount(-5.0).is_err());
     }
+
+    #[test]
+    fn test_recommended_amount_capped_by_dex_depth() {
+        use crate::price::{ConcentratedLiquidity, LiquidityProfile};
+
+        let calculator = FeeCalculator::default().with_max_ticks_crossed(1);
+        let mut prices = create_test_price_pair();
+
+        // Thin pool: SOL is token0, liquidity ends at the next tick above the price
+        prices.solana_price.liquidity =
+            Some(LiquidityProfile::Concentrated(ConcentratedLiquidity {
+                sqrt_price: 1.0,
+                liquidity: 1_000_000_000_000,
+                tick_current: 0,
+                base_is_token0: true,
+                base_decimals: 9,
+                ticks: vec![(10, -1_000_000_000_000)],
+                covered_range: (-600, 600),
+            }));
+
+        let opportunity = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+
+        let capacity = opportunity.dex_capacity.unwrap();
+        assert!(capacity > 0.0 && capacity < 10.0);
+        assert_eq!(opportunity.recommended_amount, capacity);
+    }
+
+    #[test]
+    fn test_order_book_trades_at_bid_and_ask() {
+        use crate::price::Quote;
+
+        let calculator = FeeCalculator::default();
+        let book = |bid: f64, ask: f64| {
+            SourcePrice::new((bid + ask) / 2.0, PriceSource::OpenBook)
+                .with_quote(Quote::new(bid, ask).with_sizes(2.0, 4.0))
+        };
+
+        // Buy on the book at the ask, capped by the ask size
+        let prices = ValidatedPricePair::new(
+            book(189.0, 190.0),
+            SourcePrice::new(195.0, PriceSource::Binance),
+        );
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.buy_source, PriceSource::OpenBook);
+        assert_eq!(opp.buy_price, 190.0);
+        assert_eq!(opp.recommended_amount, 4.0);
+
+        // Sell on the book at the bid
+        let prices = ValidatedPricePair::new(
+            book(200.0, 201.0),
+            SourcePrice::new(195.0, PriceSource::Binance),
+        );
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.sell_source, PriceSource::OpenBook);
+        assert_eq!(opp.sell_price, 200.0);
+        assert_eq!(opp.recommended_amount, 2.0);
+
+        // Mid below Binance but the spread eats the edge: no raw profit
+        let prices = ValidatedPricePair::new(
+            book(194.0, 196.0),
+            SourcePrice::new(195.0, PriceSource::Binance),
+        );
+        assert!(calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDC)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_binance_leg_uses_bid_and_ask() {
+        use crate::price::Quote;
+
+        let calculator = FeeCalculator::default();
+        let binance = SourcePrice::new(195.0, PriceSource::Binance)
+            .with_quote(Quote::new(194.0, 196.0).with_sizes(3.0, 5.0));
+
+        // Buying on the DEX sells into the Binance bid, capped by the bid size
+        let prices = ValidatedPricePair::new(
+            SourcePrice::new(190.0, PriceSource::Solana),
+            binance.clone(),
+        );
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.sell_source, PriceSource::Binance);
+        assert_eq!(opp.sell_price, 194.0);
+        assert_eq!(opp.recommended_amount, 3.0);
+        assert!(opp.dex_capacity.is_none());
+
+        // Selling on the DEX buys at the Binance ask
+        let prices =
+            ValidatedPricePair::new(SourcePrice::new(200.0, PriceSource::Orca), binance.clone());
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.buy_source, PriceSource::Binance);
+        assert_eq!(opp.buy_price, 196.0);
+        assert_eq!(opp.recommended_amount, 5.0);
+
+        // DEX price inside the Binance spread: no side is profitable
+        let prices = ValidatedPricePair::new(SourcePrice::new(195.5, PriceSource::Solana), binance);
+        assert!(calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_binance_leg_priced_for_trade_size() {
+        use crate::price::{BookDepth, LiquidityProfile, Quote};
+
+        // Top of book looks profitable, but 10 SOL walks the bids down
+        let mut binance = SourcePrice::new(195.0, PriceSource::Binance)
+            .with_quote(Quote::new(195.0, 195.2).with_sizes(2.0, 2.0));
+        binance.liquidity = Some(LiquidityProfile::OrderBook(BookDepth {
+            bids: vec![(195.0, 2.0), (194.0, 8.0), (193.0, 50.0)],
+            asks: vec![(195.2, 2.0)],
+        }));
+
+        // Only 10 SOL on hand to sell
+        let sizing = SizingLimits::new(0.0, None, Some(10.0), None, None).unwrap();
+        let calculator = FeeCalculator::default().with_sizing_limits(sizing);
+        let prices = ValidatedPricePair::new(SourcePrice::new(190.0, PriceSource::Solana), binance);
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+
+        // 2 @ 195 + 8 @ 194 for the 10 SOL cap
+        assert!((opp.sell_price - 194.2).abs() < 1e-9);
+        assert_eq!(opp.recommended_amount, 10.0);
+    }
+
+    #[test]
+    fn test_amm_price_impact_erases_spread() {
+        use crate::price::{ConstantProductPool, LiquidityProfile};
+
+        // 190 spot on a shallow pool: 100 SOL against 19,000 USDT
+        let pool = |base_reserve: f64| {
+            let mut price = SourcePrice::new(190.0, PriceSource::Solana);
+            price.liquidity = Some(LiquidityProfile::ConstantProduct(ConstantProductPool {
+                base_reserve,
+                quote_reserve: base_reserve * 190.0,
+                fee_rate: 0.0025,
+            }));
+            price
+        };
+        let binance = SourcePrice::new(195.0, PriceSource::Binance);
+
+        // A 1,900 USDT minimum notional forces at least 10 SOL
+        let sizing = SizingLimits::new(1_900.0, None, None, None, None).unwrap();
+        let calculator = FeeCalculator::default().with_sizing_limits(sizing);
+
+        // Buying 10 SOL moves the pool ~11%, wiping out the 2.6% spread
+        let prices = ValidatedPricePair::new(pool(100.0), binance.clone());
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.recommended_amount, 10.0);
+        assert!((opp.buy_price - 19_000.0 / 90.0 / 0.9975).abs() < 1e-9);
+        assert!((opp.dex_price_impact.unwrap() - (100.0 / 90.0 - 1.0) * 100.0).abs() < 1e-9);
+        assert!(!opp.is_profitable());
+        assert!(opp.estimated_total_profit < 0.0);
+
+        // A deep pool keeps most of the spread; the pool fee is not charged twice
+        let prices = ValidatedPricePair::new(pool(1_000_000.0), binance);
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        assert!(opp.is_profitable());
+        let binance_fee = opp.sell_price * 0.001;
+        // Gas is valued at the Binance SOL price
+        let gas_per_unit = 0.001 * opp.sell_price / opp.recommended_amount;
+        assert!((opp.total_fees_per_unit - binance_fee - gas_per_unit).abs() < 1e-9);
+    }
+
+    fn shallow_pool() -> SourcePrice {
+        use crate::price::{ConstantProductPool, LiquidityProfile};
+
+        let mut price = SourcePrice::new(190.0, PriceSource::Solana);
+        price.liquidity = Some(LiquidityProfile::ConstantProduct(ConstantProductPool {
+            base_reserve: 100.0,
+            quote_reserve: 19_000.0,
+            fee_rate: 0.0025,
+        }));
+        price
+    }
+
+    #[test]
+    fn test_recommended_amount_maximizes_profit() {
+        let calculator = FeeCalculator::default();
+        let prices = ValidatedPricePair::new(
+            shallow_pool(),
+            SourcePrice::new(195.0, PriceSource::Binance),
+        );
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+
+        // Small enough that impact leaves part of the spread, large enough to cover gas
+        assert!(opp.recommended_amount > 0.1 && opp.recommended_amount < 10.0);
+        assert!(opp.is_profitable());
+        assert!(
+            (opp.estimated_total_profit - opp.net_profit_per_unit * opp.recommended_amount).abs()
+                < 1e-9
+        );
+
+        // Neighbouring sizes earn less
+        let profit_at = |amount: f64| {
+            calculator
+                .size_trade(
+                    &prices.solana_price,
+                    &prices.binance_price,
+                    TradingPair::SOL_USDT,
+                    amount,
+                )
+                .unwrap()
+                .total_profit()
+        };
+        let best = opp.estimated_total_profit;
+        assert!(profit_at(opp.recommended_amount * 0.9) < best);
+        assert!(profit_at(opp.recommended_amount * 1.1) < best);
+    }
+
+    #[test]
+    fn test_recommended_amount_respects_caps() {
+        let prices = create_test_price_pair();
+        let opportunity = |sizing: SizingLimits| {
+            FeeCalculator::default()
+                .with_sizing_limits(sizing)
+                .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+                .unwrap()
+        };
+
+        // Flat legs: profit grows with size up to the tightest cap
+        let opp = opportunity(SizingLimits::new(0.0, Some(5_000.0), None, None, None).unwrap());
+        assert!((opp.unwrap().recommended_amount - 5_000.0 / 190.0).abs() < 1e-9);
+
+        let opp =
+            opportunity(SizingLimits::new(0.0, Some(5_000.0), Some(3.0), None, None).unwrap());
+        assert_eq!(opp.unwrap().recommended_amount, 3.0);
+
+        let opp = opportunity(SizingLimits::new(0.0, None, None, Some(380.0), None).unwrap());
+        assert_eq!(opp.unwrap().recommended_amount, 2.0);
+
+        // Minimum notional above what the inventory allows: nothing to trade
+        let opp = opportunity(SizingLimits::new(1_000.0, None, Some(3.0), None, None).unwrap());
+        assert!(opp.is_none());
+    }
+
+    #[test]
+    fn test_inventory_limits_size_and_adds_rebalancing_cost() {
+        use crate::arbitrage::inventory::{Balance, Inventory, RebalancingCosts, VenueBalances};
+
+        let prices = create_test_price_pair();
+        let calculator = |balances: VenueBalances| {
+            let inventory = Inventory::new(balances, Some(RebalancingCosts::default()));
+            FeeCalculator::default().with_inventory(SharedInventory::new(inventory))
+        };
+        let balances = VenueBalances {
+            binance: Balance::from([("SOL", 3.0)]),
+            solana: Balance::from([("USDT", 10_000.0)]),
+        };
+
+        // 3 SOL to sell on Binance
+        let opp = calculator(balances.clone())
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        assert_eq!(opp.recommended_amount, 3.0);
+        let without = FeeCalculator::default()
+            .with_sizing_limits(SizingLimits::new(0.0, None, Some(3.0), None, None).unwrap())
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+        // SOL goes back from the Solana wallet, stablecoin back from Binance
+        let rebalancing = 0.000005 * 195.0 + 1.0;
+        assert!(
+            (without.estimated_total_profit - opp.estimated_total_profit - rebalancing).abs()
+                < 1e-9
+        );
+
+        // 380 of stablecoin on the Solana wallet buys at most 2 SOL with fees
+        let opp = calculator(VenueBalances {
+            solana: Balance::from([("USDT", 380.0)]),
+            ..balances.clone()
+        })
+        .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+        .unwrap()
+        .unwrap();
+        assert!(opp.recommended_amount < 2.0 && opp.recommended_amount > 1.9);
+
+        // Nothing to sell on Binance: not executable
+        let opp = calculator(VenueBalances {
+            binance: Balance::default(),
+            ..balances
+        })
+        .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+        .unwrap();
+        assert!(opp.is_none());
+    }
+
+    #[test]
+    fn test_linear_cex_impact_model() {
+        // 100 bps per SOL on Binance against a flat DEX price
+        let sizing = SizingLimits::new(0.0, None, None, None, Some(100.0)).unwrap();
+        let calculator = FeeCalculator::default().with_sizing_limits(sizing);
+        let prices = create_test_price_pair();
+        let opp = calculator
+            .calculate_opportunity(&prices, TradingPair::SOL_USDT)
+            .unwrap()
+            .unwrap();
+
+        // Sell price falls 50 bps per SOL on average
+        let expected = 195.0 * (1.0 - 0.005 * opp.recommended_amount);
+        assert!((opp.sell_price - expected).abs() < 1e-9);
+        assert!(opp.recommended_amount < 10.0);
+        assert!(opp.is_profitable());
+    }
+
+    #[test]
+    fn test_sizing_limits_validation() {
+        assert!(SizingLimits::new(100.0, Some(50.0), None, None, None).is_err());
+        assert!(SizingLimits::new(-1.0, None, None, None, None).is_err());
+        assert!(SizingLimits::new(0.0, None, Some(f64::NAN), None, None).is_err());
+        assert!(SizingLimits::new(10.0, Some(10.0), Some(1.0), Some(1.0), Some(2.0)).is_ok());
+    }
 }
@@ -1,12 +1,15 @@
 use crate::arbitrage::calculator::{ArbitrageOpportunity, CalculatorError, FeeCalculator};
-use crate::config::{Config, ProfitThreshold, TradingPair};
+use crate::config::reload::LiveSettings;
+use crate::config::{Config, PriceBounds, ProfitThreshold, TradingPair};
 use crate::performance::metrics::MetricsCollector;
-use crate::price::{PriceCache, PriceProcessor, ProcessorError, ValidatedPricePair};
-use log::error;
+use crate::price::{
+    CacheEvent, Clock, PriceCache, PriceProcessor, ProcessorError, ValidatedPricePair,
+};
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
-use tokio::time::{interval, Instant};
+use tokio::sync::watch;
+use tokio::time::Instant;
 
 /// Errors that can occur during arbitrage detection
 #[derive(Debug, Error)]
@@ -132,8 +135,16 @@ pub struct ArbitrageDetector {
     fee_calculator: FeeCalculator,
     profit_threshold: ProfitThreshold,
     trading_pair: TradingPair,
+    default_price_bounds: PriceBounds,
     check_interval: Duration,
+    /// Quiet period that coalesces a burst of updates into one check
+    debounce: Duration,
+    updates: watch::Receiver<Option<CacheEvent>>,
+    /// First update of the burst being debounced, and when its debounce ends
+    pending: Option<(CacheEvent, Instant)>,
     stats: DetectionStats,
+    /// Receives the spread seen for each DEX on every check
+    metrics: Option<Arc<MetricsCollector>>,
     is_running: bool,
 }
 
@@ -145,23 +156,64 @@ impl ArbitrageDetector {
         config: &Config,
         fee_calculator: FeeCalculator,
     ) -> Self {
+        let updates = price_cache.subscribe();
         let price_processor = PriceProcessor::new(price_cache, config);
 
         Self {
             price_processor,
             fee_calculator,
             profit_threshold: config.threshold,
-            trading_pair: config.pair,
-            check_interval: Duration::from_millis(500), // Check twice per second
+            trading_pair: config.primary_pair(),
+            default_price_bounds: config.price_bounds,
+            check_interval: config.check_interval,
+            debounce: config.debounce,
+            updates,
+            pending: None,
             stats: DetectionStats::default(),
+            metrics: None,
             is_running: false,
         }
+        .with_trading_pair(config.primary_pair())
+    }
+
+    /// Detect opportunities for `pair` instead of the primary configured pair
+    ///
+    /// Prices are validated against the pair's own bounds when it defines them.
+    pub fn with_trading_pair(mut self, pair: TradingPair) -> Self {
+        let bounds = pair
+            .spec()
+            .price_bounds
+            .unwrap_or(self.default_price_bounds);
+        self.price_processor
+            .set_price_bounds(bounds.min_price, bounds.max_price);
+        self.trading_pair = pair;
+        self
+    }
+
+    /// Swap in reloaded threshold, fees and default price bounds between checks
+    pub fn apply_settings(&mut self, settings: &LiveSettings) {
+        self.profit_threshold = settings.threshold;
+        self.fee_calculator.set_trading_fees(settings.fees.clone());
+        self.default_price_bounds = settings.price_bounds;
+        if self.trading_pair.spec().price_bounds.is_none() {
+            self.price_processor.set_price_bounds(
+                settings.price_bounds.min_price,
+                settings.price_bounds.max_price,
+            );
+        }
     }
 
     /// Set metrics collector for performance monitoring
     #[allow(dead_code)]
     pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
-        self.price_processor = self.price_processor.with_metrics(metrics);
+        self.price_processor = self.price_processor.with_metrics(Arc::clone(&metrics));
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Judge price freshness against `clock`, e.g. simulated time in a backtest
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.price_processor = self.price_processor.with_clock(clock);
         self
     }
 
@@ -172,42 +224,101 @@ impl ArbitrageDetector {
         self
     }
 
+    /// Coalesce updates arriving within `debounce` of the first one into one check
+    #[allow(dead_code)]
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Wait for the next price update, then for the debounce period
+    ///
+    /// Returns the first update of the burst; every update up to the return counts as
+    /// seen. Cancel-safe: a call cancelled during the debounce leaves the burst pending,
+    /// and the next call returns its first update once the same debounce period ends.
+    pub async fn next_update(&mut self) -> Option<CacheEvent> {
+        if self.pending.is_none() {
+            self.updates.changed().await.ok()?;
+            let first = (*self.updates.borrow())?;
+            self.pending = Some((first, Instant::now() + self.debounce));
+        }
+        if let Some((_, deadline)) = self.pending {
+            if !self.debounce.is_zero() {
+                tokio::time::sleep_until(deadline).await;
+            }
+        }
+        self.updates.borrow_and_update();
+        self.pending.take().map(|(first, _)| first)
+    }
+
     /// Check for arbitrage opportunities once
+    ///
+    /// Binance is compared against every DEX with a fresh price; the most
+    /// profitable opportunity is returned.
     #[allow(dead_code)]
     pub async fn check_for_opportunities(
         &mut self,
     ) -> Result<Option<ArbitrageOpportunity>, DetectorError> {
-        // Get validated prices
-        let prices = self.price_processor.get_validated_prices()?;
-
-        // Update stats with this check
-        self.stats.update_check(prices.price_spread_percentage);
+        // Get validated prices for each DEX
+        let price_pairs = self.price_processor.get_validated_price_pairs()?;
+
+        // Update stats with the widest spread seen in this check
+        let widest_spread = price_pairs
+            .iter()
+            .map(|prices| prices.price_spread_percentage)
+            .fold(0.0, f64::max);
+        self.stats.update_check(widest_spread);
+        if let Some(ref metrics) = self.metrics {
+            for prices in &price_pairs {
+                metrics.set_spread(
+                    self.trading_pair,
+                    prices.solana_price.source,
+                    prices.price_spread_percentage,
+                );
+            }
+        }
 
-        // Calculate arbitrage opportunity
-        let opportunity = self
-            .fee_calculator
-            .calculate_opportunity(&prices, self.trading_pair)?;
+        // Calculate arbitrage opportunity for each DEX and keep the best one
+        let mut best: Option<ArbitrageOpportunity> = None;
+        for prices in &price_pairs {
+            let opportunity = self
+                .fee_calculator
+                .calculate_opportunity(prices, self.trading_pair)?
+                .filter(|opp| opp.is_profitable());
+
+            if let Some(opp) = opportunity {
+                if best
+                    .as_ref()
+                    .map_or(true, |b| opp.profit_percentage > b.profit_percentage)
+                {
+                    best = Some(opp);
+                }
+            }
+        }
 
-        Ok(opportunity.filter(|opp| opp.is_profitable()))
+        Ok(best)
     }
 
-    /// Wait for an arbitrage opportunity with timeout
+    /// Wait for an arbitrage opportunity with timeout, checking after each price update
     #[allow(dead_code)]
     pub async fn wait_for_opportunity(
         &mut self,
         timeout: Duration,
     ) -> Result<ArbitrageOpportunity, DetectorError> {
-        let start = Instant::now();
-        let mut check_interval = interval(Duration::from_millis(100));
-
-        while start.elapsed() < timeout {
-            check_interval.tick().await;
-
-            if let Some(opportunity) = self.check_for_opportunities().await? {
-                if opportunity.exceeds_threshold(&self.profit_threshold) {
-                    return Ok(opportunity);
+        let wait = async {
+            loop {
+                if let Some(opportunity) = self.check_for_opportunities().await? {
+                    if opportunity.exceeds_threshold(&self.profit_threshold) {
+                        return Ok(opportunity);
+                    }
+                }
+                if self.next_update().await.is_none() {
+                    return Err(DetectorError::DetectorNotRunning);
                 }
             }
+        };
+        if let Ok(result) = tokio::time::timeout(timeout, wait).await {
+            return result;
         }
 
         Err(DetectorError::DetectionTimeout(timeout))
@@ -283,8 +394,8 @@ mod tests {
         let cache = Arc::new(PriceCache::new());
 
         // Create prices with arbitrage opportunity
-        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SolUsdt, 190.0);
-        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SolUsdt, 195.0);
+        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 190.0);
+        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0);
 
         cache.update(&solana_update);
         cache.update(&binance_update);
@@ -296,8 +407,8 @@ mod tests {
         let cache = Arc::new(PriceCache::new());
 
         // Create prices with minimal spread (no profitable arbitrage)
-        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SolUsdt, 195.0);
-        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SolUsdt, 195.1);
+        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 195.0);
+        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.1);
 
         cache.update(&solana_update);
         cache.update(&binance_update);
@@ -313,7 +424,7 @@ mod tests {
 
         let detector = ArbitrageDetector::new(cache, &config, fee_calculator);
 
-        assert_eq!(detector.trading_pair(), TradingPair::SolUsdt);
+        assert_eq!(detector.trading_pair(), TradingPair::SOL_USDT);
         assert_eq!(detector.profit_threshold(), 1.0);
         assert!(!detector.is_running());
     }
@@ -357,6 +468,108 @@ mod tests {
         // Small spread might not be profitable after fees
     }
 
+    #[tokio::test]
+    async fn test_check_for_opportunities_picks_best_dex() {
+        let config = create_test_config();
+        let cache = create_test_price_cache_with_arbitrage();
+        cache.update(&PriceUpdate::new(
+            PriceSource::Orca,
+            TradingPair::SOL_USDT,
+            185.0,
+        ));
+        let fee_calculator = FeeCalculator::default();
+
+        let mut detector = ArbitrageDetector::new(cache, &config, fee_calculator);
+
+        let opportunity = detector.check_for_opportunities().await.unwrap().unwrap();
+        assert_eq!(opportunity.buy_source, PriceSource::Orca);
+        assert_eq!(opportunity.sell_source, PriceSource::Binance);
+        assert_eq!(opportunity.buy_price, 185.0);
+    }
+
+    #[tokio::test]
+    async fn test_check_for_opportunities_on_clmm_pool() {
+        use crate::price::{ConcentratedLiquidity, LiquidityProfile};
+
+        let config = create_test_config();
+        let cache = Arc::new(PriceCache::new());
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            195.0,
+        ));
+        // Thin pool: SOL is token0, liquidity ends at the next tick above the price
+        cache.update(
+            &PriceUpdate::new(PriceSource::RaydiumClmm, TradingPair::SOL_USDT, 188.0)
+                .with_liquidity(LiquidityProfile::Concentrated(ConcentratedLiquidity {
+                    sqrt_price: 1.0,
+                    liquidity: 1_000_000_000_000,
+                    tick_current: 0,
+                    base_is_token0: true,
+                    base_decimals: 9,
+                    ticks: vec![(10, -1_000_000_000_000)],
+                    covered_range: (-600, 600),
+                })),
+        );
+        let fee_calculator = FeeCalculator::default().with_max_ticks_crossed(1);
+
+        let mut detector = ArbitrageDetector::new(cache, &config, fee_calculator);
+
+        let opportunity = detector.check_for_opportunities().await.unwrap().unwrap();
+        assert_eq!(opportunity.buy_source, PriceSource::RaydiumClmm);
+        assert_eq!(opportunity.sell_source, PriceSource::Binance);
+        // Sized by the tick walk over the pool's liquidity
+        let capacity = opportunity.dex_capacity.unwrap();
+        assert!(capacity > 0.0 && capacity < 10.0);
+        assert_eq!(opportunity.recommended_amount, capacity);
+    }
+
+    #[tokio::test]
+    async fn test_check_for_opportunities_on_order_book() {
+        use crate::price::Quote;
+
+        let config = create_test_config();
+        let cache = Arc::new(PriceCache::new());
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            195.0,
+        ));
+        cache.update(
+            &PriceUpdate::new(PriceSource::OpenBook, TradingPair::SOL_USDT, 189.5)
+                .with_quote(Quote::new(189.0, 190.0).with_sizes(2.0, 4.0)),
+        );
+
+        let mut detector = ArbitrageDetector::new(cache, &config, FeeCalculator::default());
+
+        // Buys on the book at the ask, capped by the size there
+        let opportunity = detector.check_for_opportunities().await.unwrap().unwrap();
+        assert_eq!(opportunity.buy_source, PriceSource::OpenBook);
+        assert_eq!(opportunity.sell_source, PriceSource::Binance);
+        assert_eq!(opportunity.buy_price, 190.0);
+        assert_eq!(opportunity.recommended_amount, 4.0);
+    }
+
+    #[tokio::test]
+    async fn test_check_publishes_spread_per_dex() {
+        let config = create_test_config();
+        let cache = create_test_price_cache_with_arbitrage();
+        cache.update(&PriceUpdate::new(
+            PriceSource::Orca,
+            TradingPair::SOL_USDT,
+            195.0,
+        ));
+        let metrics = Arc::new(MetricsCollector::new());
+
+        let mut detector = ArbitrageDetector::new(cache, &config, FeeCalculator::default())
+            .with_metrics(Arc::clone(&metrics));
+        detector.check_for_opportunities().await.unwrap();
+
+        let spreads = metrics.get_metrics().market.spreads;
+        assert!((spreads[&("sol-usdt", "solana")] - 5.0 / 195.0 * 100.0).abs() < 1e-9);
+        assert_eq!(spreads[&("sol-usdt", "orca")], 0.0);
+    }
+
     #[tokio::test]
     async fn test_check_for_opportunities_no_data() {
         let config = create_test_config();
@@ -412,6 +625,68 @@ mod tests {
         assert!(matches!(result, Err(DetectorError::DetectionTimeout(_))));
     }
 
+    #[tokio::test]
+    async fn test_next_update_debounces_bursts() {
+        let config = create_low_threshold_test_config();
+        let cache = Arc::new(PriceCache::new());
+        let mut detector =
+            ArbitrageDetector::new(Arc::clone(&cache), &config, FeeCalculator::default())
+                .with_debounce(Duration::from_millis(50));
+
+        let first = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 190.0);
+        cache.update(&first);
+        let writer = {
+            let cache = Arc::clone(&cache);
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                cache.update(&PriceUpdate::new(
+                    PriceSource::Binance,
+                    TradingPair::SOL_USDT,
+                    195.0,
+                ));
+            })
+        };
+
+        // Woken by the first update, checked once the burst is over
+        let event = detector.next_update().await.unwrap();
+        assert_eq!(event.source, PriceSource::Solana);
+        assert_eq!(event.timestamp, first.timestamp);
+        writer.await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), detector.next_update())
+                .await
+                .is_err()
+        );
+        assert!(detector.check_for_opportunities().await.unwrap().is_some());
+
+        // A call cancelled during the debounce leaves the burst for the next one
+        let update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 191.0);
+        cache.update(&update);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), detector.next_update())
+                .await
+                .is_err()
+        );
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            195.5,
+        ));
+        let event = detector.next_update().await.unwrap();
+        assert_eq!(event.source, PriceSource::Solana);
+        assert_eq!(event.timestamp, update.timestamp);
+
+        // Without debouncing every update wakes the detector
+        let mut detector = detector.with_debounce(Duration::ZERO);
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            196.0,
+        ));
+        let event = detector.next_update().await.unwrap();
+        assert_eq!(event.source, PriceSource::Binance);
+    }
+
     #[test]
     fn test_detection_stats() {
         let mut stats = DetectionStats::default();
@@ -435,7 +710,7 @@ mod tests {
         if let Ok(prices) = detector.price_processor.get_validated_prices() {
             if let Ok(Some(opportunity)) = detector
                 .fee_calculator
-                .calculate_opportunity(&prices, TradingPair::SolUsdt)
+                .calculate_opportunity(&prices, TradingPair::SOL_USDT)
             {
                 stats.update_opportunity(&opportunity, true);
                 assert_eq!(stats.opportunities_found, 1);
@@ -477,6 +752,26 @@ mod tests {
         assert_eq!(detector.profit_threshold(), 2.5);
     }
 
+    #[tokio::test]
+    async fn test_apply_settings() {
+        let config = create_low_threshold_test_config();
+        let cache = create_test_price_cache_with_arbitrage();
+        let mut detector = ArbitrageDetector::new(cache, &config, FeeCalculator::default());
+        assert!(detector.check_for_opportunities().await.unwrap().is_some());
+
+        let mut settings = LiveSettings::from_config(&config);
+        settings.threshold = ProfitThreshold::new(0.2).unwrap();
+        settings.fees.binance_spot_fee = 0.5;
+        detector.apply_settings(&settings);
+        assert_eq!(detector.profit_threshold(), 0.2);
+        assert_eq!(detector.fee_calculator.trading_fees().binance_spot_fee, 0.5);
+
+        // Narrowed bounds reject the cached prices
+        settings.price_bounds = PriceBounds::new(1.0, 150.0).unwrap();
+        detector.apply_settings(&settings);
+        assert!(detector.check_for_opportunities().await.is_err());
+    }
+
     #[test]
     fn test_fresh_prices_check() {
         let config = create_test_config();
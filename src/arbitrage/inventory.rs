@@ -0,0 +1,467 @@
+use crate::config::TradingPair;
+use crate::price::PriceSource;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, SystemTime};
+
+/// Asset Solana transaction fees are paid in
+const SOL: &str = "SOL";
+
+/// Default starting amount of each base token on each venue for paper trading
+pub const DEFAULT_PAPER_BASE_BALANCE: f64 = 50.0;
+
+/// Default starting amount of each quote currency on each venue for paper trading
+pub const DEFAULT_PAPER_QUOTE_BALANCE: f64 = 10_000.0;
+
+/// Where a leg executes: Binance, or the Solana wallet shared by every DEX
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    Binance,
+    Solana,
+}
+
+impl Venue {
+    /// Venue that trades on `source`
+    pub fn of(source: PriceSource) -> Self {
+        if source.is_dex() {
+            Venue::Solana
+        } else {
+            Venue::Binance
+        }
+    }
+}
+
+/// Holdings on one venue by asset symbol, e.g. `SOL` or `USDC`; missing assets are 0
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Balance(BTreeMap<String, f64>);
+
+impl Balance {
+    /// Amount of `asset` held
+    pub fn get(&self, asset: &str) -> f64 {
+        self.0.get(asset).copied().unwrap_or(0.0)
+    }
+
+    /// Add `amount` of `asset`, or take it away when negative
+    pub fn add(&mut self, asset: &str, amount: f64) {
+        *self.0.entry(asset.to_string()).or_insert(0.0) += amount;
+    }
+
+    /// Assets and amounts, by symbol
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0
+            .iter()
+            .map(|(asset, &amount)| (asset.as_str(), amount))
+    }
+
+    /// Whether nothing is held
+    pub fn is_zero(&self) -> bool {
+        self.0.values().all(|&amount| amount == 0.0)
+    }
+}
+
+impl<const N: usize> From<[(&str, f64); N]> for Balance {
+    fn from(amounts: [(&str, f64); N]) -> Self {
+        Self(
+            amounts
+                .into_iter()
+                .map(|(asset, amount)| (asset.to_string(), amount))
+                .collect(),
+        )
+    }
+}
+
+/// Holdings on each venue
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VenueBalances {
+    pub binance: Balance,
+    pub solana: Balance,
+}
+
+impl VenueBalances {
+    /// Default paper trading balances: each base token and quote currency of `pairs`
+    /// on both venues
+    pub fn paper_default(pairs: impl IntoIterator<Item = TradingPair>) -> Self {
+        let mut balance = Balance::default();
+        for pair in pairs {
+            balance
+                .0
+                .insert(pair.base_symbol().to_string(), DEFAULT_PAPER_BASE_BALANCE);
+            balance
+                .0
+                .insert(pair.quote_symbol().to_string(), DEFAULT_PAPER_QUOTE_BALANCE);
+        }
+        Self {
+            binance: balance.clone(),
+            solana: balance,
+        }
+    }
+
+    pub fn get(&self, venue: Venue) -> &Balance {
+        match venue {
+            Venue::Binance => &self.binance,
+            Venue::Solana => &self.solana,
+        }
+    }
+
+    pub fn get_mut(&mut self, venue: Venue) -> &mut Balance {
+        match venue {
+            Venue::Binance => &mut self.binance,
+            Venue::Solana => &mut self.solana,
+        }
+    }
+
+    /// Whether nothing is held on either venue
+    pub fn is_zero(&self) -> bool {
+        self.binance.is_zero() && self.solana.is_zero()
+    }
+}
+
+/// Cost and time of moving funds between Binance and the Solana wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalancingCosts {
+    /// Binance withdrawal fee of each asset by symbol, in that asset
+    pub binance_withdrawal: BTreeMap<String, f64>,
+    /// Fee of a Solana transfer transaction, in SOL
+    pub solana_transfer_fee: f64,
+    /// Time until a transfer is credited on the other venue
+    pub transfer_time: Duration,
+}
+
+impl Default for RebalancingCosts {
+    fn default() -> Self {
+        Self {
+            binance_withdrawal: [(SOL, 0.008), ("USDC", 1.0), ("USDT", 1.0)]
+                .into_iter()
+                .map(|(asset, fee)| (asset.to_string(), fee))
+                .collect(),
+            solana_transfer_fee: 0.000005,
+            transfer_time: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RebalancingCosts {
+    /// Binance withdrawal fee of `asset`, in that asset; 0 when not configured
+    pub fn binance_withdrawal_fee(&self, asset: &str) -> f64 {
+        self.binance_withdrawal.get(asset).copied().unwrap_or(0.0)
+    }
+
+    /// Fee of sending `asset` out of `from`, in quote currency with the asset at
+    /// `price` and SOL at `sol_price`
+    fn withdrawal_fee(&self, from: Venue, asset: &str, price: f64, sol_price: f64) -> f64 {
+        match from {
+            Venue::Binance => self.binance_withdrawal_fee(asset) * price,
+            Venue::Solana => self.solana_transfer_fee * sol_price,
+        }
+    }
+
+    /// Cost of undoing a trade's inventory shift on `pair`, in quote currency with the
+    /// base token at `base_price` and SOL at `sol_price`
+    ///
+    /// The base bought on `buy` goes back to `sell`, and the quote received on `sell`
+    /// goes back to `buy`.
+    pub fn trade_cost(
+        &self,
+        pair: TradingPair,
+        buy: Venue,
+        sell: Venue,
+        base_price: f64,
+        sol_price: f64,
+    ) -> f64 {
+        if buy == sell {
+            return 0.0;
+        }
+        self.withdrawal_fee(buy, pair.base_symbol(), base_price, sol_price)
+            + self.withdrawal_fee(sell, pair.quote_symbol(), 1.0, sol_price)
+    }
+}
+
+/// Validated inventory settings
+#[derive(Debug, Clone)]
+pub struct InventoryConfig {
+    /// Starting balances
+    pub balances: VenueBalances,
+    pub rebalancing: RebalancingCosts,
+}
+
+/// Funds on their way to a venue
+#[derive(Debug, Clone)]
+struct Transfer {
+    to: Venue,
+    asset: &'static str,
+    amount: f64,
+    arrives_at: SystemTime,
+}
+
+/// Balances on each venue, with rebalancing transfers in flight
+#[derive(Debug)]
+pub struct Inventory {
+    balances: VenueBalances,
+    in_transit: Vec<Transfer>,
+    rebalancing: Option<RebalancingCosts>,
+}
+
+/// Inventory shared by the fee calculators and the paper trader
+#[derive(Debug, Clone)]
+pub struct SharedInventory(Arc<RwLock<Inventory>>);
+
+impl SharedInventory {
+    pub fn new(inventory: Inventory) -> Self {
+        Self(Arc::new(RwLock::new(inventory)))
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, Inventory> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Inventory> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Inventory {
+    /// Inventory starting at `balances`; trades are rebalanced when costs are given
+    pub fn new(balances: VenueBalances, rebalancing: Option<RebalancingCosts>) -> Self {
+        Self {
+            balances,
+            in_transit: Vec::new(),
+            rebalancing,
+        }
+    }
+
+    pub fn from_config(config: &InventoryConfig) -> Self {
+        Self::new(config.balances.clone(), Some(config.rebalancing.clone()))
+    }
+
+    /// Balances available for trading now
+    pub fn balances(&self) -> &VenueBalances {
+        &self.balances
+    }
+
+    pub fn balances_mut(&mut self) -> &mut VenueBalances {
+        &mut self.balances
+    }
+
+    pub fn rebalancing(&self) -> Option<&RebalancingCosts> {
+        self.rebalancing.as_ref()
+    }
+
+    /// Funds sent but not yet credited, by destination
+    pub fn in_transit(&self) -> VenueBalances {
+        let mut total = VenueBalances::default();
+        for transfer in &self.in_transit {
+            total
+                .get_mut(transfer.to)
+                .add(transfer.asset, transfer.amount);
+        }
+        total
+    }
+
+    /// Credit the transfers that have arrived by `now`
+    pub fn settle(&mut self, now: SystemTime) {
+        let balances = &mut self.balances;
+        self.in_transit.retain(|transfer| {
+            if transfer.arrives_at > now {
+                return true;
+            }
+            balances
+                .get_mut(transfer.to)
+                .add(transfer.asset, transfer.amount);
+            false
+        });
+    }
+
+    /// Send the `base` tokens of `pair` bought on `buy` to `sell`, and the `quote`
+    /// received on `sell` to `buy`
+    ///
+    /// Binance withdrawal fees come out of the amounts sent, transfers out of the Solana
+    /// wallet pay their fee from its SOL, and funds arrive after the transfer time.
+    /// Returns the cost in quote currency, valuing the base token at `base_price` and
+    /// SOL at `sol_price`; nothing is moved without rebalancing costs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rebalance(
+        &mut self,
+        pair: TradingPair,
+        buy: Venue,
+        sell: Venue,
+        base: f64,
+        quote: f64,
+        base_price: f64,
+        sol_price: f64,
+        now: SystemTime,
+    ) -> f64 {
+        let Some(costs) = self.rebalancing.as_ref() else {
+            return 0.0;
+        };
+        if buy == sell {
+            return 0.0;
+        }
+        let arrives_at = now + costs.transfer_time;
+        let (base_asset, quote_asset) = (pair.base_symbol(), pair.quote_symbol());
+        let base = base.min(self.balances.get(buy).get(base_asset)).max(0.0);
+        let quote = quote.min(self.balances.get(sell).get(quote_asset)).max(0.0);
+
+        self.balances.get_mut(buy).add(base_asset, -base);
+        let base_sent = match buy {
+            Venue::Binance => (base - costs.binance_withdrawal_fee(base_asset)).max(0.0),
+            Venue::Solana => {
+                self.balances.solana.add(SOL, -costs.solana_transfer_fee);
+                base
+            }
+        };
+        self.in_transit.push(Transfer {
+            to: sell,
+            asset: base_asset,
+            amount: base_sent,
+            arrives_at,
+        });
+
+        self.balances.get_mut(sell).add(quote_asset, -quote);
+        let quote_sent = match sell {
+            Venue::Binance => (quote - costs.binance_withdrawal_fee(quote_asset)).max(0.0),
+            Venue::Solana => {
+                self.balances.solana.add(SOL, -costs.solana_transfer_fee);
+                quote
+            }
+        };
+        self.in_transit.push(Transfer {
+            to: buy,
+            asset: quote_asset,
+            amount: quote_sent,
+            arrives_at,
+        });
+
+        costs.trade_cost(pair, buy, sell, base_price, sol_price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebalancing_costs() {
+        let costs = RebalancingCosts::default();
+        let pair = TradingPair::SOL_USDC;
+        // SOL leaves Binance, stablecoin leaves the Solana wallet
+        let cost = costs.trade_cost(pair, Venue::Binance, Venue::Solana, 200.0, 200.0);
+        assert!((cost - (0.008 * 200.0 + 0.000005 * 200.0)).abs() < 1e-12);
+        // SOL leaves the Solana wallet, stablecoin leaves Binance
+        let cost = costs.trade_cost(pair, Venue::Solana, Venue::Binance, 200.0, 200.0);
+        assert!((cost - (0.000005 * 200.0 + 1.0)).abs() < 1e-12);
+        assert_eq!(
+            costs.trade_cost(pair, Venue::Solana, Venue::Solana, 200.0, 200.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_transfers_arrive_after_transfer_time() {
+        let pair = TradingPair::SOL_USDT;
+        let mut inventory = Inventory::new(
+            VenueBalances::paper_default([pair]),
+            Some(RebalancingCosts::default()),
+        );
+        let now = SystemTime::now();
+
+        // After buying 10 SOL on Solana for 1900 and selling them on Binance for 1950
+        let balances = inventory.balances_mut();
+        balances.solana.add("SOL", 10.0);
+        balances.solana.add("USDT", -1900.0);
+        balances.binance.add("SOL", -10.0);
+        balances.binance.add("USDT", 1950.0);
+
+        let cost = inventory.rebalance(
+            pair,
+            Venue::Solana,
+            Venue::Binance,
+            10.0,
+            1950.0,
+            195.0,
+            195.0,
+            now,
+        );
+        assert!((cost - (0.000005 * 195.0 + 1.0)).abs() < 1e-12);
+        // The Solana transfer fee is paid from the wallet's SOL
+        assert_eq!(inventory.balances().solana.get("SOL"), 50.0 - 0.000005);
+        assert_eq!(inventory.balances().binance.get("USDT"), 10_000.0);
+        assert_eq!(inventory.in_transit().binance.get("SOL"), 10.0);
+        assert_eq!(inventory.in_transit().solana.get("USDT"), 1949.0);
+
+        inventory.settle(now + Duration::from_secs(59));
+        assert_eq!(inventory.balances().binance.get("SOL"), 40.0);
+        inventory.settle(now + Duration::from_secs(60));
+        assert_eq!(inventory.balances().binance.get("SOL"), 50.0);
+        assert_eq!(inventory.balances().solana.get("USDT"), 8100.0 + 1949.0);
+        assert!(inventory.in_transit().is_zero());
+
+        // Without rebalancing costs nothing moves
+        let mut inventory = Inventory::new(VenueBalances::paper_default([pair]), None);
+        assert_eq!(
+            inventory.rebalance(
+                pair,
+                Venue::Solana,
+                Venue::Binance,
+                10.0,
+                1950.0,
+                195.0,
+                195.0,
+                now
+            ),
+            0.0
+        );
+        assert!(inventory.in_transit().is_zero());
+    }
+
+    #[test]
+    fn test_balances_are_kept_per_asset() {
+        use crate::pair::{PairSpec, USDT_MINT};
+
+        let spec: PairSpec = format!(
+            "name=jup-usdt-inventory-test,base=jup,quote=usdt,base_mint={},quote_mint={},base_decimals=6,quote_decimals=6,whirlpool={}",
+            "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+            USDT_MINT,
+            "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"
+        )
+        .parse()
+        .unwrap();
+        let jup = TradingPair::define(spec).unwrap();
+        let balances = VenueBalances::paper_default([TradingPair::SOL_USDT, jup]);
+        assert_eq!(balances.binance.get("JUP"), DEFAULT_PAPER_BASE_BALANCE);
+        assert_eq!(balances.solana.get("USDT"), DEFAULT_PAPER_QUOTE_BALANCE);
+
+        // JUP bought on Binance goes to the Solana wallet without touching its SOL
+        let mut costs = RebalancingCosts::default();
+        costs.binance_withdrawal.insert("JUP".to_string(), 2.5);
+        let mut inventory = Inventory::new(
+            VenueBalances {
+                binance: Balance::from([("JUP", 100.0)]),
+                solana: Balance::from([("SOL", 1.0), ("USDT", 500.0)]),
+            },
+            Some(costs),
+        );
+        let now = SystemTime::now();
+        let cost = inventory.rebalance(
+            jup,
+            Venue::Binance,
+            Venue::Solana,
+            100.0,
+            90.0,
+            0.9,
+            200.0,
+            now,
+        );
+        // 2.5 JUP to withdraw JUP from Binance, 0.000005 SOL for the stablecoin transfer
+        assert!((cost - (2.5 * 0.9 + 0.000005 * 200.0)).abs() < 1e-12);
+        assert_eq!(inventory.balances().binance.get("JUP"), 0.0);
+        assert_eq!(inventory.balances().solana.get("SOL"), 1.0 - 0.000005);
+        assert_eq!(inventory.balances().solana.get("USDT"), 410.0);
+        let in_transit = inventory.in_transit();
+        assert_eq!(in_transit.solana.get("JUP"), 97.5);
+        assert_eq!(in_transit.binance.get("USDT"), 90.0);
+        assert_eq!(in_transit.binance.get("SOL"), 0.0);
+    }
+}
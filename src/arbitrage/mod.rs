@@ -1,2 +1,5 @@
 pub mod calculator;
 pub mod detector;
+pub mod inventory;
+pub mod paper;
+pub mod sizing;
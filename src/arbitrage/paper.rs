@@ -0,0 +1,523 @@
+use super::calculator::{ArbitrageOpportunity, FeeCalculator, LegFill};
+use super::inventory::{Inventory, SharedInventory, Venue, VenueBalances};
+use crate::config::reload::LiveSettings;
+use crate::config::TradingPair;
+use crate::price::{PriceSource, SourcePrice, TradeSide};
+use crate::websocket::PriceCaches;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// Validated paper trading settings
+#[derive(Debug, Clone, Copy)]
+pub struct PaperConfig {
+    /// Delay between detecting an opportunity and executing it
+    pub latency: Duration,
+}
+
+/// Outcome of a simulated trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillStatus {
+    /// Both legs filled the full amount
+    Filled,
+    /// A leg filled less than the amount, leaving unhedged inventory
+    PartiallyFilled,
+    /// Nothing was traded
+    Missed,
+}
+
+/// A simulated execution of a detected opportunity
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperTrade {
+    pub trading_pair: TradingPair,
+    pub buy_source: PriceSource,
+    pub sell_source: PriceSource,
+    pub status: FillStatus,
+    /// Why nothing was traded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+    /// Base tokens the opportunity recommended
+    pub requested_amount: f64,
+    /// Base tokens sent to both legs after balance limits
+    pub order_amount: f64,
+    pub buy: Option<LegFill>,
+    pub sell: Option<LegFill>,
+    /// Trading and per-trade fees in quote currency
+    pub fees: f64,
+    /// `estimated_total_profit` of the opportunity
+    pub predicted_pnl: f64,
+    /// Quote received less quote paid, fees and rebalancing costs, with unhedged base
+    /// marked at the CEX price
+    pub realized_pnl: f64,
+}
+
+/// Totals over the simulated trades
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperSummary {
+    pub filled: u64,
+    pub partially_filled: u64,
+    pub missed: u64,
+    /// Predicted PnL of the trades that executed
+    pub predicted_pnl: f64,
+    pub realized_pnl: f64,
+    /// Balances available for trading
+    pub balances: VenueBalances,
+    /// Rebalancing transfers not yet credited, by destination
+    pub in_transit: VenueBalances,
+}
+
+impl PaperSummary {
+    /// Realized PnL as a percentage of predicted PnL
+    pub fn realized_percentage(&self) -> Option<f64> {
+        (self.predicted_pnl != 0.0).then(|| self.realized_pnl / self.predicted_pnl * 100.0)
+    }
+}
+
+struct PendingOrder {
+    opportunity: ArbitrageOpportunity,
+    execute_at: SystemTime,
+}
+
+/// Simulates executing detected opportunities against the price caches
+///
+/// Orders execute once the configured latency has passed, at the prices and depth in
+/// the caches at that moment, limited by the balances on each venue. Trades draw on
+/// the fee calculator's inventory, and are rebalanced when it has rebalancing costs;
+/// without one, the trader keeps its own default balances.
+pub struct PaperTrader {
+    config: PaperConfig,
+    fee_calculator: FeeCalculator,
+    caches: PriceCaches,
+    inventory: SharedInventory,
+    pending: VecDeque<PendingOrder>,
+    /// Timestamp of the DEX price each pair last traded against
+    traded: HashMap<TradingPair, SystemTime>,
+    summary: PaperSummary,
+}
+
+impl PaperTrader {
+    pub fn new(config: PaperConfig, fee_calculator: FeeCalculator, caches: PriceCaches) -> Self {
+        let inventory = fee_calculator.inventory().cloned().unwrap_or_else(|| {
+            SharedInventory::new(Inventory::new(
+                VenueBalances::paper_default(caches.keys().copied()),
+                None,
+            ))
+        });
+        let balances = inventory.read().balances().clone();
+        Self {
+            config,
+            fee_calculator,
+            caches,
+            inventory,
+            pending: VecDeque::new(),
+            traded: HashMap::new(),
+            summary: PaperSummary {
+                filled: 0,
+                partially_filled: 0,
+                missed: 0,
+                predicted_pnl: 0.0,
+                realized_pnl: 0.0,
+                balances,
+                in_transit: VenueBalances::default(),
+            },
+        }
+    }
+
+    /// Use reloaded fees for later executions
+    pub fn apply_settings(&mut self, settings: &LiveSettings) {
+        self.fee_calculator.set_trading_fees(settings.fees.clone());
+    }
+
+    /// Queue an opportunity detected at `now` for execution after the latency
+    ///
+    /// Ignored while an order for the same pair is pending, and until the DEX price
+    /// changes after a trade, since the cached pool does not reflect our own trade.
+    pub fn submit(&mut self, opportunity: ArbitrageOpportunity, now: SystemTime) -> bool {
+        let pair = opportunity.trading_pair;
+        if self
+            .pending
+            .iter()
+            .any(|order| order.opportunity.trading_pair == pair)
+        {
+            return false;
+        }
+        let dex_timestamp = self.dex_leg(&opportunity).map(|price| price.timestamp);
+        if dex_timestamp.is_some() && dex_timestamp == self.traded.get(&pair).copied() {
+            return false;
+        }
+
+        self.pending.push_back(PendingOrder {
+            opportunity,
+            execute_at: now + self.config.latency,
+        });
+        true
+    }
+
+    /// When the next pending order is due
+    pub fn next_due(&self) -> Option<SystemTime> {
+        self.pending.front().map(|order| order.execute_at)
+    }
+
+    /// Credit arrived transfers and execute the orders due at `now`
+    pub fn execute_due(&mut self, now: SystemTime) -> Vec<PaperTrade> {
+        self.inventory.write().settle(now);
+        let mut trades = Vec::new();
+        while let Some(order) = self.pending.pop_front() {
+            if order.execute_at > now {
+                self.pending.push_front(order);
+                break;
+            }
+            trades.push(self.execute(&order.opportunity, order.execute_at));
+        }
+        trades
+    }
+
+    /// Totals so far, with the current balances
+    pub fn summary(&self) -> PaperSummary {
+        let inventory = self.inventory.read();
+        PaperSummary {
+            balances: inventory.balances().clone(),
+            in_transit: inventory.in_transit(),
+            ..self.summary.clone()
+        }
+    }
+
+    fn dex_leg(&self, opportunity: &ArbitrageOpportunity) -> Option<SourcePrice> {
+        let source = if opportunity.buy_source.is_dex() {
+            opportunity.buy_source
+        } else {
+            opportunity.sell_source
+        };
+        self.caches
+            .get(&opportunity.trading_pair)?
+            .get_price(source)
+            .map(|price| SourcePrice::clone(&price))
+    }
+
+    fn execute(&mut self, opportunity: &ArbitrageOpportunity, now: SystemTime) -> PaperTrade {
+        let mut trade = PaperTrade {
+            trading_pair: opportunity.trading_pair,
+            buy_source: opportunity.buy_source,
+            sell_source: opportunity.sell_source,
+            status: FillStatus::Missed,
+            reason: None,
+            requested_amount: opportunity.recommended_amount,
+            order_amount: 0.0,
+            buy: None,
+            sell: None,
+            fees: 0.0,
+            predicted_pnl: opportunity.estimated_total_profit,
+            realized_pnl: 0.0,
+        };
+
+        let snapshot = self
+            .caches
+            .get(&opportunity.trading_pair)
+            .map(|cache| cache.snapshot());
+        let legs = snapshot.as_ref().and_then(|snapshot| {
+            Some((
+                snapshot.get(opportunity.buy_source)?,
+                snapshot.get(opportunity.sell_source)?,
+            ))
+        });
+        let Some((buy_leg, sell_leg)) = legs else {
+            return self.miss(trade, "no prices");
+        };
+        // Inventory and fees paid in SOL are valued at the Binance price
+        let mark = if buy_leg.source.is_cex() {
+            buy_leg.price
+        } else {
+            sell_leg.price
+        };
+        let Some(sol_price) = self
+            .fee_calculator
+            .sol_price(opportunity.trading_pair, mark)
+        else {
+            return self.miss(trade, "no SOL price");
+        };
+        if let Some(dex_leg) = self.dex_leg(opportunity) {
+            self.traded
+                .insert(opportunity.trading_pair, dex_leg.timestamp);
+        }
+
+        // Send no more than the sell venue holds and the buy venue can pay for
+        let buy_venue = Venue::of(opportunity.buy_source);
+        let sell_venue = Venue::of(opportunity.sell_source);
+        let pair = opportunity.trading_pair;
+        let (base_asset, quote_asset) = (pair.base_symbol(), pair.quote_symbol());
+        let mut inventory = self.inventory.write();
+        let mut amount = opportunity
+            .recommended_amount
+            .min(inventory.balances().get(sell_venue).get(base_asset));
+        if let Some(fill) = self
+            .fee_calculator
+            .fill_leg(buy_leg, TradeSide::BuyBase, amount)
+        {
+            let cost = fill.price * fill.amount + fill.fee;
+            let quote = inventory.balances().get(buy_venue).get(quote_asset);
+            if cost > quote {
+                amount = fill.amount * quote.max(0.0) / cost;
+            }
+        }
+        if amount <= 0.0 {
+            drop(inventory);
+            return self.miss(trade, "insufficient balance");
+        }
+        trade.order_amount = amount;
+
+        let buy = self
+            .fee_calculator
+            .fill_leg(buy_leg, TradeSide::BuyBase, amount);
+        let sell = self
+            .fee_calculator
+            .fill_leg(sell_leg, TradeSide::SellBase, amount);
+        if buy.is_none() && sell.is_none() {
+            drop(inventory);
+            return self.miss(trade, "no liquidity");
+        }
+
+        let balances = inventory.balances_mut();
+        let mut realized = 0.0;
+        let mut unhedged = 0.0;
+        let mut proceeds = 0.0;
+        if let Some(fill) = buy {
+            let balance = balances.get_mut(buy_venue);
+            balance.add(base_asset, fill.amount);
+            balance.add(quote_asset, -(fill.price * fill.amount + fill.fee));
+            realized -= fill.price * fill.amount;
+            unhedged += fill.amount;
+            trade.fees += fill.fee;
+        }
+        if let Some(fill) = sell {
+            let balance = balances.get_mut(sell_venue);
+            balance.add(base_asset, -fill.amount);
+            proceeds = fill.price * fill.amount - fill.fee;
+            balance.add(quote_asset, proceeds);
+            realized += fill.price * fill.amount;
+            unhedged -= fill.amount;
+            trade.fees += fill.fee;
+        }
+
+        // Gas and transfer costs are paid from the Solana wallet, in quote terms
+        let execution_fees = self.fee_calculator.execution_fees(
+            opportunity.buy_source,
+            opportunity.sell_source,
+            sol_price,
+        );
+        balances.solana.add(quote_asset, -execution_fees);
+        trade.fees += execution_fees;
+
+        // Move the bought base and the proceeds back to where they came from
+        trade.fees += inventory.rebalance(
+            pair,
+            buy_venue,
+            sell_venue,
+            buy.map_or(0.0, |fill| fill.amount),
+            proceeds,
+            mark,
+            sol_price,
+            now,
+        );
+        drop(inventory);
+
+        trade.realized_pnl = realized - trade.fees + unhedged * mark;
+        let complete = |fill: Option<LegFill>| fill.is_some_and(|f| f.amount >= amount);
+        trade.status = if complete(buy) && complete(sell) {
+            self.summary.filled += 1;
+            FillStatus::Filled
+        } else {
+            self.summary.partially_filled += 1;
+            FillStatus::PartiallyFilled
+        };
+        trade.buy = buy;
+        trade.sell = sell;
+        self.summary.predicted_pnl += trade.predicted_pnl;
+        self.summary.realized_pnl += trade.realized_pnl;
+        trade
+    }
+
+    fn miss(&mut self, mut trade: PaperTrade, reason: &'static str) -> PaperTrade {
+        self.summary.missed += 1;
+        trade.reason = Some(reason);
+        trade
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::inventory::Balance;
+    use crate::price::{BookDepth, LiquidityProfile, PriceCache, PriceUpdate, Quote};
+    use std::sync::Arc;
+
+    fn opportunity(amount: f64, predicted: f64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            buy_source: PriceSource::Solana,
+            sell_source: PriceSource::Binance,
+            buy_price: 190.0,
+            sell_price: 195.0,
+            raw_profit_per_unit: 5.0,
+            net_profit_per_unit: 4.0,
+            profit_percentage: 2.1,
+            total_fees_per_unit: 1.0,
+            trading_pair: TradingPair::SOL_USDT,
+            recommended_amount: amount,
+            estimated_total_profit: predicted,
+            dex_capacity: None,
+            dex_price_impact: None,
+        }
+    }
+
+    fn paper_trader(balances: VenueBalances) -> (PaperTrader, Arc<PriceCache>) {
+        let cache = Arc::new(PriceCache::new());
+        let caches = PriceCaches::from([(TradingPair::SOL_USDT, Arc::clone(&cache))]);
+        let config = PaperConfig {
+            latency: Duration::from_millis(200),
+        };
+        let inventory = SharedInventory::new(Inventory::new(balances, None));
+        let fee_calculator = FeeCalculator::default().with_inventory(inventory);
+        (PaperTrader::new(config, fee_calculator, caches), cache)
+    }
+
+    fn set_prices(cache: &PriceCache, dex_price: f64, asks: Vec<(f64, f64)>) {
+        let pair = TradingPair::SOL_USDT;
+        let liquidity = LiquidityProfile::OrderBook(BookDepth {
+            bids: vec![(dex_price - 0.5, 100.0)],
+            asks,
+        });
+        cache.update(
+            &PriceUpdate::new(PriceSource::Solana, pair, dex_price).with_liquidity(liquidity),
+        );
+        cache.update(
+            &PriceUpdate::new(PriceSource::Binance, pair, 195.0)
+                .with_quote(Quote::new(195.0, 195.1)),
+        );
+    }
+
+    #[test]
+    fn test_executes_after_latency_at_current_prices() {
+        let (mut trader, cache) =
+            paper_trader(VenueBalances::paper_default([TradingPair::SOL_USDT]));
+        set_prices(&cache, 190.0, vec![(190.0, 100.0)]);
+        let now = SystemTime::now();
+
+        assert!(trader.submit(opportunity(10.0, 38.0), now));
+        // One order per pair in flight
+        assert!(!trader.submit(opportunity(10.0, 38.0), now));
+        assert!(trader
+            .execute_due(now + Duration::from_millis(199))
+            .is_empty());
+
+        // The DEX moved up during the latency
+        set_prices(&cache, 192.0, vec![(192.0, 100.0)]);
+        let trades = trader.execute_due(now + Duration::from_millis(200));
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.status, FillStatus::Filled);
+        assert_eq!(trade.buy.unwrap().price, 192.0);
+        assert_eq!(trade.sell.unwrap().price, 195.0);
+        // 3.0 per SOL less 0.25% and 0.1% trading fees and gas at the Binance price
+        let fees = 192.0 * 10.0 * 0.0025 + 195.0 * 10.0 * 0.001 + 0.001 * 195.0;
+        assert!((trade.fees - fees).abs() < 1e-9);
+        assert!((trade.realized_pnl - (30.0 - fees)).abs() < 1e-9);
+
+        let summary = trader.summary();
+        assert_eq!(summary.filled, 1);
+        assert_eq!(summary.predicted_pnl, 38.0);
+        assert_eq!(summary.balances.solana.get("SOL"), 60.0);
+        assert_eq!(summary.balances.binance.get("SOL"), 40.0);
+        assert!((summary.balances.binance.get("USDT") - (10_000.0 + 1950.0 - 1.95)).abs() < 1e-9);
+
+        // The same DEX price is not traded against twice
+        assert!(!trader.submit(opportunity(10.0, 38.0), now));
+        set_prices(&cache, 190.0, vec![(190.0, 100.0)]);
+        assert!(trader.submit(opportunity(10.0, 38.0), now));
+    }
+
+    #[test]
+    fn test_partial_fills_and_balance_limits() {
+        let (mut trader, cache) =
+            paper_trader(VenueBalances::paper_default([TradingPair::SOL_USDT]));
+        // Only 4 SOL offered on the DEX
+        set_prices(&cache, 190.0, vec![(190.0, 4.0)]);
+        let now = SystemTime::now();
+        trader.submit(opportunity(10.0, 38.0), now);
+        let trade = trader.execute_due(now + Duration::from_secs(1)).remove(0);
+        assert_eq!(trade.status, FillStatus::PartiallyFilled);
+        assert_eq!(trade.buy.unwrap().amount, 4.0);
+        assert_eq!(trade.sell.unwrap().amount, 10.0);
+        // Sold 6 SOL more than bought, marked at Binance's price
+        let gross = 195.0 * 10.0 - 190.0 * 4.0 - 6.0 * 195.0;
+        assert!((trade.realized_pnl - (gross - trade.fees)).abs() < 1e-9);
+
+        // Balances cap the order: 2 SOL to sell on Binance
+        let balances = VenueBalances {
+            binance: Balance::from([("SOL", 2.0)]),
+            solana: Balance::from([("USDT", 10_000.0)]),
+        };
+        let (mut trader, cache) = paper_trader(balances.clone());
+        set_prices(&cache, 190.0, vec![(190.0, 100.0)]);
+        trader.submit(opportunity(10.0, 38.0), now);
+        let trade = trader.execute_due(now + Duration::from_secs(1)).remove(0);
+        assert_eq!(trade.order_amount, 2.0);
+        assert_eq!(trade.status, FillStatus::Filled);
+
+        // Nothing to sell
+        let (mut trader, cache) = paper_trader(VenueBalances {
+            binance: Balance::default(),
+            ..balances
+        });
+        set_prices(&cache, 190.0, vec![(190.0, 100.0)]);
+        trader.submit(opportunity(10.0, 38.0), now);
+        let trade = trader.execute_due(now + Duration::from_secs(1)).remove(0);
+        assert_eq!(trade.status, FillStatus::Missed);
+        assert_eq!(trade.reason, Some("insufficient balance"));
+        assert_eq!(trader.summary().missed, 1);
+        assert_eq!(trader.summary().predicted_pnl, 0.0);
+    }
+
+    #[test]
+    fn test_rebalances_through_shared_inventory() {
+        use crate::arbitrage::inventory::RebalancingCosts;
+
+        let cache = Arc::new(PriceCache::new());
+        let caches = PriceCaches::from([(TradingPair::SOL_USDT, Arc::clone(&cache))]);
+        let inventory = SharedInventory::new(Inventory::new(
+            VenueBalances::paper_default([TradingPair::SOL_USDT]),
+            Some(RebalancingCosts::default()),
+        ));
+        let fee_calculator = FeeCalculator::default().with_inventory(inventory.clone());
+        let config = PaperConfig {
+            latency: Duration::from_millis(200),
+        };
+        let mut trader = PaperTrader::new(config, fee_calculator, caches);
+        set_prices(&cache, 190.0, vec![(190.0, 100.0)]);
+        let now = SystemTime::now();
+
+        trader.submit(opportunity(10.0, 38.0), now);
+        let trade = trader
+            .execute_due(now + Duration::from_millis(200))
+            .remove(0);
+        assert_eq!(trade.status, FillStatus::Filled);
+        let trading = 190.0 * 10.0 * 0.0025 + 195.0 * 10.0 * 0.001 + 0.001 * 195.0;
+        let rebalancing = 0.000005 * 195.0 + 1.0;
+        assert!((trade.fees - (trading + rebalancing)).abs() < 1e-9);
+
+        // The bought SOL and the proceeds are on their way back
+        let summary = trader.summary();
+        // The Solana transfer fee is paid from the wallet's SOL
+        assert!((summary.balances.solana.get("SOL") - (50.0 - 0.000005)).abs() < 1e-9);
+        assert_eq!(summary.balances.binance.get("SOL"), 40.0);
+        assert_eq!(summary.balances.binance.get("USDT"), 10_000.0);
+        assert_eq!(summary.in_transit.binance.get("SOL"), 10.0);
+        assert!((summary.in_transit.solana.get("USDT") - (1950.0 - 1.95 - 1.0)).abs() < 1e-9);
+        // The detector sizes against the same inventory
+        assert_eq!(inventory.read().balances().binance.get("SOL"), 40.0);
+
+        trader.execute_due(now + Duration::from_secs(61));
+        let summary = trader.summary();
+        assert!(summary.in_transit.is_zero());
+        assert_eq!(summary.balances.binance.get("SOL"), 50.0);
+    }
+}
@@ -0,0 +1,88 @@
+/// Golden-section iterations; shrinks the search interval by a factor of ~1e-21
+const ITERATIONS: usize = 100;
+
+/// Relative distance from a bound at which the search is taken to have converged onto it
+const BOUND_TOLERANCE: f64 = 1e-9;
+
+/// Inverse golden ratio
+const INV_PHI: f64 = 0.618_033_988_749_895;
+
+/// Find the amount in `[lower, upper]` that maximizes `profit`
+///
+/// Assumes profit is unimodal in the amount, which holds for concave price impact
+/// (AMM curves, order books, linear impact) with flat per-trade costs. Amounts where
+/// `profit` returns `None` (a leg cannot fill them) are treated as unprofitable.
+/// Returns the best (amount, profit), or `None` if no amount in range can be filled.
+pub fn maximize_profit<F>(lower: f64, upper: f64, profit: F) -> Option<(f64, f64)>
+where
+    F: Fn(f64) -> Option<f64>,
+{
+    if !(lower.is_finite() && upper.is_finite()) || lower > upper {
+        return None;
+    }
+    let value = |amount: f64| profit(amount).unwrap_or(f64::NEG_INFINITY);
+
+    let (mut a, mut b) = (lower, upper);
+    let mut c = b - INV_PHI * (b - a);
+    let mut d = a + INV_PHI * (b - a);
+    let (mut fc, mut fd) = (value(c), value(d));
+    for _ in 0..ITERATIONS {
+        if fc >= fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - INV_PHI * (b - a);
+            fc = value(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + INV_PHI * (b - a);
+            fd = value(d);
+        }
+    }
+
+    // Profit rising up to a bound peaks there, which the interior search only approaches
+    let tolerance = BOUND_TOLERANCE * (upper - lower).max(upper.abs());
+    let interior = match (a + b) / 2.0 {
+        mid if upper - mid <= tolerance && profit(upper).is_some() => upper,
+        mid if mid - lower <= tolerance && profit(lower).is_some() => lower,
+        mid => mid,
+    };
+    [interior, lower, upper]
+        .into_iter()
+        .filter_map(|amount| profit(amount).map(|p| (amount, p)))
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concave_profit_interior_maximum() {
+        // Peak at 3.0
+        let (amount, profit) = maximize_profit(0.0, 10.0, |q| Some(6.0 * q - q * q - 1.0)).unwrap();
+        assert!((amount - 3.0).abs() < 1e-6);
+        assert!((profit - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_linear_profit_takes_upper_bound() {
+        let (amount, _) = maximize_profit(1.0, 10.0, |q| Some(2.0 * q - 5.0)).unwrap();
+        assert_eq!(amount, 10.0);
+
+        let (amount, _) = maximize_profit(1.0, 10.0, |q| Some(-q)).unwrap();
+        assert_eq!(amount, 1.0);
+    }
+
+    #[test]
+    fn test_unfillable_amounts_avoided() {
+        // Cannot fill beyond 4.0; profit keeps rising until then
+        let (amount, _) = maximize_profit(0.0, 10.0, |q| (q < 4.0).then_some(q)).unwrap();
+        assert!(amount < 4.0 && amount > 3.999);
+
+        assert!(maximize_profit(0.0, 1.0, |_| None).is_none());
+        assert!(maximize_profit(2.0, 1.0, Some).is_none());
+    }
+}
@@ -0,0 +1,617 @@
+use crate::arbitrage::calculator::FeeCalculator;
+use crate::arbitrage::detector::ArbitrageDetector;
+use crate::arbitrage::paper::{PaperSummary, PaperTrader};
+use crate::config::{Config, TradingPair};
+use crate::performance::metrics::MetricsCollector;
+use crate::price::{Clock, PriceCache, PriceSource, PriceUpdate, Quote, SimulatedClock};
+use crate::websocket::record::RecordedFrame;
+use crate::websocket::{ConnectionManager, ConnectionManagerError, PriceCaches};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Arguments of the `backtest` subcommand
+#[derive(clap::Args, Debug, Clone)]
+pub struct BacktestArgs {
+    /// Price files (CSV or NDJSON price updates) or recordings made with --record
+    #[arg(required = true, value_name = "FILE")]
+    pub inputs: Vec<PathBuf>,
+}
+
+/// Errors that stop a backtest
+#[derive(Debug, Error)]
+pub enum BacktestError {
+    #[error("Cannot read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{path}:{line}: {message}")]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("Cannot replay recorded frames: {0}")]
+    Replay(#[from] ConnectionManagerError),
+    #[error("No price updates for the configured pairs in the input files")]
+    NoEvents,
+}
+
+/// Summary statistics of a set of values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Distribution {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    /// Nearest-rank percentiles of `values`, or `None` if there are none
+    pub fn from_values(mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+            values[rank.clamp(1, values.len()) - 1]
+        };
+        Some(Self {
+            min: values[0],
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+/// Aggregated results of a backtest
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    /// Price updates replayed
+    pub events: usize,
+    /// Updates skipped because their pair is not monitored
+    pub skipped_events: usize,
+    /// Simulated time from the first to the last update
+    pub duration_ms: u64,
+    /// Detection checks, over all pairs
+    pub checks: u64,
+    /// Checks without fresh, valid prices
+    pub checks_without_prices: u64,
+    /// Prices rejected during validation, by reason code (`skew`: legs too far apart in time)
+    pub rejections: BTreeMap<&'static str, u64>,
+    /// Checks that found an opportunity above the threshold
+    pub opportunities: u64,
+    /// Runs of consecutive checks with an opportunity
+    pub opportunity_windows: u64,
+    /// `profit_percentage` of every opportunity found
+    pub profit_percentage: Option<Distribution>,
+    /// Estimated profit of one trade per window, taken when the window opens
+    pub total_pnl: f64,
+    /// Time spent inside opportunity windows, summed over pairs
+    pub time_in_opportunity_ms: u64,
+    /// Time in opportunity as a percentage of duration times pairs
+    pub time_in_opportunity_percentage: f64,
+    /// Simulated executions, with `--paper-trade`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paper: Option<PaperSummary>,
+}
+
+/// Detection state of one pair
+struct PairRun {
+    cache: Arc<PriceCache>,
+    detector: ArbitrageDetector,
+    window_start: Option<SystemTime>,
+}
+
+/// Run detection over the input files on a simulated clock
+///
+/// Checks run every `check_interval` of simulated time, between the price updates
+/// that were received before and after them.
+pub async fn run(config: &Config, args: &BacktestArgs) -> Result<BacktestReport, BacktestError> {
+    let mut events = Vec::new();
+    for path in &args.inputs {
+        events.extend(load_events(path, config)?);
+    }
+    events.sort_by_key(|update| update.received_at);
+
+    let first = events.first().ok_or(BacktestError::NoEvents)?.received_at;
+    let last = events.last().ok_or(BacktestError::NoEvents)?.received_at;
+    let clock = Arc::new(SimulatedClock::new(first));
+    let caches: PriceCaches = config
+        .pairs
+        .iter()
+        .map(|&pair| (pair, Arc::new(PriceCache::new())))
+        .collect();
+    let fee_calculator = FeeCalculator::from_config(config).with_sol_price_caches(&caches);
+    let metrics = Arc::new(MetricsCollector::new());
+
+    let mut runs: Vec<(TradingPair, PairRun)> = config
+        .pairs
+        .iter()
+        .map(|&pair| {
+            let cache = Arc::clone(&caches[&pair]);
+            let detector =
+                ArbitrageDetector::new(Arc::clone(&cache), config, fee_calculator.clone())
+                    .with_trading_pair(pair)
+                    .with_clock(Clock::from(Arc::clone(&clock)))
+                    .with_metrics(Arc::clone(&metrics));
+            let run = PairRun {
+                cache,
+                detector,
+                window_start: None,
+            };
+            (pair, run)
+        })
+        .collect();
+
+    let mut report = BacktestReport {
+        events: 0,
+        skipped_events: 0,
+        duration_ms: last.duration_since(first).unwrap_or_default().as_millis() as u64,
+        checks: 0,
+        checks_without_prices: 0,
+        rejections: BTreeMap::new(),
+        opportunities: 0,
+        opportunity_windows: 0,
+        profit_percentage: None,
+        total_pnl: 0.0,
+        time_in_opportunity_ms: 0,
+        time_in_opportunity_percentage: 0.0,
+        paper: None,
+    };
+    let mut profits = Vec::new();
+    let mut time_in_opportunity = Duration::ZERO;
+
+    let mut paper = config
+        .paper
+        .map(|paper| PaperTrader::new(paper, fee_calculator.clone(), caches));
+
+    let mut next_check = first;
+    let mut index = 0;
+    loop {
+        // Paper orders execute at the prices of the moment they are due
+        let next_event = events
+            .get(index)
+            .map_or(next_check, |e| e.received_at.min(next_check));
+        if let Some(trader) = paper.as_mut() {
+            if let Some(due) = trader.next_due().filter(|due| *due <= next_event) {
+                trader.execute_due(due);
+            }
+        }
+
+        // A check due before the next update sees only the prices received so far
+        if events
+            .get(index)
+            .map_or(true, |e| next_check <= e.received_at)
+        {
+            clock.set(next_check);
+            // Detection sizes against the funds that have arrived by now
+            if let Some(inventory) = fee_calculator.inventory() {
+                inventory.write().settle(next_check);
+            }
+            for (_, run) in runs.iter_mut() {
+                report.checks += 1;
+                let opportunity = match run.detector.check_for_opportunities().await {
+                    Ok(opportunity) => {
+                        opportunity.filter(|opp| opp.exceeds_threshold(&config.threshold))
+                    }
+                    Err(_) => {
+                        report.checks_without_prices += 1;
+                        None
+                    }
+                };
+                match (opportunity, run.window_start) {
+                    (Some(opp), window) => {
+                        if let Some(trader) = paper.as_mut() {
+                            trader.submit(opp.clone(), next_check);
+                        }
+                        report.opportunities += 1;
+                        profits.push(opp.profit_percentage);
+                        if window.is_none() {
+                            report.opportunity_windows += 1;
+                            report.total_pnl += opp.estimated_total_profit;
+                            run.window_start = Some(next_check);
+                        }
+                    }
+                    (None, Some(start)) => {
+                        time_in_opportunity += next_check
+                            .min(last)
+                            .duration_since(start)
+                            .unwrap_or_default();
+                        run.window_start = None;
+                    }
+                    (None, None) => {}
+                }
+            }
+            if index == events.len() {
+                break;
+            }
+            next_check += config.check_interval;
+            continue;
+        }
+
+        let update = &events[index];
+        match runs.iter().find(|(pair, _)| *pair == update.pair) {
+            Some((_, run)) => {
+                run.cache.update(update);
+                report.events += 1;
+            }
+            None => report.skipped_events += 1,
+        }
+        index += 1;
+    }
+
+    // Windows still open at the end last until the final update
+    let end = last;
+    for (_, run) in &runs {
+        if let Some(start) = run.window_start {
+            time_in_opportunity += end.duration_since(start).unwrap_or_default();
+        }
+    }
+
+    if let Some(mut trader) = paper {
+        while let Some(due) = trader.next_due() {
+            trader.execute_due(due);
+        }
+        report.paper = Some(trader.summary());
+    }
+
+    report.rejections = metrics.rejections();
+    report.profit_percentage = Distribution::from_values(profits);
+    report.time_in_opportunity_ms = time_in_opportunity.as_millis() as u64;
+    let pair_time = report.duration_ms as f64 * runs.len() as f64;
+    if pair_time > 0.0 {
+        report.time_in_opportunity_percentage =
+            report.time_in_opportunity_ms as f64 / pair_time * 100.0;
+    }
+    Ok(report)
+}
+
+/// Price updates from one input file, by extension and content
+///
+/// `.csv` files are price tables; other files hold one JSON object per line, either
+/// serialized price updates or frames recorded with `--record`.
+fn load_events(path: &Path, config: &Config) -> Result<Vec<PriceUpdate>, BacktestError> {
+    let io_error = |source| BacktestError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = std::fs::File::open(path).map_err(io_error)?;
+    let mut lines = BufReader::new(file).lines().enumerate();
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    {
+        let Some((_, header)) = lines.next() else {
+            return Ok(Vec::new());
+        };
+        let columns = CsvColumns::new(path, &header.map_err(io_error)?)?;
+        let mut events = Vec::new();
+        for (index, line) in lines {
+            let line = line.map_err(io_error)?;
+            if !line.trim().is_empty() {
+                events.push(
+                    columns
+                        .parse(&line)
+                        .map_err(|message| BacktestError::Parse {
+                            path: path.to_path_buf(),
+                            line: index + 1,
+                            message,
+                        })?,
+                );
+            }
+        }
+        return Ok(events);
+    }
+
+    let mut events = Vec::new();
+    let mut replayer = None;
+    for (index, line) in lines {
+        let line = line.map_err(io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(recorded) = serde_json::from_str::<RecordedFrame>(&line) {
+            let replayer = match &mut replayer {
+                Some(replayer) => replayer,
+                None => replayer.insert(ConnectionManager::new(config)?.into_replayer()),
+            };
+            if let Ok(Some(update)) = replayer.replay(&recorded.frame, recorded.received_at()) {
+                events.push(update);
+            }
+            continue;
+        }
+        let update =
+            serde_json::from_str::<PriceUpdate>(&line).map_err(|e| BacktestError::Parse {
+                path: path.to_path_buf(),
+                line: index + 1,
+                message: e.to_string(),
+            })?;
+        events.push(update);
+    }
+    Ok(events)
+}
+
+/// Column positions of a price CSV
+///
+/// Required: `timestamp` (milliseconds since the Unix epoch or RFC 3339), `source`,
+/// `pair` and `price`. Optional: `bid`, `ask`, `bid_size`, `ask_size`.
+struct CsvColumns {
+    timestamp: usize,
+    source: usize,
+    pair: usize,
+    price: usize,
+    bid: Option<usize>,
+    ask: Option<usize>,
+    bid_size: Option<usize>,
+    ask_size: Option<usize>,
+}
+
+impl CsvColumns {
+    fn new(path: &Path, header: &str) -> Result<Self, BacktestError> {
+        let names: Vec<String> = header
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let find = |name: &str| names.iter().position(|n| n == name);
+        let require = |name: &str| {
+            find(name).ok_or_else(|| BacktestError::Parse {
+                path: path.to_path_buf(),
+                line: 1,
+                message: format!("missing column `{}`", name),
+            })
+        };
+        Ok(Self {
+            timestamp: require("timestamp")?,
+            source: require("source")?,
+            pair: require("pair")?,
+            price: require("price")?,
+            bid: find("bid"),
+            ask: find("ask"),
+            bid_size: find("bid_size"),
+            ask_size: find("ask_size"),
+        })
+    }
+
+    fn parse(&self, line: &str) -> Result<PriceUpdate, String> {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |index: usize| fields.get(index).copied().unwrap_or_default();
+        let optional = |index: Option<usize>| -> Result<Option<f64>, String> {
+            match index.map(field).filter(|value| !value.is_empty()) {
+                Some(value) => parse_number(value).map(Some),
+                None => Ok(None),
+            }
+        };
+
+        let timestamp = parse_timestamp(field(self.timestamp))?;
+        let source: PriceSource = serde_json::from_value(field(self.source).into())
+            .map_err(|_| format!("unknown source `{}`", field(self.source)))?;
+        let pair: TradingPair = field(self.pair).parse().map_err(|e| format!("{}", e))?;
+        let price = parse_number(field(self.price))?;
+
+        let mut update = PriceUpdate::with_timestamp(source, pair, price, timestamp);
+        if let (Some(bid), Some(ask)) = (optional(self.bid)?, optional(self.ask)?) {
+            let mut quote = Quote::new(bid, ask);
+            if let (Some(bid_size), Some(ask_size)) =
+                (optional(self.bid_size)?, optional(self.ask_size)?)
+            {
+                quote = quote.with_sizes(bid_size, ask_size);
+            }
+            update = update.with_quote(quote);
+        }
+        Ok(update)
+    }
+}
+
+fn parse_number(value: &str) -> Result<f64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", value))
+}
+
+/// Milliseconds since the Unix epoch, or an RFC 3339 date
+fn parse_timestamp(value: &str) -> Result<SystemTime, String> {
+    if let Ok(millis) = value.parse::<u64>() {
+        return Ok(UNIX_EPOCH + Duration::from_millis(millis));
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(SystemTime::from)
+        .map_err(|_| format!("invalid timestamp `{}`", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::config::create_test_config_with_threshold;
+
+    fn write_input(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("arb-backtest-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_distribution() {
+        let distribution = Distribution::from_values((1..=100).map(f64::from).collect()).unwrap();
+        assert_eq!(distribution.min, 1.0);
+        assert_eq!(distribution.p50, 50.0);
+        assert_eq!(distribution.p90, 90.0);
+        assert_eq!(distribution.p99, 99.0);
+        assert_eq!(distribution.max, 100.0);
+        assert_eq!(distribution.mean, 50.5);
+        assert!(Distribution::from_values(Vec::new()).is_none());
+    }
+
+    /// Binance ticks every second; the DEX diverges for 2s starting at 3s
+    fn divergence_csv() -> String {
+        let mut csv = String::from("timestamp,source,pair,price,bid,ask\n");
+        for second in 0..=6u64 {
+            let t = 1_700_000_000_000 + second * 1000;
+            csv.push_str(&format!("{},binance,sol-usdt,195.0,194.99,195.01\n", t));
+            let dex = if (3..5).contains(&second) {
+                190.0
+            } else {
+                195.0
+            };
+            csv.push_str(&format!("{},solana,sol-usdt,{},,\n", t + 100, dex));
+        }
+        csv.push_str(&format!(
+            "{},binance,sol-usdc,150.0,,\n",
+            1_700_000_000_000u64
+        ));
+        csv
+    }
+
+    #[tokio::test]
+    async fn test_backtest_csv_on_simulated_clock() {
+        let csv = divergence_csv();
+        let path = write_input("prices.csv", &csv);
+
+        let config = create_test_config_with_threshold(0.5);
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let report = run(&config, &args).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(report.events, 14);
+        assert_eq!(report.skipped_events, 1);
+        assert_eq!(report.duration_ms, 6100);
+        // One check every 500ms of simulated time, the first without a DEX price
+        assert_eq!(report.checks, 14);
+        assert_eq!(report.checks_without_prices, 1);
+        assert_eq!(report.opportunity_windows, 1);
+        assert_eq!(report.opportunities, 4);
+        assert_eq!(report.time_in_opportunity_ms, 2000);
+        let profit = report.profit_percentage.unwrap();
+        assert!(profit.min > 0.5 && profit.min == profit.max);
+        assert!(report.total_pnl > 0.0);
+
+        // The same opportunity is under a higher threshold
+        let config = create_test_config_with_threshold(5.0);
+        let path = write_input("prices-high.csv", &csv);
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let report = run(&config, &args).await.unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(report.opportunities, 0);
+        assert_eq!(report.total_pnl, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_paper_trading() {
+        let path = write_input("paper.csv", &divergence_csv());
+        let mut config = create_test_config_with_threshold(0.5);
+        config.paper = Some(crate::arbitrage::paper::PaperConfig {
+            latency: Duration::from_millis(200),
+        });
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let report = run(&config, &args).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // One trade per DEX update while the spread lasts, at unchanged prices
+        let paper = report.paper.unwrap();
+        assert_eq!(
+            (paper.filled, paper.partially_filled, paper.missed),
+            (2, 0, 0)
+        );
+        assert!((paper.realized_pnl - paper.predicted_pnl).abs() < 1e-6);
+        assert!(paper.balances.solana.get("SOL") > paper.balances.binance.get("SOL"));
+    }
+
+    #[tokio::test]
+    async fn test_backtest_counts_skew_rejections() {
+        // The DEX prices once while Binance keeps ticking
+        let mut csv = String::from("timestamp,source,pair,price\n");
+        csv.push_str("1700000000000,solana,sol-usdt,190.0\n");
+        for second in 0..=4u64 {
+            let t = 1_700_000_000_000 + second * 1000;
+            csv.push_str(&format!("{},binance,sol-usdt,195.0\n", t));
+        }
+        let path = write_input("skew.csv", &csv);
+
+        let config = create_test_config_with_threshold(0.5);
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let report = run(&config, &args).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // Once Binance is priced 3s after the DEX, the legs are no longer compared
+        assert_eq!(report.rejections.get("skew"), Some(&3));
+        assert_eq!(
+            report.checks,
+            report.checks_without_prices + report.opportunities
+        );
+        assert!(report.opportunities > 0);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_recorded_frames() {
+        let ticker = |t: u64, price: &str| {
+            let frame = crate::websocket::record::Frame::Binance {
+                f: format!(
+                    r#"{{"stream":"solusdt@ticker","data":{{"s":"SOLUSDT","c":"{}","E":1}}}}"#,
+                    price
+                ),
+            };
+            serde_json::to_string(&RecordedFrame { t, frame }).unwrap()
+        };
+        let recording = [ticker(1_000_000, "190.5"), ticker(3_000_000, "191.25")].join("\n");
+        let path = write_input("frames.ndjson", &recording);
+
+        let config = create_test_config_with_threshold(0.5);
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let report = run(&config, &args).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // Updates take the recorded receive time
+        assert_eq!(report.events, 2);
+        assert_eq!(report.duration_ms, 2000);
+        assert_eq!(report.checks, report.checks_without_prices);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_input_errors() {
+        let config = create_test_config_with_threshold(0.5);
+
+        let path = write_input("bad.csv", "timestamp,source,price\n1,binance,1\n");
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let error = run(&config, &args).await.unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(error.contains("missing column `pair`"), "{}", error);
+
+        let update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0);
+        let ndjson = format!(
+            "{}\n{{\"price\":1}}\n",
+            serde_json::to_string(&update).unwrap()
+        );
+        let path = write_input("bad.ndjson", &ndjson);
+        let args = BacktestArgs {
+            inputs: vec![path.clone()],
+        };
+        let error = run(&config, &args).await.unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(error.contains("bad.ndjson:2:"), "{}", error);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::output::OutputFormat;
+use crate::pair::PairDefinition;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use url::Url;
+
+/// Settings read from a `--config` TOML file
+///
+/// Every key is optional. Keys that also exist as command-line flags are overridden by
+/// the flag or its environment variable; the rest can only be set here.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Trading pairs to monitor, as with `--pair`
+    pub pairs: Option<Vec<String>>,
+    /// User-defined pairs, as with `--define-pair`
+    #[serde(default)]
+    pub pair_definitions: Vec<PairDefinition>,
+    pub threshold: Option<f64>,
+    pub max_price_age_ms: Option<u64>,
+    pub max_price_skew_ms: Option<u64>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub rpc_url: Option<Url>,
+    pub enable_performance_monitor: Option<bool>,
+    /// Address to serve Prometheus metrics on, as with `--metrics-addr`
+    pub metrics_addr: Option<SocketAddr>,
+    /// Longest time between arbitrage checks when no prices arrive
+    pub check_interval_ms: Option<u64>,
+    /// Quiet period after a price update before checking, as with `--debounce-ms`
+    pub debounce_ms: Option<u64>,
+    /// Interval between performance reports
+    pub monitor_interval_secs: Option<u64>,
+    /// Period latency percentiles are computed over
+    pub metrics_window_secs: Option<u64>,
+    #[serde(default)]
+    pub output: OutputSection,
+    #[serde(default)]
+    pub sizing: SizingSection,
+    #[serde(default)]
+    pub fees: FeesSection,
+    #[serde(default)]
+    pub reconnect: ReconnectSection,
+    #[serde(default)]
+    pub paper: PaperSection,
+    #[serde(default)]
+    pub webhook: WebhookSection,
+    /// Balances trades are sized to; without it, sizes are not limited by venue
+    pub inventory: Option<InventorySection>,
+    #[serde(default)]
+    pub rebalancing: RebalancingSection,
+    /// RPC providers in priority order, used unless `rpc_url` is set
+    #[serde(default)]
+    pub providers: Vec<ProviderSection>,
+    #[serde(default)]
+    pub api_keys: ApiKeysSection,
+}
+
+/// `[output]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputSection {
+    pub format: Option<OutputFormat>,
+}
+
+/// `[sizing]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SizingSection {
+    pub min_notional: Option<f64>,
+    pub max_notional: Option<f64>,
+    pub max_base_inventory: Option<f64>,
+    pub max_quote_inventory: Option<f64>,
+    pub cex_impact_bps: Option<f64>,
+    /// Trade amount used when a leg gives no size information (base tokens)
+    pub default_trade_amount: Option<f64>,
+    /// Initialized ticks a concentrated liquidity trade may cross
+    pub max_ticks_crossed: Option<u32>,
+}
+
+/// `[fees]`, percentages except for gas
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeesSection {
+    pub binance_spot: Option<f64>,
+    pub solana_dex: Option<f64>,
+    /// Gas per transaction, in SOL
+    pub solana_gas: Option<f64>,
+    pub transfer: Option<f64>,
+}
+
+/// `[reconnect]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReconnectSection {
+    pub initial_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+    pub backoff_multiplier: Option<f64>,
+    /// Attempts before giving up; 0 retries forever
+    pub max_attempts: Option<usize>,
+    /// Time before giving up; 0 retries forever
+    pub max_total_duration_secs: Option<u64>,
+    pub jitter: Option<bool>,
+}
+
+/// `[paper]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PaperSection {
+    pub enabled: Option<bool>,
+    pub latency_ms: Option<u64>,
+}
+
+/// `[webhook]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSection {
+    /// Endpoint to post opportunities to, as with `--webhook-url`
+    pub url: Option<Url>,
+    /// Extra request headers, e.g. `Authorization`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub timeout_ms: Option<u64>,
+    /// Retries after a failed delivery; 0 never retries
+    pub max_retries: Option<usize>,
+    /// Delay before the first retry, doubling for each later one
+    pub retry_delay_ms: Option<u64>,
+    /// Opportunities waiting for delivery before new ones are dropped
+    pub queue_size: Option<usize>,
+}
+
+/// `[inventory]`, starting balances on each venue by asset symbol, e.g. `SOL = 50.0`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InventorySection {
+    #[serde(default)]
+    pub binance: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub solana: BTreeMap<String, f64>,
+}
+
+/// `[rebalancing]`, withdrawal fees in the asset withdrawn
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RebalancingSection {
+    /// `[rebalancing.binance_withdrawal]`, Binance withdrawal fee by asset symbol
+    #[serde(default)]
+    pub binance_withdrawal: BTreeMap<String, f64>,
+    /// Fee of a Solana transfer transaction, in SOL
+    pub solana_transfer_fee: Option<f64>,
+    pub transfer_time_secs: Option<u64>,
+}
+
+/// `[[providers]]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderSection {
+    pub name: String,
+    pub url: Url,
+}
+
+/// `[api_keys]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeysSection {
+    pub helius: Option<String>,
+    pub alchemy: Option<String>,
+    pub genesisgo: Option<String>,
+}
+
+impl ConfigFile {
+    /// Read and parse a TOML config file
+    ///
+    /// Parse errors name the offending key with its line and column.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parse TOML config file contents
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, RawConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const EXAMPLE: &str = r#"
+pairs = ["sol-usdc", "jup-usdc-file"]
+threshold = 0.8
+check_interval_ms = 250
+metrics_window_secs = 120
+max_price_skew_ms = 1500
+metrics_addr = "127.0.0.1:9184"
+rpc_url = "wss://file.example.com/"
+
+[[pair_definitions]]
+name = "jup-usdc-file"
+base = "JUP"
+quote = "USDC"
+base_mint = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN"
+quote_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+base_decimals = 6
+quote_decimals = 6
+whirlpool = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"
+
+[output]
+format = "json"
+
+[sizing]
+max_notional = 5000.0
+default_trade_amount = 2.0
+
+[fees]
+binance_spot = 0.075
+
+[reconnect]
+initial_delay_ms = 250
+max_attempts = 0
+
+[webhook]
+url = "https://hooks.example.com/arb"
+headers = { Authorization = "Bearer file-token" }
+max_retries = 5
+
+[[providers]]
+name = "Primary"
+url = "wss://primary.example.com/"
+"#;
+
+    /// Config file in the temp dir, removed on drop
+    struct TempConfig(std::path::PathBuf);
+
+    impl TempConfig {
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            std::fs::remove_file(&self.0).ok();
+        }
+    }
+
+    fn write_config(contents: &str) -> TempConfig {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "arb-config-{}-{}.toml",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, contents).unwrap();
+        TempConfig(path)
+    }
+
+    fn load(args: &[&str]) -> Result<Config, crate::config::ConfigErrors> {
+        let args = std::iter::once("solana-arbitrage-watcher").chain(args.iter().copied());
+        RawConfig::try_load_from(args).and_then(|raw| Config::new(&raw))
+    }
+
+    #[test]
+    fn test_file_settings_applied() {
+        let file = write_config(EXAMPLE);
+        let config = load(&["--config", file.path()]).unwrap();
+
+        assert_eq!(config.pairs.len(), 2);
+        assert_eq!(config.pairs[1].binance_symbol(), "JUPUSDC");
+        assert_eq!(config.threshold.value(), 0.8);
+        assert_eq!(config.check_interval, Duration::from_millis(250));
+        assert_eq!(config.metrics_window, Duration::from_secs(120));
+        assert_eq!(config.max_price_skew, Duration::from_millis(1500));
+        assert_eq!(config.metrics_addr, Some("127.0.0.1:9184".parse().unwrap()));
+        assert!(matches!(config.output_format, OutputFormat::Json));
+        assert_eq!(config.sizing.max_notional, Some(5000.0));
+        assert_eq!(config.default_trade_amount, 2.0);
+        assert_eq!(config.fees.binance_spot_fee, 0.075);
+        assert_eq!(config.fees.solana_dex_fee, 0.25);
+        assert_eq!(config.reconnect.initial_delay, Duration::from_millis(250));
+        assert_eq!(config.reconnect.max_attempts, None);
+        let webhook = config.webhook.unwrap();
+        assert_eq!(webhook.url.as_str(), "https://hooks.example.com/arb");
+        assert_eq!(
+            webhook.headers,
+            [("Authorization".to_string(), "Bearer file-token".to_string())]
+        );
+        assert_eq!(webhook.max_retries, 5);
+        assert_eq!(webhook.queue_size, 100);
+        assert!(config.inventory.is_none());
+        // rpc_url from the file takes precedence over the provider list
+        assert_eq!(config.rpc_providers.len(), 1);
+        assert_eq!(
+            config.rpc_providers[0].websocket_url.as_str(),
+            "wss://file.example.com/"
+        );
+    }
+
+    #[test]
+    fn test_precedence_file_env_cli() {
+        let file = write_config(&EXAMPLE.replace("rpc_url", "# rpc_url"));
+        let path = file.path();
+
+        // Without rpc_url the file's provider list is used
+        let config = load(&["--config", path, "--threshold", "1.5"]).unwrap();
+        assert_eq!(config.rpc_providers[0].name, "Primary");
+        assert_eq!(config.threshold.value(), 1.5);
+
+        // Environment overrides the file, flags override the environment
+        let file = write_config(EXAMPLE);
+        let path = file.path();
+        std::env::set_var("ALCHEMY_API_KEY", "from-env");
+        let env = load(&["--config", path]).unwrap();
+        let cli = load(&["--config", path, "--alchemy-api-key", "from-cli"]).unwrap();
+        std::env::remove_var("ALCHEMY_API_KEY");
+        assert_eq!(env.api_keys.alchemy.as_deref(), Some("from-env"));
+        assert_eq!(cli.api_keys.alchemy.as_deref(), Some("from-cli"));
+
+        let cli = load(&["--config", path, "--pair", "sol-usdt"]).unwrap();
+        assert_eq!(cli.pairs, vec![crate::config::TradingPair::SOL_USDT]);
+
+        // The flag replaces the file's webhook URL but keeps its other settings
+        let cli = load(&[
+            "--config",
+            path,
+            "--webhook-url",
+            "https://cli.example.com/",
+        ])
+        .unwrap();
+        let webhook = cli.webhook.unwrap();
+        assert_eq!(webhook.url.as_str(), "https://cli.example.com/");
+        assert_eq!(webhook.max_retries, 5);
+    }
+
+    #[test]
+    fn test_inventory_section() {
+        let file = write_config(&format!(
+            "{}\n[inventory.binance]\nsol = 20.0\n\n[inventory.solana]\nUSDC = 5000.0\n\n[rebalancing]\ntransfer_time_secs = 30\n\n[rebalancing.binance_withdrawal]\njup = 2.5\n",
+            EXAMPLE
+        ));
+        let inventory = load(&["--config", file.path()]).unwrap().inventory.unwrap();
+        // Symbols are case-insensitive, like pair names
+        assert_eq!(inventory.balances.binance.get("SOL"), 20.0);
+        assert_eq!(inventory.balances.binance.get("USDC"), 0.0);
+        assert_eq!(inventory.balances.solana.get("USDC"), 5000.0);
+        assert_eq!(inventory.rebalancing.transfer_time, Duration::from_secs(30));
+        // Withdrawal fees are in the asset withdrawn, over the defaults
+        assert_eq!(inventory.rebalancing.binance_withdrawal_fee("JUP"), 2.5);
+        assert_eq!(inventory.rebalancing.binance_withdrawal_fee("SOL"), 0.008);
+        assert_eq!(inventory.rebalancing.binance_withdrawal_fee("USDC"), 1.0);
+
+        let file = write_config(&format!("{}\n[inventory.solana]\nSOL = -1.0\n", EXAMPLE));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("inventory.solana.SOL in"), "{}", errors);
+
+        // A traded asset without a withdrawal fee cannot be rebalanced
+        let file = write_config(&format!("{}\n[inventory.binance]\nsol = 20.0\n", EXAMPLE));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(
+            errors.contains("rebalancing.binance_withdrawal.JUP in"),
+            "{}",
+            errors
+        );
+        assert!(errors.contains("pair 'jup-usdc-file'"), "{}", errors);
+    }
+
+    #[test]
+    fn test_errors_name_the_key() {
+        let file = write_config(&EXAMPLE.replace("threshold = 0.8", "threshold = 150.0"));
+        let path = file.path();
+        let errors = load(&["--config", path]).unwrap_err().to_string();
+        assert!(
+            errors.contains(&format!("threshold in {}", path)),
+            "{}",
+            errors
+        );
+
+        // The same value given as a flag is reported against the flag
+        let errors = load(&["--config", path, "--threshold", "200"])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            errors.contains("--threshold: Invalid threshold: 200"),
+            "{}",
+            errors
+        );
+
+        let file =
+            write_config(&EXAMPLE.replace("max_price_skew_ms = 1500", "max_price_skew_ms = 0"));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("max_price_skew_ms in"), "{}", errors);
+
+        let file = write_config(&EXAMPLE.replace("binance_spot = 0.075", "binance_spot = -1.0"));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("fees.binance_spot in"), "{}", errors);
+
+        let file =
+            write_config(&EXAMPLE.replace("base_decimals = 6", "base_decimals = 6\nlogo = 1"));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("unknown field `logo`"), "{}", errors);
+        assert!(errors.contains("line"), "{}", errors);
+
+        let file = write_config(&EXAMPLE.replace("initial_delay_ms = 250", "initial_delay_ms = 0"));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("reconnect in"), "{}", errors);
+
+        let file = write_config(&EXAMPLE.replace("max_retries = 5", "queue_size = 0"));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(errors.contains("webhook in"), "{}", errors);
+
+        // Flag errors are not blamed on the file
+        let errors = load(&["--config", path, "--threshold", "high"])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            errors.contains("Command line error: invalid value 'high' for '--threshold"),
+            "{}",
+            errors
+        );
+        assert!(!errors.contains("Config file error"), "{}", errors);
+
+        // Fees paid in SOL on JUP/USDC are valued with SOL/USDC
+        let file = write_config(&EXAMPLE.replace("\"sol-usdc\", ", ""));
+        let errors = load(&["--config", file.path()]).unwrap_err().to_string();
+        assert!(
+            errors.contains("pair 'jup-usdc-file' needs a SOL/USDC pair"),
+            "{}",
+            errors
+        );
+    }
+}
@@ -0,0 +1,1121 @@
+mod file;
+pub mod reload;
+
+use crate::arbitrage::calculator::{TradingFees, DEFAULT_MAX_TICKS_CROSSED};
+use crate::arbitrage::inventory::{Balance, InventoryConfig, RebalancingCosts, VenueBalances};
+use crate::arbitrage::paper::PaperConfig;
+use crate::backtest::BacktestArgs;
+use crate::output::webhook::{
+    DEFAULT_WEBHOOK_QUEUE_SIZE, DEFAULT_WEBHOOK_RETRIES, DEFAULT_WEBHOOK_RETRY_DELAY,
+    DEFAULT_WEBHOOK_TIMEOUT,
+};
+use crate::output::{OutputFormat, WebhookConfig};
+use crate::pair::PairError;
+pub use crate::pair::{PairSpec, TradingPair};
+use crate::performance::histogram::DEFAULT_WINDOW as DEFAULT_METRICS_WINDOW;
+use crate::websocket::reconnect::ReconnectConfig;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+pub use file::ConfigFile;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+
+/// Raw configuration from CLI args and environment (unvalidated)
+#[derive(Parser, Debug)]
+#[command(name = "solana-arbitrage-watcher")]
+pub struct RawConfig {
+    /// TOML config file; environment variables and flags override its settings
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Trading pairs to monitor (comma-separated): sol-usdt, sol-usdc or a defined pair
+    #[arg(long, value_delimiter = ',')]
+    pub pair: Vec<String>,
+
+    /// Define a trading pair as key=value,... (base, quote, base_mint, quote_mint,
+    /// base_decimals, quote_decimals, pool addresses, binance_symbol)
+    #[arg(long, value_name = "SPEC")]
+    pub define_pair: Vec<PairSpec>,
+
+    /// Minimum profit threshold percentage
+    #[arg(long, default_value = "0.1")]
+    pub threshold: f64,
+
+    /// Maximum price age in milliseconds before data is considered stale
+    #[arg(long, default_value = "5000")]
+    pub max_price_age_ms: u64,
+
+    /// Maximum difference in milliseconds between the source times of the Binance and DEX prices compared
+    #[arg(long, default_value = "2000")]
+    pub max_price_skew_ms: u64,
+
+    /// Solana RPC WebSocket URL
+    #[arg(long, env = "SOLANA_RPC_URL")]
+    pub rpc_url: Option<Url>,
+
+    /// Helius API key for premium RPC access
+    #[arg(long, env = "HELIUS_API_KEY")]
+    pub helius_api_key: Option<String>,
+
+    /// Alchemy API key for premium RPC access
+    #[arg(long, env = "ALCHEMY_API_KEY")]
+    pub alchemy_api_key: Option<String>,
+
+    /// GenesisGo API key for premium RPC access
+    #[arg(long, env = "GENESISGO_API_KEY")]
+    pub genesisgo_api_key: Option<String>,
+
+    /// Output format for displaying results
+    #[arg(long, value_enum, default_value = "table")]
+    pub output_format: OutputFormat,
+
+    /// Minimum valid price for pairs without their own bounds (default: 1.0)
+    #[arg(long, default_value = "1.0")]
+    pub min_price: f64,
+
+    /// Maximum valid price for pairs without their own bounds (default: 10000.0)
+    #[arg(long, default_value = "10000.0")]
+    pub max_price: f64,
+
+    /// Enable performance monitoring and metrics collection
+    #[arg(long, default_value = "false")]
+    pub enable_performance_monitor: bool,
+
+    /// Serve Prometheus metrics at http://ADDR/metrics, e.g. 127.0.0.1:9184
+    #[arg(long, value_name = "ADDR")]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// POST opportunities above the threshold as JSON to this URL
+    #[arg(long, value_name = "URL", env = "WEBHOOK_URL")]
+    pub webhook_url: Option<Url>,
+
+    /// Minimum trade notional in quote currency
+    #[arg(long, default_value = "0.0")]
+    pub min_notional: f64,
+
+    /// Maximum trade notional in quote currency
+    #[arg(long)]
+    pub max_notional: Option<f64>,
+
+    /// Base tokens available to sell
+    #[arg(long)]
+    pub max_base_inventory: Option<f64>,
+
+    /// Quote currency available to buy with
+    #[arg(long)]
+    pub max_quote_inventory: Option<f64>,
+
+    /// Linear Binance price impact in basis points per base token, used without book depth
+    #[arg(long)]
+    pub cex_impact_bps: Option<f64>,
+
+    /// Record every raw Binance and Solana frame to this file
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Replay frames recorded with --record instead of connecting
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Replay speed as a multiple of real time
+    #[arg(long, default_value = "1.0", requires = "replay")]
+    pub replay_speed: f64,
+
+    /// Wait this long after a price update for further updates before checking (0 checks on every update)
+    #[arg(long, default_value = "0")]
+    pub debounce_ms: u64,
+
+    /// Simulate executing opportunities above the threshold and report realized PnL
+    #[arg(long, default_value = "false")]
+    pub paper_trade: bool,
+
+    /// Delay between detecting an opportunity and its simulated execution
+    #[arg(long, default_value = "200")]
+    pub paper_latency_ms: u64,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Config file settings that have no flag
+    #[arg(skip)]
+    pub file: ConfigFile,
+
+    /// Flags whose value was taken from the config file
+    #[arg(skip)]
+    pub from_file: BTreeSet<&'static str>,
+}
+
+/// Subcommands run instead of live monitoring
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run detection over historical price files and report the results
+    Backtest(BacktestArgs),
+}
+
+impl RawConfig {
+    /// Parse the command line and environment, then fill in settings from `--config`
+    ///
+    /// Exits with usage on invalid arguments, like `Parser::parse`.
+    pub fn load() -> Result<Self, ConfigErrors> {
+        Self::from_matches(&Self::command().get_matches())
+    }
+
+    /// Like [`RawConfig::load`], with explicit arguments
+    #[allow(dead_code)]
+    pub fn try_load_from<I, T>(args: I) -> Result<Self, ConfigErrors>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let matches = Self::command()
+            .try_get_matches_from(args)
+            .map_err(ConfigError::args)?;
+        Self::from_matches(&matches)
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Result<Self, ConfigErrors> {
+        let mut raw = Self::from_arg_matches(matches).map_err(ConfigError::args)?;
+        if let Some(path) = raw.config.clone() {
+            let file = ConfigFile::load(&path).map_err(ConfigError::File)?;
+            raw.apply_file(file, matches);
+        }
+        Ok(raw)
+    }
+
+    /// Take settings from the file where neither a flag nor its environment variable is set
+    fn apply_file(&mut self, file: ConfigFile, matches: &ArgMatches) {
+        let mut from_file = BTreeSet::new();
+        let mut unset = |id: &'static str| {
+            let set = matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            );
+            if !set {
+                from_file.insert(id);
+            }
+            !set
+        };
+
+        macro_rules! fill {
+            ($field:ident, $value:expr) => {
+                if let Some(value) = $value {
+                    if unset(stringify!($field)) {
+                        self.$field = value;
+                    }
+                }
+            };
+        }
+        macro_rules! fill_option {
+            ($field:ident, $value:expr) => {
+                if let Some(value) = $value {
+                    if unset(stringify!($field)) {
+                        self.$field = Some(value);
+                    }
+                }
+            };
+        }
+
+        fill!(pair, file.pairs.clone());
+        fill!(threshold, file.threshold);
+        fill!(max_price_age_ms, file.max_price_age_ms);
+        fill!(max_price_skew_ms, file.max_price_skew_ms);
+        fill!(min_price, file.min_price);
+        fill!(max_price, file.max_price);
+        fill!(enable_performance_monitor, file.enable_performance_monitor);
+        fill!(output_format, file.output.format);
+        fill!(debounce_ms, file.debounce_ms);
+        fill!(paper_trade, file.paper.enabled);
+        fill!(paper_latency_ms, file.paper.latency_ms);
+        fill!(min_notional, file.sizing.min_notional);
+        fill_option!(max_notional, file.sizing.max_notional);
+        fill_option!(max_base_inventory, file.sizing.max_base_inventory);
+        fill_option!(max_quote_inventory, file.sizing.max_quote_inventory);
+        fill_option!(cex_impact_bps, file.sizing.cex_impact_bps);
+        fill_option!(metrics_addr, file.metrics_addr);
+        fill_option!(webhook_url, file.webhook.url.clone());
+        fill_option!(rpc_url, file.rpc_url.clone());
+        fill_option!(helius_api_key, file.api_keys.helius.clone());
+        fill_option!(alchemy_api_key, file.api_keys.alchemy.clone());
+        fill_option!(genesisgo_api_key, file.api_keys.genesisgo.clone());
+
+        self.from_file = from_file;
+        self.file = file;
+    }
+
+    /// Trading fees from the config file, with defaults for missing keys (unvalidated)
+    pub fn fees(&self) -> TradingFees {
+        let fees = &self.file.fees;
+        let default_fees = TradingFees::default();
+        TradingFees {
+            binance_spot_fee: fees.binance_spot.unwrap_or(default_fees.binance_spot_fee),
+            solana_dex_fee: fees.solana_dex.unwrap_or(default_fees.solana_dex_fee),
+            solana_gas_fee: fees.solana_gas.unwrap_or(default_fees.solana_gas_fee),
+            transfer_fee: fees.transfer.unwrap_or(default_fees.transfer_fee),
+        }
+    }
+
+    /// Name of a setting for error messages, noting when it came from the config file
+    fn key(&self, flag: &'static str, file_key: &str) -> String {
+        match &self.config {
+            Some(path) if self.from_file.contains(flag) => {
+                format!("{} in {}", file_key, path.display())
+            }
+            _ => format!("--{}", flag.replace('_', "-")),
+        }
+    }
+
+    /// Name of a setting that only exists in the config file
+    fn file_key(&self, file_key: &str) -> String {
+        match &self.config {
+            Some(path) => format!("{} in {}", file_key, path.display()),
+            None => file_key.to_string(),
+        }
+    }
+}
+
+/// Validated application configuration (always valid)
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Monitored trading pairs, without duplicates, in the order given
+    pub pairs: Vec<TradingPair>,
+    pub threshold: ProfitThreshold,
+    pub max_price_age_ms: MaxPriceAge,
+    /// Largest difference between the source times of the two legs of an opportunity
+    pub max_price_skew: Duration,
+    pub rpc_providers: Vec<RpcProvider>,
+    pub output_format: OutputFormat,
+    pub price_bounds: PriceBounds,
+    pub api_keys: ApiKeyConfig,
+    pub enable_performance_monitor: bool,
+    /// Address to serve Prometheus metrics on
+    pub metrics_addr: Option<SocketAddr>,
+    /// Webhook opportunities above the threshold are posted to
+    pub webhook: Option<WebhookConfig>,
+    pub sizing: SizingLimits,
+    pub fees: TradingFees,
+    /// Trade amount used when a leg gives no size information (base tokens)
+    pub default_trade_amount: f64,
+    /// Initialized ticks a concentrated liquidity trade may cross
+    pub max_ticks_crossed: u32,
+    /// Longest time between arbitrage checks when no prices arrive
+    pub check_interval: Duration,
+    /// Quiet period after a price update before checking
+    pub debounce: Duration,
+    /// Interval between performance reports
+    pub monitor_interval: Duration,
+    /// Period latency percentiles are computed over
+    pub metrics_window: Duration,
+    pub reconnect: ReconnectConfig,
+    /// File to record raw frames to
+    pub record: Option<PathBuf>,
+    /// Recording to replay instead of connecting
+    pub replay: Option<PathBuf>,
+    /// Replay speed as a multiple of real time
+    pub replay_speed: f64,
+    /// Paper trading settings, when enabled
+    pub paper: Option<PaperConfig>,
+    /// Balances on each venue and rebalancing costs, when trades are sized to them
+    pub inventory: Option<InventoryConfig>,
+}
+
+/// Default interval between arbitrage checks
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default largest difference between the source times of an opportunity's legs
+pub const DEFAULT_MAX_PRICE_SKEW: Duration = Duration::from_millis(2000);
+
+/// Default interval between performance reports
+pub const DEFAULT_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default trade amount when a leg gives no size information (base tokens)
+pub const DEFAULT_TRADE_AMOUNT: f64 = 10.0;
+
+/// Default price bounds constants
+pub const DEFAULT_MIN_PRICE: f64 = 1.0;
+pub const DEFAULT_MAX_PRICE: f64 = 10000.0;
+
+/// Validated price bounds for validation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceBounds {
+    pub min_price: f64,
+    pub max_price: f64,
+}
+
+impl PriceBounds {
+    pub fn new(min_price: f64, max_price: f64) -> Result<Self, ConfigError> {
+        if !min_price.is_finite() || !max_price.is_finite() {
+            return Err(ConfigError::PriceBound(format!(
+                "Prices must be finite numbers, got min={} max={}",
+                min_price, max_price
+            )));
+        }
+        if min_price <= 0.0 {
+            return Err(ConfigError::PriceBound(format!(
+                "Minimum price must be positive, got: {}",
+                min_price
+            )));
+        }
+        if max_price <= min_price {
+            return Err(ConfigError::PriceBound(format!(
+                "Maximum price ({}) must be greater than minimum price ({})",
+                max_price, min_price
+            )));
+        }
+        Ok(Self {
+            min_price,
+            max_price,
+        })
+    }
+}
+
+impl Default for PriceBounds {
+    /// Create default price bounds
+    fn default() -> Self {
+        Self {
+            min_price: DEFAULT_MIN_PRICE,
+            max_price: DEFAULT_MAX_PRICE,
+        }
+    }
+}
+
+/// Validated trade sizing limits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizingLimits {
+    /// Minimum trade notional in quote currency
+    pub min_notional: f64,
+    /// Maximum trade notional in quote currency
+    pub max_notional: Option<f64>,
+    /// Base tokens available to sell
+    pub max_base_inventory: Option<f64>,
+    /// Quote currency available to buy with
+    pub max_quote_inventory: Option<f64>,
+    /// Linear Binance price impact in basis points per base token
+    pub cex_impact_bps: Option<f64>,
+}
+
+impl SizingLimits {
+    pub fn new(
+        min_notional: f64,
+        max_notional: Option<f64>,
+        max_base_inventory: Option<f64>,
+        max_quote_inventory: Option<f64>,
+        cex_impact_bps: Option<f64>,
+    ) -> Result<Self, ConfigError> {
+        let values = [
+            ("min notional", Some(min_notional)),
+            ("max notional", max_notional),
+            ("max base inventory", max_base_inventory),
+            ("max quote inventory", max_quote_inventory),
+            ("CEX impact", cex_impact_bps),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(ConfigError::Sizing(format!(
+                        "{} must be a non-negative number, got: {}",
+                        name, value
+                    )));
+                }
+            }
+        }
+        if let Some(max_notional) = max_notional {
+            if max_notional < min_notional {
+                return Err(ConfigError::Sizing(format!(
+                    "Maximum notional ({}) must not be below minimum notional ({})",
+                    max_notional, min_notional
+                )));
+            }
+        }
+
+        Ok(Self {
+            min_notional,
+            max_notional,
+            max_base_inventory,
+            max_quote_inventory,
+            cex_impact_bps,
+        })
+    }
+}
+
+/// Validated profit threshold percentage
+#[derive(Debug, Clone, Copy)]
+pub struct ProfitThreshold(f64);
+
+impl ProfitThreshold {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Create new ProfitThreshold for testing
+    #[cfg(test)]
+    pub fn new(value: f64) -> Result<Self, ConfigError> {
+        if (0.0..=100.0).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err(ConfigError::Threshold(value))
+        }
+    }
+}
+
+/// Validated maximum price age in milliseconds
+#[derive(Debug, Clone, Copy)]
+pub struct MaxPriceAge(u64);
+
+impl MaxPriceAge {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Create new MaxPriceAge for testing
+    #[cfg(test)]
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+/// API key configuration for RPC providers
+#[derive(Clone)]
+pub struct ApiKeyConfig {
+    pub helius: Option<String>,
+    pub alchemy: Option<String>,
+    pub genesisgo: Option<String>,
+}
+
+impl ApiKeyConfig {
+    /// Create from raw configuration
+    pub fn from_raw(raw: &RawConfig) -> Self {
+        Self {
+            helius: raw.helius_api_key.clone(),
+            alchemy: raw.alchemy_api_key.clone(),
+            genesisgo: raw.genesisgo_api_key.clone(),
+        }
+    }
+
+    /// Check if any API keys are configured
+    pub fn has_keys(&self) -> bool {
+        self.helius.is_some() || self.alchemy.is_some() || self.genesisgo.is_some()
+    }
+}
+
+impl std::fmt::Debug for ApiKeyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyConfig")
+            .field("helius", &self.helius.as_ref().map(|_| "***"))
+            .field("alchemy", &self.alchemy.as_ref().map(|_| "***"))
+            .field("genesisgo", &self.genesisgo.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+/// RPC provider configuration with failover support
+#[derive(Clone)]
+#[allow(dead_code)]
+pub struct RpcProvider {
+    pub name: String,
+    pub websocket_url: Url,
+    pub priority: u8,
+    pub provider_type: RpcProviderType,
+}
+
+/// Types of RPC providers for different authentication methods
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcProviderType {
+    Helius,
+    #[allow(dead_code)] // Kept for backward compatibility in tests
+    QuickNode,
+    Alchemy,
+    GenesisGo,
+    Custom,
+    Public,
+}
+
+impl Config {
+    /// Parse and validate raw configuration, accumulating all errors
+    ///
+    /// Each error names the flag or config file key it concerns.
+    pub fn new(raw: &RawConfig) -> Result<Self, ConfigErrors> {
+        let mut errors = Vec::new();
+
+        // Register user-defined pairs before resolving names; flags replace file
+        // definitions of the same name
+        for (index, definition) in raw.file.pair_definitions.iter().enumerate() {
+            let result = PairSpec::try_from(definition.clone()).and_then(|spec| {
+                if raw.define_pair.iter().any(|flag| flag.name == spec.name) {
+                    return Ok(());
+                }
+                TradingPair::define(spec).map(|_| ())
+            });
+            if let Err(e) = result {
+                errors.push(
+                    ConfigError::Pair(e).at(raw.file_key(&format!("pair_definitions[{}]", index))),
+                );
+            }
+        }
+        for spec in &raw.define_pair {
+            if let Err(e) = TradingPair::define(spec.clone()) {
+                errors.push(ConfigError::Pair(e).at(raw.key("define_pair", "pair_definitions")));
+            }
+        }
+
+        // Deduplicate pairs, keeping the order they were given in
+        let mut pairs = Vec::new();
+        for name in &raw.pair {
+            match name.parse::<TradingPair>() {
+                Ok(pair) if !pairs.contains(&pair) => pairs.push(pair),
+                Ok(_) => {}
+                Err(e) => errors.push(ConfigError::Pair(e).at(raw.key("pair", "pairs"))),
+            }
+        }
+        if raw.pair.is_empty() {
+            errors.push(ConfigError::NoPairs);
+        }
+
+        // Gas and transfer fees are paid in SOL, valued on pairs without SOL at the
+        // price of a monitored SOL pair with the same quote currency
+        for pair in &pairs {
+            let sol_priced = pair.base_is_sol()
+                || pair.quote_is_sol()
+                || pairs.iter().any(|other| {
+                    other.base_is_sol() && other.spec().quote_mint == pair.spec().quote_mint
+                });
+            if !sol_priced {
+                errors.push(
+                    ConfigError::Invalid(format!(
+                        "pair '{}' needs a SOL/{} pair monitored as well to value fees paid in SOL",
+                        pair.name(),
+                        pair.spec().quote_symbol
+                    ))
+                    .at(raw.key("pair", "pairs")),
+                );
+            }
+        }
+
+        // Validate threshold
+        let threshold = if raw.threshold >= 0.0 && raw.threshold <= 100.0 {
+            Some(ProfitThreshold(raw.threshold))
+        } else {
+            errors
+                .push(ConfigError::Threshold(raw.threshold).at(raw.key("threshold", "threshold")));
+            None
+        };
+
+        // Validate max price age (reasonable range: 100ms to 60s)
+        let max_price_age_ms = if raw.max_price_age_ms >= 100 && raw.max_price_age_ms <= 60000 {
+            Some(MaxPriceAge(raw.max_price_age_ms))
+        } else {
+            errors.push(
+                ConfigError::MaxPriceAge(raw.max_price_age_ms)
+                    .at(raw.key("max_price_age_ms", "max_price_age_ms")),
+            );
+            None
+        };
+
+        // Validate max price skew (same range as the max price age)
+        if !(1..=60000).contains(&raw.max_price_skew_ms) {
+            errors.push(
+                ConfigError::MaxPriceSkew(raw.max_price_skew_ms)
+                    .at(raw.key("max_price_skew_ms", "max_price_skew_ms")),
+            );
+        }
+
+        // Validate price bounds
+        let price_bounds = match PriceBounds::new(raw.min_price, raw.max_price) {
+            Ok(bounds) => Some(bounds),
+            Err(e) => {
+                let key = if raw.min_price.is_finite() && raw.min_price > 0.0 {
+                    raw.key("max_price", "max_price")
+                } else {
+                    raw.key("min_price", "min_price")
+                };
+                errors.push(e.at(key));
+                None
+            }
+        };
+
+        // Validate trade sizing limits
+        let sizing_values = [
+            ("min_notional", Some(raw.min_notional)),
+            ("max_notional", raw.max_notional),
+            ("max_base_inventory", raw.max_base_inventory),
+            ("max_quote_inventory", raw.max_quote_inventory),
+            ("cex_impact_bps", raw.cex_impact_bps),
+        ];
+        let sizing_errors = errors.len();
+        for (flag, value) in sizing_values {
+            if let Some(value) = value.filter(|v| !v.is_finite() || *v < 0.0) {
+                errors.push(
+                    ConfigError::Sizing(format!("must be a non-negative number, got: {}", value))
+                        .at(raw.key(flag, &format!("sizing.{}", flag))),
+                );
+            }
+        }
+        let sizing = if errors.len() > sizing_errors {
+            None
+        } else {
+            match SizingLimits::new(
+                raw.min_notional,
+                raw.max_notional,
+                raw.max_base_inventory,
+                raw.max_quote_inventory,
+                raw.cex_impact_bps,
+            ) {
+                Ok(sizing) => Some(sizing),
+                Err(e) => {
+                    errors.push(e.at(raw.key("max_notional", "sizing.max_notional")));
+                    None
+                }
+            }
+        };
+
+        // Settings that only exist in the config file
+        let file = &raw.file;
+        let fees = raw.fees();
+        for (key, value, max) in [
+            ("fees.binance_spot", fees.binance_spot_fee, 100.0),
+            ("fees.solana_dex", fees.solana_dex_fee, 100.0),
+            ("fees.solana_gas", fees.solana_gas_fee, 1.0),
+            ("fees.transfer", fees.transfer_fee, 100.0),
+        ] {
+            if !(0.0..=max).contains(&value) {
+                errors.push(
+                    ConfigError::Invalid(format!("must be between 0 and {}, got: {}", max, value))
+                        .at(raw.file_key(key)),
+                );
+            }
+        }
+
+        let default_trade_amount = file
+            .sizing
+            .default_trade_amount
+            .unwrap_or(DEFAULT_TRADE_AMOUNT);
+        if !(default_trade_amount.is_finite() && default_trade_amount > 0.0) {
+            errors.push(
+                ConfigError::Invalid(format!("must be positive, got: {}", default_trade_amount))
+                    .at(raw.file_key("sizing.default_trade_amount")),
+            );
+        }
+
+        let mut intervals = Vec::new();
+        for (key, value, default) in [
+            (
+                "check_interval_ms",
+                file.check_interval_ms.map(Duration::from_millis),
+                DEFAULT_CHECK_INTERVAL,
+            ),
+            (
+                "monitor_interval_secs",
+                file.monitor_interval_secs.map(Duration::from_secs),
+                DEFAULT_MONITOR_INTERVAL,
+            ),
+            (
+                "metrics_window_secs",
+                file.metrics_window_secs.map(Duration::from_secs),
+                DEFAULT_METRICS_WINDOW,
+            ),
+        ] {
+            let interval = value.unwrap_or(default);
+            if interval.is_zero() {
+                errors.push(
+                    ConfigError::Invalid("must be greater than zero".to_string())
+                        .at(raw.file_key(key)),
+                );
+            }
+            intervals.push(interval);
+        }
+
+        let reconnect = Self::create_reconnect_config(&file.reconnect);
+        if let Err(message) = reconnect.validate() {
+            errors.push(ConfigError::Invalid(message).at(raw.file_key("reconnect")));
+        }
+
+        let webhook = raw.webhook_url.clone().map(|url| {
+            let section = &file.webhook;
+            WebhookConfig::new(url)
+                .with_headers(section.headers.clone().into_iter().collect())
+                .with_timeout(
+                    section
+                        .timeout_ms
+                        .map_or(DEFAULT_WEBHOOK_TIMEOUT, Duration::from_millis),
+                )
+                .with_retries(
+                    section.max_retries.unwrap_or(DEFAULT_WEBHOOK_RETRIES),
+                    section
+                        .retry_delay_ms
+                        .map_or(DEFAULT_WEBHOOK_RETRY_DELAY, Duration::from_millis),
+                )
+                .with_queue_size(section.queue_size.unwrap_or(DEFAULT_WEBHOOK_QUEUE_SIZE))
+        });
+        if let Some(Err(e)) = webhook.as_ref().map(WebhookConfig::validate) {
+            errors.push(ConfigError::Invalid(e.to_string()).at(raw.file_key("webhook")));
+        }
+
+        // Create API key configuration
+        let api_keys = ApiKeyConfig::from_raw(raw);
+
+        // Create RPC providers with API key support
+        let rpc_providers = if raw.rpc_url.is_none() && !file.providers.is_empty() {
+            file.providers
+                .iter()
+                .zip(1..)
+                .map(|(provider, priority)| RpcProvider {
+                    name: provider.name.clone(),
+                    websocket_url: provider.url.clone(),
+                    priority,
+                    provider_type: RpcProviderType::Custom,
+                })
+                .collect()
+        } else {
+            Self::create_rpc_providers(&raw.rpc_url, &api_keys)
+        };
+
+        if !(raw.replay_speed.is_finite() && raw.replay_speed > 0.0) {
+            errors.push(
+                ConfigError::Invalid(format!("must be positive, got: {}", raw.replay_speed))
+                    .at("--replay-speed".to_string()),
+            );
+        }
+
+        let paper = raw.paper_trade.then(|| PaperConfig {
+            latency: Duration::from_millis(raw.paper_latency_ms),
+        });
+
+        let mut non_negative = |key: String, value: f64| {
+            if !(value.is_finite() && value >= 0.0) {
+                errors.push(
+                    ConfigError::Invalid(format!("must be a non-negative number, got: {}", value))
+                        .at(raw.file_key(&key)),
+                );
+            }
+            value
+        };
+        let inventory = file.inventory.as_ref().map(|section| {
+            let mut balance = |venue: &str, amounts: &BTreeMap<String, f64>| {
+                let mut balance = Balance::default();
+                for (asset, &amount) in amounts {
+                    let asset = asset.to_uppercase();
+                    let amount = non_negative(format!("inventory.{}.{}", venue, asset), amount);
+                    balance.add(&asset, amount);
+                }
+                balance
+            };
+            let balances = VenueBalances {
+                binance: balance("binance", &section.binance),
+                solana: balance("solana", &section.solana),
+            };
+            let rebalancing = &file.rebalancing;
+            let defaults = RebalancingCosts::default();
+            let mut binance_withdrawal = defaults.binance_withdrawal.clone();
+            for (asset, &fee) in &rebalancing.binance_withdrawal {
+                let asset = asset.to_uppercase();
+                let fee = non_negative(format!("rebalancing.binance_withdrawal.{}", asset), fee);
+                binance_withdrawal.insert(asset, fee);
+            }
+            InventoryConfig {
+                balances,
+                rebalancing: RebalancingCosts {
+                    binance_withdrawal,
+                    solana_transfer_fee: non_negative(
+                        "rebalancing.solana_transfer_fee".to_string(),
+                        rebalancing
+                            .solana_transfer_fee
+                            .unwrap_or(defaults.solana_transfer_fee),
+                    ),
+                    transfer_time: rebalancing
+                        .transfer_time_secs
+                        .map_or(defaults.transfer_time, Duration::from_secs),
+                },
+            }
+        });
+        // Every traded asset may need withdrawing from Binance to rebalance
+        if let Some(inventory) = &inventory {
+            let fees = &inventory.rebalancing.binance_withdrawal;
+            let mut missing = Vec::new();
+            for pair in &pairs {
+                for asset in [pair.base_symbol(), pair.quote_symbol()] {
+                    if !fees.contains_key(asset) && !missing.contains(&asset) {
+                        missing.push(asset);
+                        errors.push(
+                            ConfigError::Invalid(format!(
+                                "missing Binance withdrawal fee for {}, traded by pair '{}'",
+                                asset,
+                                pair.name()
+                            ))
+                            .at(raw.file_key(&format!("rebalancing.binance_withdrawal.{}", asset))),
+                        );
+                    }
+                }
+            }
+        }
+
+        // Return errors if any, otherwise return valid config
+        if !errors.is_empty() {
+            return Err(ConfigErrors { errors });
+        }
+
+        Ok(Config {
+            pairs,
+            threshold: threshold.unwrap(), // Safe because we checked for errors above
+            max_price_age_ms: max_price_age_ms.unwrap(), // Safe because we checked for errors above
+            max_price_skew: Duration::from_millis(raw.max_price_skew_ms),
+            rpc_providers,
+            output_format: raw.output_format,
+            price_bounds: price_bounds.unwrap(), // Safe because we checked for errors above
+            api_keys,
+            enable_performance_monitor: raw.enable_performance_monitor,
+            metrics_addr: raw.metrics_addr,
+            webhook,
+            sizing: sizing.unwrap(), // Safe because we checked for errors above
+            fees,
+            default_trade_amount,
+            max_ticks_crossed: file
+                .sizing
+                .max_ticks_crossed
+                .unwrap_or(DEFAULT_MAX_TICKS_CROSSED),
+            check_interval: intervals[0],
+            debounce: Duration::from_millis(raw.debounce_ms),
+            monitor_interval: intervals[1],
+            metrics_window: intervals[2],
+            reconnect,
+            record: raw.record.clone(),
+            replay: raw.replay.clone(),
+            replay_speed: raw.replay_speed,
+            paper,
+            inventory,
+        })
+    }
+
+    /// Build the reconnect policy from the `[reconnect]` section over the defaults
+    fn create_reconnect_config(section: &file::ReconnectSection) -> ReconnectConfig {
+        let default = ReconnectConfig::default();
+        let mut config = ReconnectConfig::new(
+            section
+                .initial_delay_ms
+                .map_or(default.initial_delay, Duration::from_millis),
+            section
+                .max_delay_ms
+                .map_or(default.max_delay, Duration::from_millis),
+            section
+                .backoff_multiplier
+                .unwrap_or(default.backoff_multiplier),
+        );
+        if let Some(attempts) = section.max_attempts {
+            config = config.with_max_attempts((attempts > 0).then_some(attempts));
+        }
+        if let Some(secs) = section.max_total_duration_secs {
+            config = config.with_max_total_duration((secs > 0).then(|| Duration::from_secs(secs)));
+        }
+        config.with_jitter(section.jitter.unwrap_or(default.jitter))
+    }
+
+    /// First configured trading pair
+    pub fn primary_pair(&self) -> TradingPair {
+        self.pairs[0]
+    }
+
+    /// Create RPC providers based on configuration with API key support
+    fn create_rpc_providers(custom_url: &Option<Url>, api_keys: &ApiKeyConfig) -> Vec<RpcProvider> {
+        if let Some(ref url) = custom_url {
+            vec![RpcProvider {
+                name: "Custom".to_string(),
+                websocket_url: url.clone(),
+                priority: 1,
+                provider_type: RpcProviderType::Custom,
+            }]
+        } else if api_keys.has_keys() {
+            Self::get_authenticated_providers(api_keys)
+        } else {
+            Self::get_default_providers()
+        }
+    }
+
+    /// Get authenticated RPC providers using API keys
+    fn get_authenticated_providers(api_keys: &ApiKeyConfig) -> Vec<RpcProvider> {
+        let mut providers = Vec::new();
+        let mut priority = 1;
+
+        // Helius (highest priority if available)
+        if let Some(ref api_key) = api_keys.helius {
+            if let Ok(url) = format!("wss://mainnet.helius-rpc.com/?api-key={}", api_key).parse() {
+                providers.push(RpcProvider {
+                    name: "Helius (Authenticated)".to_string(),
+                    websocket_url: url,
+                    priority,
+                    provider_type: RpcProviderType::Helius,
+                });
+                priority += 1;
+            }
+        }
+
+        // QuickNode requires a full endpoint URL. Do not synthesize from tokens.
+        // Users should pass --rpc-url wss://<their-quicknode-endpoint>/<token>/ to use QuickNode.
+
+        // Alchemy
+        if let Some(ref api_key) = api_keys.alchemy {
+            if let Ok(url) = format!("wss://solana-mainnet.g.alchemy.com/v2/{}", api_key).parse() {
+                providers.push(RpcProvider {
+                    name: "Alchemy (Authenticated)".to_string(),
+                    websocket_url: url,
+                    priority,
+                    provider_type: RpcProviderType::Alchemy,
+                });
+                priority += 1;
+            }
+        }
+
+        // GenesisGo (Triton/Shadow)
+        if let Some(ref api_key) = api_keys.genesisgo {
+            if let Ok(url) = format!("wss://triton.genesysgo.net/{}", api_key).parse() {
+                providers.push(RpcProvider {
+                    name: "GenesisGo Triton (Authenticated)".to_string(),
+                    websocket_url: url,
+                    priority,
+                    provider_type: RpcProviderType::GenesisGo,
+                });
+            }
+        }
+
+        // Fallback to public providers if no authenticated providers were created
+        if providers.is_empty() {
+            providers = Self::get_default_providers();
+        }
+
+        providers
+    }
+
+    /// Get default public RPC providers as fallback
+    fn get_default_providers() -> Vec<RpcProvider> {
+        vec![
+            RpcProvider {
+                name: "Mainnet Beta (Primary)".to_string(),
+                websocket_url: "wss://api.mainnet-beta.solana.com/"
+                    .parse()
+                    .expect("Invalid default RPC URL"),
+                priority: 1,
+                provider_type: RpcProviderType::Public,
+            },
+            RpcProvider {
+                name: "Ankr Mainnet (Backup)".to_string(),
+                websocket_url: "wss://rpc.ankr.com/solana/ws"
+                    .parse()
+                    .expect("Invalid default RPC URL"),
+                priority: 2,
+                provider_type: RpcProviderType::Public,
+            },
+        ]
+    }
+}
+
+impl std::fmt::Debug for RpcProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn redact_url(url: &Url, provider_type: &RpcProviderType) -> String {
+            let mut s = url.as_str().to_string();
+            // Redact api-key query parameters
+            if let Some(idx) = s.find("api-key=") {
+                let start = idx + "api-key=".len();
+                let end = s[start..].find('&').map(|i| start + i).unwrap_or(s.len());
+                s.replace_range(start..end, "***");
+                return s;
+            }
+            // Redact path segment after /v2/
+            if s.contains("/v2/") {
+                if let Some(idx) = s.find("/v2/") {
+                    let start = idx + "/v2/".len();
+                    let end = s[start..].find('/').map(|i| start + i).unwrap_or(s.len());
+                    s.replace_range(start..end, "***");
+                    return s;
+                }
+            }
+            // For QuickNode/GenesisGo, redact last path segment
+            match provider_type {
+                RpcProviderType::QuickNode | RpcProviderType::GenesisGo => {
+                    if let Some(mut path_segments) = url.path_segments() {
+                        if let Some(last) = path_segments.next_back() {
+                            return s.replace(last, "***");
+                        }
+                    }
+                    s
+                }
+                _ => s,
+            }
+        }
+        f.debug_struct("RpcProvider")
+            .field("name", &self.name)
+            .field(
+                "websocket_url",
+                &redact_url(&self.websocket_url, &self.provider_type),
+            )
+            .field("priority", &self.priority)
+            .field("provider_type", &self.provider_type)
+            .finish()
+    }
+}
+
+/// Collection of configuration validation errors
+#[derive(Debug, thiserror::Error)]
+#[error("Configuration validation failed:\n{}",
+    .errors.iter()
+        .enumerate()
+        .map(|(i, e)| format!("  {}. {}", i + 1, e))
+        .collect::<Vec<_>>()
+        .join("\n")
+)]
+pub struct ConfigErrors {
+    pub errors: Vec<ConfigError>,
+}
+
+/// Individual configuration validation error
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Invalid threshold: {0}. Must be between 0.0 and 100.0")]
+    Threshold(f64),
+    #[error("Invalid max price age: {0}ms. Must be between 100 and 60000 milliseconds")]
+    MaxPriceAge(u64),
+    #[error("Invalid max price skew: {0}ms. Must be between 1 and 60000 milliseconds")]
+    MaxPriceSkew(u64),
+    #[error("Invalid price bound: {0}")]
+    PriceBound(String),
+    #[error("Invalid sizing limit: {0}")]
+    Sizing(String),
+    #[error("At least one trading pair must be given")]
+    NoPairs,
+    #[error("Invalid trading pair: {0}")]
+    Pair(#[from] PairError),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Command line error: {0}")]
+    Args(String),
+    #[error("Config file error: {0}")]
+    File(String),
+    #[error("{key}: {source}")]
+    Key {
+        key: String,
+        source: Box<ConfigError>,
+    },
+}
+
+impl ConfigError {
+    /// Invalid command-line arguments, without clap's `error: ` prefix
+    fn args(error: clap::Error) -> Self {
+        let message = error.to_string();
+        ConfigError::Args(message.trim_start_matches("error: ").trim_end().to_string())
+    }
+
+    /// Attach the flag or config file key the error concerns
+    pub fn at(self, key: String) -> Self {
+        ConfigError::Key {
+            key,
+            source: Box::new(self),
+        }
+    }
+}
+
+impl From<ConfigError> for ConfigErrors {
+    fn from(error: ConfigError) -> Self {
+        Self {
+            errors: vec![error],
+        }
+    }
+}
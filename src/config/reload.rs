@@ -0,0 +1,398 @@
+use super::{Config, ConfigErrors, PriceBounds, ProfitThreshold, RawConfig};
+use crate::arbitrage::calculator::TradingFees;
+use crate::output::{OutputFormatter, OutputSink};
+use log::{error, info, warn};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+/// How often the config file is checked for changes
+pub const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings that can change without a restart
+#[derive(Debug, Clone)]
+pub struct LiveSettings {
+    pub threshold: ProfitThreshold,
+    pub fees: TradingFees,
+    /// Bounds for pairs that do not define their own
+    pub price_bounds: PriceBounds,
+}
+
+impl LiveSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            threshold: config.threshold,
+            fees: config.fees.clone(),
+            price_bounds: config.price_bounds,
+        }
+    }
+
+    /// Settings in `raw` that differ from these, whether or not they are valid
+    pub fn changes(&self, raw: &RawConfig) -> Vec<SettingChange> {
+        let fees = raw.fees();
+        [
+            ("threshold", self.threshold.value(), raw.threshold),
+            (
+                "fees.binance_spot",
+                self.fees.binance_spot_fee,
+                fees.binance_spot_fee,
+            ),
+            (
+                "fees.solana_dex",
+                self.fees.solana_dex_fee,
+                fees.solana_dex_fee,
+            ),
+            (
+                "fees.solana_gas",
+                self.fees.solana_gas_fee,
+                fees.solana_gas_fee,
+            ),
+            ("fees.transfer", self.fees.transfer_fee, fees.transfer_fee),
+            ("min_price", self.price_bounds.min_price, raw.min_price),
+            ("max_price", self.price_bounds.max_price, raw.max_price),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(key, old, new)| SettingChange { key, old, new })
+        .collect()
+    }
+}
+
+/// A live setting changed by a reload
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingChange {
+    pub key: &'static str,
+    pub old: f64,
+    pub new: f64,
+}
+
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.key, self.old, self.new)
+    }
+}
+
+/// A reload that failed validation; the previous settings stay in effect
+#[derive(Debug, thiserror::Error)]
+#[error("{errors}")]
+pub struct ReloadRejected {
+    pub errors: ConfigErrors,
+    /// What the reload would have changed
+    pub changes: Vec<SettingChange>,
+}
+
+/// Re-reads the configuration and publishes changed live settings
+///
+/// A reload parses the original arguments again, so the config file is re-read while
+/// flags and environment variables keep overriding it.
+pub struct ConfigReloader {
+    args: Vec<OsString>,
+    config_path: Option<PathBuf>,
+    current: Config,
+    settings: watch::Sender<LiveSettings>,
+    /// Receive config-changed events besides the console output
+    sinks: Vec<Arc<dyn OutputSink>>,
+}
+
+impl ConfigReloader {
+    /// Reloader for a config built from `args`, with a receiver for its live settings
+    pub fn new(
+        args: Vec<OsString>,
+        raw: &RawConfig,
+        config: Config,
+    ) -> (Self, watch::Receiver<LiveSettings>) {
+        let (settings, receiver) = watch::channel(LiveSettings::from_config(&config));
+        let reloader = Self {
+            args,
+            config_path: raw.config.clone(),
+            current: config,
+            settings,
+            sinks: Vec::new(),
+        };
+        (reloader, receiver)
+    }
+
+    /// Send config-changed events to `sinks` as well
+    pub fn with_sinks(mut self, sinks: Vec<Arc<dyn OutputSink>>) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// Reload now, returning the live settings that changed
+    ///
+    /// Settings that need a restart are logged and otherwise ignored.
+    pub fn reload(&mut self) -> Result<Vec<SettingChange>, ReloadRejected> {
+        let live = LiveSettings::from_config(&self.current);
+        let raw = RawConfig::try_load_from(&self.args).map_err(|errors| ReloadRejected {
+            errors,
+            changes: Vec::new(),
+        })?;
+        let changes = live.changes(&raw);
+        let config = Config::new(&raw).map_err(|errors| ReloadRejected {
+            errors,
+            changes: changes.clone(),
+        })?;
+
+        for key in self.restart_required(&config) {
+            warn!("Ignoring reloaded {}: changing it requires a restart", key);
+        }
+        if !changes.is_empty() {
+            self.current.threshold = config.threshold;
+            self.current.fees = config.fees;
+            self.current.price_bounds = config.price_bounds;
+            self.settings
+                .send_replace(LiveSettings::from_config(&self.current));
+        }
+        Ok(changes)
+    }
+
+    /// Settings in `config` that differ from the running ones but are not live
+    fn restart_required(&self, config: &Config) -> Vec<&'static str> {
+        let current = &self.current;
+        [
+            ("pairs", current.pairs != config.pairs),
+            (
+                "max_price_age_ms",
+                current.max_price_age_ms.value() != config.max_price_age_ms.value(),
+            ),
+            (
+                "max_price_skew_ms",
+                current.max_price_skew != config.max_price_skew,
+            ),
+            (
+                "output.format",
+                current.output_format.to_string() != config.output_format.to_string(),
+            ),
+            (
+                "check_interval_ms",
+                current.check_interval != config.check_interval,
+            ),
+            ("debounce_ms", current.debounce != config.debounce),
+            ("metrics_addr", current.metrics_addr != config.metrics_addr),
+            ("webhook", current.webhook != config.webhook),
+            (
+                "metrics_window_secs",
+                current.metrics_window != config.metrics_window,
+            ),
+            (
+                "monitor_interval_secs",
+                current.monitor_interval != config.monitor_interval,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(key, _)| key)
+        .collect()
+    }
+
+    /// Reload on SIGHUP and whenever the config file is modified, until the task is aborted
+    ///
+    /// Accepted changes are written to the output and the sinks as a config-changed
+    /// event; rejected reloads are logged with the changes they attempted.
+    pub async fn run(mut self, formatter: OutputFormatter) {
+        let mut hangup = Hangup::new();
+        let mut poll = tokio::time::interval(RELOAD_POLL_INTERVAL);
+        let mut modified = self.modified();
+
+        loop {
+            let trigger = tokio::select! {
+                _ = poll.tick() => {
+                    let now = self.modified();
+                    if now == modified {
+                        continue;
+                    }
+                    modified = now;
+                    "config file change"
+                }
+                _ = hangup.recv() => "SIGHUP",
+            };
+
+            info!("Reloading configuration after {}", trigger);
+            self.reload_and_announce(&formatter);
+        }
+    }
+
+    /// Reload, announcing accepted changes and logging rejected ones
+    fn reload_and_announce(&mut self, formatter: &OutputFormatter) {
+        match self.reload() {
+            Ok(changes) if changes.is_empty() => {
+                info!("Configuration reloaded, live settings unchanged");
+            }
+            Ok(changes) => {
+                println!("{}", formatter.format_config_change(&changes));
+                println!();
+                for sink in &self.sinks {
+                    sink.send_config_change(&changes);
+                }
+            }
+            Err(rejected) => {
+                error!(
+                    "Rejected configuration reload, keeping previous settings: {}",
+                    rejected.errors
+                );
+                for change in &rejected.changes {
+                    error!("  rejected {}", change);
+                }
+            }
+        }
+    }
+
+    /// Modification time of the config file, if there is one
+    fn modified(&self) -> Option<SystemTime> {
+        let path = self.config_path.as_ref()?;
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+/// SIGHUP listener; never fires where the signal is unavailable
+struct Hangup {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangup {
+    fn new() -> Self {
+        Self {
+            #[cfg(unix)]
+            signal: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+                .map_err(|e| warn!("Cannot listen for SIGHUP: {}", e))
+                .ok(),
+        }
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = self.signal.as_mut() {
+            if signal.recv().await.is_some() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &std::path::Path, threshold: &str, binance_fee: &str) {
+        let contents = format!(
+            "pairs = [\"sol-usdt\"]\nthreshold = {}\n\n[fees]\nbinance_spot = {}\n",
+            threshold, binance_fee
+        );
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn reloader(path: &std::path::Path) -> (ConfigReloader, watch::Receiver<LiveSettings>) {
+        let args: Vec<OsString> = vec![
+            "solana-arbitrage-watcher".into(),
+            "--config".into(),
+            path.into(),
+            "--max-price".into(),
+            "500".into(),
+        ];
+        let raw = RawConfig::try_load_from(&args).unwrap();
+        let config = Config::new(&raw).unwrap();
+        ConfigReloader::new(args, &raw, config)
+    }
+
+    #[test]
+    fn test_reload_publishes_changed_settings() {
+        let path = std::env::temp_dir().join(format!("arb-reload-{}.toml", std::process::id()));
+        write(&path, "0.5", "0.1");
+        let (mut reloader, mut settings) = reloader(&path);
+
+        assert_eq!(reloader.reload().unwrap(), Vec::new());
+        assert!(!settings.has_changed().unwrap());
+
+        write(&path, "0.8", "0.075");
+        let changes = reloader.reload().unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                SettingChange {
+                    key: "threshold",
+                    old: 0.5,
+                    new: 0.8
+                },
+                SettingChange {
+                    key: "fees.binance_spot",
+                    old: 0.1,
+                    new: 0.075
+                },
+            ]
+        );
+        assert!(settings.has_changed().unwrap());
+        let live = settings.borrow_and_update().clone();
+        assert_eq!(live.threshold.value(), 0.8);
+        assert_eq!(live.fees.binance_spot_fee, 0.075);
+        // The command line still overrides the file
+        assert_eq!(live.price_bounds.max_price, 500.0);
+
+        // Invalid values are rejected and the previous settings kept
+        write(&path, "0.9", "-1");
+        let rejected = reloader.reload().unwrap_err();
+        assert!(rejected.errors.to_string().contains("fees.binance_spot"));
+        assert_eq!(rejected.changes.len(), 2);
+        assert!(!settings.has_changed().unwrap());
+        assert_eq!(reloader.current.threshold.value(), 0.8);
+
+        std::fs::write(&path, "threshold = [").unwrap();
+        assert!(reloader.reload().is_err());
+        assert_eq!(reloader.current.fees.binance_spot_fee, 0.075);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_accepted_changes_are_sent_to_sinks() {
+        use crate::arbitrage::calculator::ArbitrageOpportunity;
+        use crate::output::OutputFormat;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<Vec<SettingChange>>>);
+
+        impl OutputSink for Recorder {
+            fn name(&self) -> &str {
+                "recorder"
+            }
+
+            fn send_opportunity(&self, _opportunity: &ArbitrageOpportunity) {}
+
+            fn send_config_change(&self, changes: &[SettingChange]) {
+                self.0.lock().unwrap().push(changes.to_vec());
+            }
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("arb-reload-sinks-{}.toml", std::process::id()));
+        write(&path, "0.5", "0.1");
+        let recorder = Arc::new(Recorder::default());
+        let (reloader, _settings) = reloader(&path);
+        let mut reloader = reloader.with_sinks(vec![recorder.clone()]);
+        let formatter = OutputFormatter::new(OutputFormat::Compact);
+
+        // Nothing changed, or the reload was rejected: nothing to announce
+        reloader.reload_and_announce(&formatter);
+        write(&path, "0.9", "-1");
+        reloader.reload_and_announce(&formatter);
+        assert!(recorder.0.lock().unwrap().is_empty());
+
+        write(&path, "0.8", "0.1");
+        reloader.reload_and_announce(&formatter);
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![vec![SettingChange {
+                key: "threshold",
+                old: 0.5,
+                new: 0.8
+            }]]
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+}
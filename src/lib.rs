@@ -0,0 +1,13 @@
+//! Watches Solana DEX and Binance prices for cross-venue arbitrage opportunities
+
+pub mod arbitrage;
+pub mod backtest;
+pub mod config;
+pub mod output;
+pub mod pair;
+pub mod performance;
+pub mod price;
+#[cfg(test)]
+mod test_utils;
+pub mod util;
+pub mod websocket;
@@ -1,12 +1,18 @@
-use crate::arbitrage::calculator::ArbitrageOpportunity;
+use crate::arbitrage::calculator::{ArbitrageOpportunity, LegFill};
+use crate::arbitrage::inventory::Balance;
+use crate::arbitrage::paper::{FillStatus, PaperSummary, PaperTrade};
+use crate::backtest::BacktestReport;
+use crate::config::reload::SettingChange;
 use crate::config::TradingPair;
+use crate::performance::metrics::format_rejections;
 use crate::price::ValidatedPricePair;
 use crate::util::{format_price_source, format_trading_pair, round_to_precision};
 use serde_json::json;
 use std::fmt;
 
 /// Output format options for displaying arbitrage data
-#[derive(Debug, Clone, Copy, clap::ValueEnum, Default)]
+#[derive(Debug, Clone, Copy, clap::ValueEnum, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// Human-readable table format
     #[default]
@@ -124,6 +130,22 @@ impl OutputFormatter {
             prec = self.precision
         ));
 
+        if let Some(capacity) = opportunity.dex_capacity {
+            output.push_str(&format!(
+                "DEX Depth:        {:.prec$} {}\n",
+                capacity,
+                format_trading_pair(opportunity.trading_pair)
+                    .split('/')
+                    .next()
+                    .unwrap_or("SOL"),
+                prec = self.precision
+            ));
+        }
+
+        if let Some(impact) = opportunity.dex_price_impact {
+            output.push_str(&format!("DEX Impact:       {:.2}%\n", impact));
+        }
+
         if self.show_timestamps {
             output.push_str(&format!(
                 "Detected at:      {}\n",
@@ -136,7 +158,7 @@ impl OutputFormatter {
     }
 
     /// Format arbitrage opportunity as JSON
-    fn format_opportunity_json(&self, opportunity: &ArbitrageOpportunity) -> String {
+    pub fn format_opportunity_json(&self, opportunity: &ArbitrageOpportunity) -> String {
         let mut json_obj = json!({
             "type": "arbitrage_opportunity",
             "trading_pair": format_trading_pair(opportunity.trading_pair).to_lowercase(),
@@ -152,6 +174,24 @@ impl OutputFormatter {
             "estimated_total_profit": round_to_precision(opportunity.estimated_total_profit, self.precision),
         });
 
+        if let (Some(capacity), serde_json::Value::Object(ref mut map)) =
+            (opportunity.dex_capacity, &mut json_obj)
+        {
+            map.insert(
+                "dex_capacity".to_string(),
+                json!(round_to_precision(capacity, self.precision)),
+            );
+        }
+
+        if let (Some(impact), serde_json::Value::Object(ref mut map)) =
+            (opportunity.dex_price_impact, &mut json_obj)
+        {
+            map.insert(
+                "dex_price_impact".to_string(),
+                json!(round_to_precision(impact, 2)),
+            );
+        }
+
         if self.show_timestamps {
             if let serde_json::Value::Object(ref mut map) = json_obj {
                 map.insert(
@@ -172,13 +212,19 @@ impl OutputFormatter {
         } else {
             0.0
         };
+        let dex_source = if opportunity.buy_source.is_dex() {
+            opportunity.buy_source
+        } else {
+            opportunity.sell_source
+        };
 
         if self.show_timestamps {
             format!(
-                "[{}] {} | Solana: ${:.prec$} | Binance: ${:.prec$} | Spread: {:.2}% | Profit: ${:.prec$} ({:.2}%)",
+                "[{}] {} | {}: ${:.prec$} | Binance: ${:.prec$} | Spread: {:.2}% | Profit: ${:.prec$} ({:.2}%)",
                 chrono::Utc::now().format("%H:%M:%S"),
                 format_trading_pair(opportunity.trading_pair),
-                if opportunity.buy_source == crate::price::PriceSource::Solana { opportunity.buy_price } else { opportunity.sell_price },
+                format_price_source(dex_source),
+                if opportunity.buy_source.is_dex() { opportunity.buy_price } else { opportunity.sell_price },
                 if opportunity.buy_source == crate::price::PriceSource::Binance { opportunity.buy_price } else { opportunity.sell_price },
                 spread_pct,
                 opportunity.net_profit_per_unit,
@@ -187,9 +233,10 @@ impl OutputFormatter {
             )
         } else {
             format!(
-                "{} | Solana: ${:.prec$} | Binance: ${:.prec$} | Spread: {:.2}% | Profit: ${:.prec$} ({:.2}%)",
+                "{} | {}: ${:.prec$} | Binance: ${:.prec$} | Spread: {:.2}% | Profit: ${:.prec$} ({:.2}%)",
                 format_trading_pair(opportunity.trading_pair),
-                if opportunity.buy_source == crate::price::PriceSource::Solana { opportunity.buy_price } else { opportunity.sell_price },
+                format_price_source(dex_source),
+                if opportunity.buy_source.is_dex() { opportunity.buy_price } else { opportunity.sell_price },
                 if opportunity.buy_source == crate::price::PriceSource::Binance { opportunity.buy_price } else { opportunity.sell_price },
                 spread_pct,
                 opportunity.net_profit_per_unit,
@@ -208,7 +255,8 @@ impl OutputFormatter {
         output.push('\n');
 
         output.push_str(&format!(
-            "Solana:    ${:.prec$} (age: {}ms)\n",
+            "{:<10} ${:.prec$} (age: {}ms)\n",
+            format!("{}:", format_price_source(prices.dex_source())),
             prices.solana_price.price,
             prices.solana_price.age_ms(),
             prec = self.precision
@@ -244,6 +292,7 @@ impl OutputFormatter {
         let mut json_obj = json!({
             "type": "price_update",
             "trading_pair": format_trading_pair(pair).to_lowercase(),
+            "dex_source": format_price_source(prices.dex_source()).to_lowercase(),
             "solana_price": round_to_precision(prices.solana_price.price, self.precision),
             "binance_price": round_to_precision(prices.binance_price.price, self.precision),
             "price_spread": round_to_precision(prices.price_spread, self.precision),
@@ -303,6 +352,242 @@ impl OutputFormatter {
         }
     }
 
+    /// Format a config-changed event listing reloaded settings
+    pub fn format_config_change(&self, changes: &[SettingChange]) -> String {
+        match self.format {
+            OutputFormat::Table => {
+                let mut output = String::from("CONFIGURATION RELOADED\n");
+                output.push_str("=".repeat(50).as_str());
+                for change in changes {
+                    output.push_str(&format!(
+                        "\n{:<18}{} -> {}",
+                        format!("{}:", change.key),
+                        change.old,
+                        change.new
+                    ));
+                }
+                output
+            }
+            OutputFormat::Json => {
+                let mut json_obj = json!({
+                    "type": "config_changed",
+                    "changes": changes
+                        .iter()
+                        .map(|change| json!({
+                            "key": change.key,
+                            "old": change.old,
+                            "new": change.new,
+                        }))
+                        .collect::<Vec<_>>(),
+                });
+                if self.show_timestamps {
+                    if let serde_json::Value::Object(ref mut map) = json_obj {
+                        map.insert(
+                            "timestamp".to_string(),
+                            json!(chrono::Utc::now().to_rfc3339()),
+                        );
+                    }
+                }
+                serde_json::to_string_pretty(&json_obj).unwrap_or_else(|_| "{}".to_string())
+            }
+            OutputFormat::Compact => format!(
+                "CONFIG: {}",
+                changes
+                    .iter()
+                    .map(|change| change.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Format the aggregated results of a backtest
+    pub fn format_backtest_report(&self, report: &BacktestReport) -> String {
+        let p = self.precision;
+        match self.format {
+            OutputFormat::Table => {
+                let mut output = String::from("BACKTEST REPORT\n");
+                output.push_str("=".repeat(50).as_str());
+                output.push_str(&format!(
+                    "\nPrice updates:       {} ({} skipped)",
+                    report.events, report.skipped_events
+                ));
+                output.push_str(&format!(
+                    "\nSimulated time:      {:.1}s",
+                    report.duration_ms as f64 / 1000.0
+                ));
+                output.push_str(&format!(
+                    "\nChecks:              {} ({} without prices)",
+                    report.checks, report.checks_without_prices
+                ));
+                if !report.rejections.is_empty() {
+                    output.push_str(&format!(
+                        "\nRejected prices:     {}",
+                        format_rejections(&report.rejections)
+                    ));
+                }
+                output.push_str(&format!(
+                    "\nOpportunities:       {} in {} windows",
+                    report.opportunities, report.opportunity_windows
+                ));
+                if let Some(profit) = &report.profit_percentage {
+                    output.push_str(&format!(
+                        "\nProfit %:            min {:.p$} / mean {:.p$} / p50 {:.p$} / p90 {:.p$} / p99 {:.p$} / max {:.p$}",
+                        profit.min, profit.mean, profit.p50, profit.p90, profit.p99, profit.max
+                    ));
+                }
+                output.push_str(&format!(
+                    "\nTheoretical PnL:     ${:.p$}",
+                    report.total_pnl
+                ));
+                output.push_str(&format!(
+                    "\nTime in opportunity: {:.1}s ({:.2}%)",
+                    report.time_in_opportunity_ms as f64 / 1000.0,
+                    report.time_in_opportunity_percentage
+                ));
+                if let Some(paper) = &report.paper {
+                    output.push_str("\n\n");
+                    output.push_str(&self.format_paper_summary(paper));
+                }
+                output
+            }
+            OutputFormat::Json => Self::format_tagged_json("backtest_report", report),
+            OutputFormat::Compact => format!(
+                "BACKTEST: {} opportunities in {} windows | PnL ${:.p$} | p50 {:.p$}% | in opportunity {:.2}%",
+                report.opportunities,
+                report.opportunity_windows,
+                report.total_pnl,
+                report.profit_percentage.as_ref().map_or(0.0, |profit| profit.p50),
+                report.time_in_opportunity_percentage
+            ),
+        }
+    }
+
+    /// Format a simulated execution of an opportunity
+    pub fn format_paper_trade(&self, trade: &PaperTrade) -> String {
+        let p = self.precision;
+        let leg = |fill: &Option<LegFill>| match fill {
+            Some(fill) => format!("{:.p$} @ ${:.p$}", fill.amount, fill.price),
+            None => "unfilled".to_string(),
+        };
+        match self.format {
+            OutputFormat::Table => {
+                let mut output = String::from("PAPER TRADE\n");
+                output.push_str("=".repeat(50).as_str());
+                output.push_str(&format!(
+                    "\nPair:             {}",
+                    format_trading_pair(trade.trading_pair)
+                ));
+                output.push_str(&format!("\nStatus:           {}", status_name(trade)));
+                output.push_str(&format!(
+                    "\nBuy:              {} {}",
+                    format_price_source(trade.buy_source),
+                    leg(&trade.buy)
+                ));
+                output.push_str(&format!(
+                    "\nSell:             {} {}",
+                    format_price_source(trade.sell_source),
+                    leg(&trade.sell)
+                ));
+                output.push_str(&format!("\nFees:             ${:.p$}", trade.fees));
+                output.push_str(&format!(
+                    "\nPnL:              ${:.p$} realized / ${:.p$} predicted",
+                    trade.realized_pnl, trade.predicted_pnl
+                ));
+                output
+            }
+            OutputFormat::Json => Self::format_tagged_json("paper_trade", trade),
+            OutputFormat::Compact => format!(
+                "PAPER: {} {} | buy {} | sell {} | PnL ${:.p$} (predicted ${:.p$})",
+                format_trading_pair(trade.trading_pair),
+                status_name(trade),
+                leg(&trade.buy),
+                leg(&trade.sell),
+                trade.realized_pnl,
+                trade.predicted_pnl
+            ),
+        }
+    }
+
+    /// Format paper trading totals and balances
+    pub fn format_paper_summary(&self, summary: &PaperSummary) -> String {
+        let p = self.precision;
+        let realized = summary
+            .realized_percentage()
+            .map_or_else(|| "n/a".to_string(), |pct| format!("{:.1}%", pct));
+        match self.format {
+            OutputFormat::Table => {
+                let mut output = String::from("PAPER TRADING\n");
+                output.push_str("=".repeat(50).as_str());
+                output.push_str(&format!(
+                    "\nTrades:           {} filled, {} partial, {} missed",
+                    summary.filled, summary.partially_filled, summary.missed
+                ));
+                output.push_str(&format!(
+                    "\nPnL:              ${:.p$} realized / ${:.p$} predicted ({} of predicted)",
+                    summary.realized_pnl, summary.predicted_pnl, realized
+                ));
+                for (venue, balance) in [
+                    ("Binance", &summary.balances.binance),
+                    ("Solana", &summary.balances.solana),
+                ] {
+                    output.push_str(&format!(
+                        "\n{:<18}{}",
+                        format!("{} balance:", venue),
+                        self.format_balance(balance)
+                    ));
+                }
+                let in_transit = &summary.in_transit;
+                if !in_transit.is_zero() {
+                    let mut total = in_transit.binance.clone();
+                    for (asset, amount) in in_transit.solana.iter() {
+                        total.add(asset, amount);
+                    }
+                    output.push_str(&format!(
+                        "\nIn transit:       {}",
+                        self.format_balance(&total)
+                    ));
+                }
+                output
+            }
+            OutputFormat::Json => Self::format_tagged_json("paper_summary", summary),
+            OutputFormat::Compact => format!(
+                "PAPER TOTAL: {} filled, {} partial, {} missed | PnL ${:.p$} of ${:.p$} predicted ({})",
+                summary.filled,
+                summary.partially_filled,
+                summary.missed,
+                summary.realized_pnl,
+                summary.predicted_pnl,
+                realized
+            ),
+        }
+    }
+
+    /// Amounts of each asset held, e.g. `50.0000 SOL, 10000.0000 USDT`
+    fn format_balance(&self, balance: &Balance) -> String {
+        let p = self.precision;
+        let amounts: Vec<String> = balance
+            .iter()
+            .map(|(asset, amount)| format!("{:.p$} {}", amount, asset))
+            .collect();
+        if amounts.is_empty() {
+            "none".to_string()
+        } else {
+            amounts.join(", ")
+        }
+    }
+
+    /// Pretty JSON of `value`'s fields with a `type` tag
+    fn format_tagged_json(kind: &str, value: &impl serde::Serialize) -> String {
+        let mut json_obj = json!({ "type": kind });
+        if let (serde_json::Value::Object(map), Ok(serde_json::Value::Object(fields))) =
+            (&mut json_obj, serde_json::to_value(value))
+        {
+            map.extend(fields);
+        }
+        serde_json::to_string_pretty(&json_obj).unwrap_or_else(|_| "{}".to_string())
+    }
+
     /// Format error message
     pub fn format_error(&self, error: &str) -> String {
         match self.format {
@@ -320,6 +605,15 @@ impl OutputFormatter {
     }
 }
 
+/// Status of a paper trade, with the reason when it was missed
+fn status_name(trade: &PaperTrade) -> String {
+    match trade.status {
+        FillStatus::Filled => "filled".to_string(),
+        FillStatus::PartiallyFilled => "partially filled".to_string(),
+        FillStatus::Missed => format!("missed ({})", trade.reason.unwrap_or("unknown")),
+    }
+}
+
 impl Default for OutputFormatter {
     fn default() -> Self {
         Self::new(OutputFormat::Table)
@@ -352,9 +646,11 @@ mod tests {
             net_profit_per_unit: 0.75,
             profit_percentage: 0.38,
             total_fees_per_unit: 0.25,
-            trading_pair: TradingPair::SolUsdt,
+            trading_pair: TradingPair::SOL_USDT,
             recommended_amount: 10.0,
             estimated_total_profit: 7.5,
+            dex_capacity: None,
+            dex_price_impact: None,
         }
     }
 
@@ -387,6 +683,20 @@ mod tests {
         assert!(output.contains("\"sell_source\": \"solana\""));
     }
 
+    #[test]
+    fn test_dex_depth_and_impact_output() {
+        let mut opportunity = create_test_opportunity();
+        opportunity.dex_capacity = Some(42.0);
+        opportunity.dex_price_impact = Some(1.234);
+
+        let table = OutputFormatter::new(OutputFormat::Table).format_opportunity(&opportunity);
+        assert!(table.contains("DEX Depth:        42.0000 SOL"));
+        assert!(table.contains("DEX Impact:       1.23%"));
+
+        let json = OutputFormatter::new(OutputFormat::Json).format_opportunity(&opportunity);
+        assert!(json.contains("\"dex_price_impact\": 1.23"));
+    }
+
     #[test]
     fn test_compact_format_opportunity() {
         let formatter = OutputFormatter::new(OutputFormat::Compact);
@@ -405,7 +715,7 @@ mod tests {
     fn test_price_pair_formatting() {
         let formatter = OutputFormatter::new(OutputFormat::Table);
         let prices = create_test_price_pair();
-        let output = formatter.format_price_pair(&prices, TradingPair::SolUsdt);
+        let output = formatter.format_price_pair(&prices, TradingPair::SOL_USDT);
 
         assert!(output.contains("SOL/USDT PRICE UPDATE"));
         assert!(output.contains("Solana:    $196.0000"));
@@ -425,7 +735,7 @@ mod tests {
     #[test]
     fn test_no_opportunities_format() {
         let formatter = OutputFormatter::new(OutputFormat::Table);
-        let output = formatter.format_no_opportunities(TradingPair::SolUsdt);
+        let output = formatter.format_no_opportunities(TradingPair::SOL_USDT);
 
         assert!(output.contains("No arbitrage opportunities"));
         assert!(output.contains("SOL/USDT"));
@@ -441,6 +751,82 @@ mod tests {
         assert!(output.contains("\"message\": \"Connection failed\""));
     }
 
+    #[test]
+    fn test_config_change_format() {
+        let changes = [SettingChange {
+            key: "threshold",
+            old: 0.5,
+            new: 0.8,
+        }];
+
+        let output = OutputFormatter::new(OutputFormat::Json).format_config_change(&changes);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["type"], "config_changed");
+        assert_eq!(value["changes"][0]["key"], "threshold");
+        assert_eq!(value["changes"][0]["new"], 0.8);
+
+        let output = OutputFormatter::new(OutputFormat::Compact).format_config_change(&changes);
+        assert_eq!(output, "CONFIG: threshold: 0.5 -> 0.8");
+    }
+
+    #[test]
+    fn test_paper_trade_format() {
+        use crate::arbitrage::inventory::VenueBalances;
+        use crate::arbitrage::paper::PaperSummary;
+
+        let trade = PaperTrade {
+            trading_pair: TradingPair::SOL_USDT,
+            buy_source: PriceSource::Solana,
+            sell_source: PriceSource::Binance,
+            status: FillStatus::PartiallyFilled,
+            reason: None,
+            requested_amount: 10.0,
+            order_amount: 10.0,
+            buy: Some(LegFill {
+                amount: 4.0,
+                price: 190.0,
+                fee: 1.9,
+            }),
+            sell: None,
+            fees: 2.1,
+            predicted_pnl: 38.0,
+            realized_pnl: 17.9,
+        };
+        let compact = OutputFormatter::with_settings(OutputFormat::Compact, false, 2)
+            .format_paper_trade(&trade);
+        assert_eq!(
+            compact,
+            "PAPER: SOL/USDT partially filled | buy 4.00 @ $190.00 | sell unfilled | PnL $17.90 (predicted $38.00)"
+        );
+        let json = OutputFormatter::new(OutputFormat::Json).format_paper_trade(&trade);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["type"], "paper_trade");
+        assert_eq!(value["status"], "partially_filled");
+        assert_eq!(value["buy"]["amount"], 4.0);
+
+        let summary = PaperSummary {
+            filled: 3,
+            partially_filled: 1,
+            missed: 0,
+            predicted_pnl: 100.0,
+            realized_pnl: 80.0,
+            balances: VenueBalances {
+                binance: Balance::from([("SOL", 48.0), ("USDT", 10_400.0)]),
+                solana: Balance::default(),
+            },
+            in_transit: VenueBalances {
+                binance: Balance::from([("SOL", 2.0)]),
+                solana: Balance::from([("SOL", 1.0), ("USDT", 0.0)]),
+            },
+        };
+        let table = OutputFormatter::new(OutputFormat::Table).format_paper_summary(&summary);
+        assert!(table.contains("3 filled, 1 partial, 0 missed"));
+        assert!(table.contains("(80.0% of predicted)"));
+        assert!(table.contains("Binance balance:  48.0000 SOL, 10400.0000 USDT"));
+        assert!(table.contains("Solana balance:   none"));
+        assert!(table.contains("In transit:       3.0000 SOL, 0.0000 USDT"));
+    }
+
     #[test]
     fn test_output_format_display() {
         assert_eq!(OutputFormat::Table.to_string(), "table");
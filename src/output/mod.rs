@@ -1,3 +1,7 @@
 pub mod formatter;
+pub mod sink;
+pub mod webhook;
 
 pub use formatter::{OutputFormat, OutputFormatter};
+pub use sink::OutputSink;
+pub use webhook::{WebhookConfig, WebhookSink};
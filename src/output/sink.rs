@@ -0,0 +1,19 @@
+use crate::arbitrage::calculator::ArbitrageOpportunity;
+use crate::config::reload::SettingChange;
+
+/// Destination for opportunities above the profit threshold and config-changed events,
+/// besides the console output
+///
+/// Sinks are called from the detection loop and the config reloader, so the `send_*`
+/// methods must return without waiting on I/O: queue the event, or drop it when the
+/// queue is full.
+pub trait OutputSink: Send + Sync {
+    /// Name for logs, e.g. `webhook hooks.example.com`
+    fn name(&self) -> &str;
+
+    /// Hand over an opportunity for delivery
+    fn send_opportunity(&self, opportunity: &ArbitrageOpportunity);
+
+    /// Hand over the settings changed by a config reload; ignored unless overridden
+    fn send_config_change(&self, _changes: &[SettingChange]) {}
+}
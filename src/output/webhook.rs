@@ -0,0 +1,470 @@
+use super::formatter::{OutputFormat, OutputFormatter};
+use super::sink::OutputSink;
+use crate::arbitrage::calculator::ArbitrageOpportunity;
+use crate::config::reload::SettingChange;
+use crate::websocket::reconnect::{ReconnectConfig, ReconnectHandler};
+use log::{debug, warn};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// Default time allowed for each delivery attempt
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default retries after a failed delivery attempt
+pub const DEFAULT_WEBHOOK_RETRIES: usize = 3;
+
+/// Default delay before the first retry
+pub const DEFAULT_WEBHOOK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Default number of events waiting for delivery before new ones are dropped
+pub const DEFAULT_WEBHOOK_QUEUE_SIZE: usize = 100;
+
+/// Longest delay between retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Errors in webhook settings
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid header name: {0:?}")]
+    InvalidHeaderName(String),
+    #[error("Invalid value for header {0}")]
+    InvalidHeaderValue(String),
+    #[error("Timeout must be greater than zero")]
+    ZeroTimeout,
+    #[error("Invalid retry policy: {0}")]
+    InvalidRetry(String),
+    #[error("Queue size must be greater than zero")]
+    ZeroQueueSize,
+    #[error("HTTP client error: {0}")]
+    ClientError(#[from] reqwest::Error),
+}
+
+/// Validated webhook settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    pub url: Url,
+    /// Extra request headers, e.g. an `Authorization` token
+    pub headers: Vec<(String, String)>,
+    /// Time allowed for each delivery attempt
+    pub timeout: Duration,
+    /// Retries after a failed attempt; 0 never retries
+    pub max_retries: usize,
+    /// Delay before the first retry, doubling for each later one up to 30s
+    pub retry_delay: Duration,
+    /// Events waiting for delivery before new ones are dropped
+    pub queue_size: usize,
+}
+
+impl WebhookConfig {
+    /// Post to `url` with the default timeout, retries and queue size
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            headers: Vec::new(),
+            timeout: DEFAULT_WEBHOOK_TIMEOUT,
+            max_retries: DEFAULT_WEBHOOK_RETRIES,
+            retry_delay: DEFAULT_WEBHOOK_RETRY_DELAY,
+            queue_size: DEFAULT_WEBHOOK_QUEUE_SIZE,
+        }
+    }
+
+    /// Send `headers` with every request
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Set the time allowed for each delivery attempt
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry a failed delivery up to `max_retries` times, first after `retry_delay`
+    pub fn with_retries(mut self, max_retries: usize, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Set how many events may wait for delivery
+    pub fn with_queue_size(mut self, queue_size: usize) -> Self {
+        self.queue_size = queue_size;
+        self
+    }
+
+    /// Check the headers, timeout, retry policy and queue size
+    pub fn validate(&self) -> Result<(), WebhookError> {
+        self.header_map()?;
+        if self.timeout.is_zero() {
+            return Err(WebhookError::ZeroTimeout);
+        }
+        if let Some(policy) = self.retry_policy() {
+            policy.validate().map_err(WebhookError::InvalidRetry)?;
+        }
+        if self.queue_size == 0 {
+            return Err(WebhookError::ZeroQueueSize);
+        }
+        Ok(())
+    }
+
+    /// Headers sent with every request
+    fn header_map(&self) -> Result<HeaderMap, WebhookError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| WebhookError::InvalidHeaderName(name.clone()))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| WebhookError::InvalidHeaderValue(name.to_string()))?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+
+    /// Backoff between retries, or `None` when failed deliveries are not retried
+    fn retry_policy(&self) -> Option<ReconnectConfig> {
+        (self.max_retries > 0).then(|| {
+            ReconnectConfig::new(self.retry_delay, self.retry_delay.max(MAX_RETRY_DELAY), 2.0)
+                .with_max_attempts(Some(self.max_retries))
+                .with_max_total_duration(None)
+        })
+    }
+}
+
+/// Posts opportunities and config-changed events to an HTTP endpoint as the JSON of the
+/// `json` output format
+///
+/// Events are queued and delivered in order by a background task, which retries failed
+/// attempts with exponential backoff. A full queue drops new events rather than
+/// blocking detection.
+pub struct WebhookSink {
+    name: String,
+    formatter: OutputFormatter,
+    queue: mpsc::Sender<String>,
+    dropped: AtomicU64,
+}
+
+impl WebhookSink {
+    /// Validate `config` and start the delivery task
+    ///
+    /// The returned task ends once the sink is dropped and its queue has been delivered;
+    /// await it on shutdown so that queued events are not lost.
+    pub fn spawn(config: WebhookConfig) -> Result<(Self, JoinHandle<()>), WebhookError> {
+        config.validate()?;
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .default_headers(config.header_map()?)
+            .build()?;
+        let (queue, pending) = mpsc::channel(config.queue_size);
+        // The URL itself may carry a token, so only the host is logged
+        let name = format!("webhook {}", config.url.host_str().unwrap_or("endpoint"));
+
+        let delivery = tokio::spawn(deliver(client, config, pending, name.clone()));
+        let sink = Self {
+            name,
+            formatter: OutputFormatter::new(OutputFormat::Json),
+            queue,
+            dropped: AtomicU64::new(0),
+        };
+        Ok((sink, delivery))
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Queue `payload` for delivery, dropping it when the queue is full
+    fn enqueue(&self, payload: String, event: &str) {
+        match self.queue.try_send(payload) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!(
+                    "{} queue is full, dropped {} ({} dropped so far)",
+                    self.name, event, dropped
+                );
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                warn!("{} delivery stopped, dropped {}", self.name, event);
+            }
+        }
+    }
+}
+
+impl OutputSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send_opportunity(&self, opportunity: &ArbitrageOpportunity) {
+        let payload = self.formatter.format_opportunity_json(opportunity);
+        self.enqueue(payload, "opportunity");
+    }
+
+    fn send_config_change(&self, changes: &[SettingChange]) {
+        let payload = self.formatter.format_config_change(changes);
+        self.enqueue(payload, "config change");
+    }
+}
+
+/// Why a delivery attempt failed
+enum AttemptError {
+    /// A timeout, connection error, 408, 429 or server error, worth retrying
+    Retryable(String),
+    /// Any other non-success status
+    Rejected(StatusCode),
+}
+
+/// Deliver queued payloads in order until the sink is dropped
+async fn deliver(
+    client: reqwest::Client,
+    config: WebhookConfig,
+    mut pending: mpsc::Receiver<String>,
+    name: String,
+) {
+    let mut retries = config
+        .retry_policy()
+        .and_then(|policy| ReconnectHandler::new(policy).ok());
+
+    while let Some(payload) = pending.recv().await {
+        if let Some(retries) = retries.as_mut() {
+            retries.reset();
+        }
+
+        loop {
+            match attempt(&client, &config.url, &payload).await {
+                Ok(()) => {
+                    debug!("Delivered event to {}", name);
+                    break;
+                }
+                Err(AttemptError::Rejected(status)) => {
+                    warn!("{} rejected event with {}, not retrying", name, status);
+                    break;
+                }
+                Err(AttemptError::Retryable(reason)) => {
+                    match retries.as_mut().map(ReconnectHandler::should_reconnect) {
+                        Some(Ok(delay)) => {
+                            warn!(
+                                "Delivery to {} failed ({}), retrying in {:?}",
+                                name, reason, delay
+                            );
+                            tokio::time::sleep(delay).await;
+                        }
+                        _ => {
+                            warn!("Delivery to {} failed ({}), dropping event", name, reason);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// POST `payload` once
+async fn attempt(client: &reqwest::Client, url: &Url, payload: &str) -> Result<(), AttemptError> {
+    let response = client
+        .post(url.clone())
+        .body(payload.to_string())
+        .send()
+        .await
+        .map_err(|e| AttemptError::Retryable(e.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else if status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+    {
+        Err(AttemptError::Retryable(status.to_string()))
+    } else {
+        Err(AttemptError::Rejected(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TradingPair;
+    use crate::price::PriceSource;
+    use crate::test_utils::http;
+
+    fn opportunity(profit_percentage: f64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            buy_source: PriceSource::Orca,
+            sell_source: PriceSource::Binance,
+            buy_price: 195.0,
+            sell_price: 196.0,
+            raw_profit_per_unit: 1.0,
+            net_profit_per_unit: 0.75,
+            profit_percentage,
+            total_fees_per_unit: 0.25,
+            trading_pair: TradingPair::SOL_USDT,
+            recommended_amount: 10.0,
+            estimated_total_profit: 7.5,
+            dex_capacity: None,
+            dex_price_impact: None,
+        }
+    }
+
+    fn config(base_url: &str) -> WebhookConfig {
+        WebhookConfig::new(format!("{}/hooks/arb", base_url).parse().unwrap())
+            .with_retries(3, Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_posts_opportunity_json_with_headers() {
+        let (base_url, requests) = http::serve(vec![(200, "{}".to_string())]).await;
+        let (sink, _delivery) = WebhookSink::spawn(config(&base_url).with_headers(vec![(
+            "Authorization".to_string(),
+            "Bearer secret".to_string(),
+        )]))
+        .unwrap();
+        assert_eq!(sink.name(), "webhook 127.0.0.1");
+
+        sink.send_opportunity(&opportunity(0.38));
+        let requests = requests.await.unwrap();
+        assert_eq!(requests[0].request_line, "POST /hooks/arb HTTP/1.1");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["type"], "arbitrage_opportunity");
+        assert_eq!(body["buy_source"], "orca");
+        assert_eq!(body["profit_percentage"], 0.38);
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors_with_the_same_payload() {
+        let (base_url, requests) = http::serve(vec![
+            (500, "{}".to_string()),
+            (429, "{}".to_string()),
+            (200, "{}".to_string()),
+        ])
+        .await;
+        let (sink, _delivery) = WebhookSink::spawn(config(&base_url)).unwrap();
+
+        sink.send_opportunity(&opportunity(1.5));
+        let requests = requests.await.unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|request| request.body == requests[0].body));
+    }
+
+    #[tokio::test]
+    async fn test_slow_endpoint_fills_queue_without_blocking() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (accepted, mut first_request) = mpsc::channel(8);
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+                accepted.send(()).await.ok();
+            }
+        });
+
+        let (sink, _delivery) = WebhookSink::spawn(
+            config(&base_url)
+                .with_timeout(Duration::from_secs(30))
+                .with_queue_size(1),
+        )
+        .unwrap();
+        sink.send_opportunity(&opportunity(1.0));
+        first_request.recv().await.unwrap();
+
+        // One opportunity is in flight, the next waits in the queue and the last is dropped
+        let start = std::time::Instant::now();
+        sink.send_opportunity(&opportunity(1.1));
+        sink.send_opportunity(&opportunity(1.2));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(sink.dropped(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_attempts_are_retried() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sink, _delivery) = WebhookSink::spawn(
+            config(&base_url)
+                .with_timeout(Duration::from_millis(50))
+                .with_retries(1, Duration::from_millis(1)),
+        )
+        .unwrap();
+        sink.send_opportunity(&opportunity(1.0));
+
+        // The first attempt times out unanswered and is retried on a new connection
+        let (_first, _) = listener.accept().await.unwrap();
+        let (_second, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_posts_config_changes_and_drains_queue_once_dropped() {
+        let (base_url, requests) =
+            http::serve(vec![(200, "{}".to_string()), (200, "{}".to_string())]).await;
+        let (sink, delivery) = WebhookSink::spawn(config(&base_url)).unwrap();
+
+        sink.send_opportunity(&opportunity(0.5));
+        sink.send_config_change(&[SettingChange {
+            key: "threshold",
+            old: 0.5,
+            new: 0.8,
+        }]);
+        drop(sink);
+        tokio::time::timeout(Duration::from_secs(5), delivery)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let requests = requests.await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+        assert_eq!(body["type"], "config_changed");
+        assert_eq!(body["changes"][0]["key"], "threshold");
+        assert_eq!(body["changes"][0]["new"], 0.8);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_settings() {
+        let base = WebhookConfig::new("https://hooks.example.com/arb".parse().unwrap());
+        assert!(base.validate().is_ok());
+        assert!(base
+            .clone()
+            .with_retries(0, Duration::ZERO)
+            .validate()
+            .is_ok());
+
+        let bad_header = base
+            .clone()
+            .with_headers(vec![("Bad Header".to_string(), "x".to_string())]);
+        assert!(matches!(
+            bad_header.validate(),
+            Err(WebhookError::InvalidHeaderName(_))
+        ));
+        assert!(matches!(
+            base.clone().with_retries(2, Duration::ZERO).validate(),
+            Err(WebhookError::InvalidRetry(_))
+        ));
+        assert!(matches!(
+            base.clone().with_queue_size(0).validate(),
+            Err(WebhookError::ZeroQueueSize)
+        ));
+        assert!(matches!(
+            base.with_timeout(Duration::ZERO).validate(),
+            Err(WebhookError::ZeroTimeout)
+        ));
+    }
+}
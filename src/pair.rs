@@ -0,0 +1,618 @@
+use crate::config::PriceBounds;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Wrapped SOL mint address
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// USDT mint address
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY9MGXtKKxV3kG6s";
+
+/// USDC mint address
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Largest token decimals accepted in a pair definition
+const MAX_DECIMALS: u8 = 18;
+
+/// Errors in trading pair definitions
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PairError {
+    #[error("Pair definition entry '{0}' is not key=value")]
+    Syntax(String),
+    #[error("Unknown pair definition key '{0}'")]
+    UnknownKey(String),
+    #[error("Pair definition is missing '{0}'")]
+    MissingKey(&'static str),
+    #[error("Invalid value '{value}' for pair definition key '{key}'")]
+    InvalidValue { key: &'static str, value: String },
+    #[error("Pair '{0}' has no Solana venue (amm_pool, whirlpool, clmm_pool or openbook_market)")]
+    NoVenue(String),
+    #[error("Pair '{0}' is already defined differently")]
+    Conflict(String),
+    #[error("Unknown trading pair '{0}'")]
+    UnknownPair(String),
+}
+
+/// Definition of a token pair: mints, decimals, Solana venues and the Binance symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairSpec {
+    /// Name used to select the pair, e.g. `sol-usdt`
+    pub name: Cow<'static, str>,
+    /// Base token symbol, e.g. `SOL`
+    pub base_symbol: Cow<'static, str>,
+    /// Quote token symbol, e.g. `USDT`
+    pub quote_symbol: Cow<'static, str>,
+    pub base_mint: Cow<'static, str>,
+    pub quote_mint: Cow<'static, str>,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Raydium AMM v4 pool
+    pub amm_pool: Option<Cow<'static, str>>,
+    /// Orca Whirlpool
+    pub whirlpool: Option<Cow<'static, str>>,
+    /// Raydium CLMM pool
+    pub clmm_pool: Option<Cow<'static, str>>,
+    /// OpenBook v2 market
+    pub openbook_market: Option<Cow<'static, str>>,
+    /// Binance spot symbol, e.g. `SOLUSDT`
+    pub binance_symbol: Cow<'static, str>,
+    /// Valid price range, overriding the configured default bounds
+    pub price_bounds: Option<PriceBounds>,
+}
+
+/// SOL/USDT: Raydium AMM v4 pool (mainnet)
+const SOL_USDT_SPEC: PairSpec = PairSpec {
+    name: Cow::Borrowed("sol-usdt"),
+    base_symbol: Cow::Borrowed("SOL"),
+    quote_symbol: Cow::Borrowed("USDT"),
+    base_mint: Cow::Borrowed(WSOL_MINT),
+    quote_mint: Cow::Borrowed(USDT_MINT),
+    base_decimals: 9,
+    quote_decimals: 6,
+    amm_pool: Some(Cow::Borrowed(
+        "7XawhbbxtsRcQA8KTkHT9f9nc6d69UwqCDh6U5EEbEmX",
+    )),
+    whirlpool: None,
+    clmm_pool: None,
+    openbook_market: None,
+    binance_symbol: Cow::Borrowed("SOLUSDT"),
+    price_bounds: None,
+};
+
+/// SOL/USDC: Raydium AMM v4, Orca (64 tick spacing), Raydium CLMM and OpenBook v2 (mainnet)
+const SOL_USDC_SPEC: PairSpec = PairSpec {
+    name: Cow::Borrowed("sol-usdc"),
+    base_symbol: Cow::Borrowed("SOL"),
+    quote_symbol: Cow::Borrowed("USDC"),
+    base_mint: Cow::Borrowed(WSOL_MINT),
+    quote_mint: Cow::Borrowed(USDC_MINT),
+    base_decimals: 9,
+    quote_decimals: 6,
+    amm_pool: Some(Cow::Borrowed(
+        "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    )),
+    whirlpool: Some(Cow::Borrowed(
+        "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ",
+    )),
+    clmm_pool: Some(Cow::Borrowed(
+        "2QdhepnKRTLjjSqPL1PtKNwqrUkoLee5Gqs8bvZhRdMv",
+    )),
+    openbook_market: Some(Cow::Borrowed(
+        "CFSMrBssNG8Ud1edW59jNLnq2cwrQ9uY5cM3wXmqRJj3",
+    )),
+    binance_symbol: Cow::Borrowed("SOLUSDC"),
+    price_bounds: None,
+};
+
+/// User-defined pairs, registered once and kept for the life of the process
+static DEFINED_PAIRS: RwLock<Vec<TradingPair>> = RwLock::new(Vec::new());
+
+impl PairSpec {
+    /// Check mints, venues, decimals and symbols
+    pub fn validate(&self) -> Result<(), PairError> {
+        fn non_empty(key: &'static str, value: &str) -> Result<(), PairError> {
+            if value.is_empty() {
+                return Err(PairError::InvalidValue {
+                    key,
+                    value: value.to_string(),
+                });
+            }
+            Ok(())
+        }
+        non_empty("name", &self.name)?;
+        non_empty("base", &self.base_symbol)?;
+        non_empty("quote", &self.quote_symbol)?;
+
+        if self.name.contains(',') {
+            return Err(PairError::InvalidValue {
+                key: "name",
+                value: self.name.to_string(),
+            });
+        }
+        if self.binance_symbol.is_empty()
+            || !self
+                .binance_symbol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(PairError::InvalidValue {
+                key: "binance_symbol",
+                value: self.binance_symbol.to_string(),
+            });
+        }
+        for (key, decimals) in [
+            ("base_decimals", self.base_decimals),
+            ("quote_decimals", self.quote_decimals),
+        ] {
+            if decimals > MAX_DECIMALS {
+                return Err(PairError::InvalidValue {
+                    key,
+                    value: decimals.to_string(),
+                });
+            }
+        }
+
+        let addresses = [
+            ("base_mint", Some(&self.base_mint)),
+            ("quote_mint", Some(&self.quote_mint)),
+            ("amm_pool", self.amm_pool.as_ref()),
+            ("whirlpool", self.whirlpool.as_ref()),
+            ("clmm_pool", self.clmm_pool.as_ref()),
+            ("openbook_market", self.openbook_market.as_ref()),
+        ];
+        for (key, address) in addresses {
+            if let Some(address) = address {
+                if !is_valid_address(address) {
+                    return Err(PairError::InvalidValue {
+                        key,
+                        value: address.to_string(),
+                    });
+                }
+            }
+        }
+        if self.base_mint == self.quote_mint {
+            return Err(PairError::InvalidValue {
+                key: "quote_mint",
+                value: self.quote_mint.to_string(),
+            });
+        }
+
+        if self.amm_pool.is_none()
+            && self.whirlpool.is_none()
+            && self.clmm_pool.is_none()
+            && self.openbook_market.is_none()
+        {
+            return Err(PairError::NoVenue(self.name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Whether `address` is a base58-encoded 32-byte Solana public key
+fn is_valid_address(address: &str) -> bool {
+    matches!(bs58::decode(address).into_vec(), Ok(bytes) if bytes.len() == 32)
+}
+
+/// Pair definition as written in a config file or on the command line
+///
+/// Required keys: `base`, `quote`, `base_mint`, `quote_mint`, `base_decimals`,
+/// `quote_decimals` and at least one of `amm_pool`, `whirlpool`, `clmm_pool` or
+/// `openbook_market`. `name` defaults to `base-quote` in lower case, `binance_symbol`
+/// to `BASEQUOTE`, and `min_price`/`max_price` to the configured bounds.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PairDefinition {
+    pub name: Option<String>,
+    pub base: Option<String>,
+    pub quote: Option<String>,
+    pub base_mint: Option<String>,
+    pub quote_mint: Option<String>,
+    pub base_decimals: Option<u8>,
+    pub quote_decimals: Option<u8>,
+    pub amm_pool: Option<String>,
+    pub whirlpool: Option<String>,
+    pub clmm_pool: Option<String>,
+    pub openbook_market: Option<String>,
+    pub binance_symbol: Option<String>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+}
+
+impl TryFrom<PairDefinition> for PairSpec {
+    type Error = PairError;
+
+    fn try_from(definition: PairDefinition) -> Result<Self, Self::Error> {
+        let base_symbol = definition
+            .base
+            .ok_or(PairError::MissingKey("base"))?
+            .to_uppercase();
+        let quote_symbol = definition
+            .quote
+            .ok_or(PairError::MissingKey("quote"))?
+            .to_uppercase();
+        let price_bounds = match (definition.min_price, definition.max_price) {
+            (None, None) => None,
+            (Some(min), Some(max)) => {
+                Some(
+                    PriceBounds::new(min, max).map_err(|_| PairError::InvalidValue {
+                        key: "max_price",
+                        value: max.to_string(),
+                    })?,
+                )
+            }
+            (None, Some(_)) => return Err(PairError::MissingKey("min_price")),
+            (Some(_), None) => return Err(PairError::MissingKey("max_price")),
+        };
+
+        Ok(Self {
+            name: Cow::Owned(
+                definition
+                    .name
+                    .unwrap_or_else(|| format!("{}-{}", base_symbol, quote_symbol))
+                    .to_lowercase(),
+            ),
+            binance_symbol: Cow::Owned(
+                definition
+                    .binance_symbol
+                    .unwrap_or_else(|| format!("{}{}", base_symbol, quote_symbol))
+                    .to_uppercase(),
+            ),
+            base_symbol: Cow::Owned(base_symbol),
+            quote_symbol: Cow::Owned(quote_symbol),
+            base_mint: Cow::Owned(
+                definition
+                    .base_mint
+                    .ok_or(PairError::MissingKey("base_mint"))?,
+            ),
+            quote_mint: Cow::Owned(
+                definition
+                    .quote_mint
+                    .ok_or(PairError::MissingKey("quote_mint"))?,
+            ),
+            base_decimals: definition
+                .base_decimals
+                .ok_or(PairError::MissingKey("base_decimals"))?,
+            quote_decimals: definition
+                .quote_decimals
+                .ok_or(PairError::MissingKey("quote_decimals"))?,
+            amm_pool: definition.amm_pool.map(Cow::Owned),
+            whirlpool: definition.whirlpool.map(Cow::Owned),
+            clmm_pool: definition.clmm_pool.map(Cow::Owned),
+            openbook_market: definition.openbook_market.map(Cow::Owned),
+            price_bounds,
+        })
+    }
+}
+
+/// Parse a [`PairDefinition`] written as `key=value,key=value,...`
+impl FromStr for PairSpec {
+    type Err = PairError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut definition = PairDefinition::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| PairError::Syntax(entry.to_string()))?;
+            let value = value.trim().to_string();
+            match key.trim() {
+                "name" => definition.name = Some(value),
+                "base" => definition.base = Some(value),
+                "quote" => definition.quote = Some(value),
+                "base_mint" => definition.base_mint = Some(value),
+                "quote_mint" => definition.quote_mint = Some(value),
+                "base_decimals" => {
+                    definition.base_decimals = Some(parse_value("base_decimals", &value)?)
+                }
+                "quote_decimals" => {
+                    definition.quote_decimals = Some(parse_value("quote_decimals", &value)?)
+                }
+                "amm_pool" => definition.amm_pool = Some(value),
+                "whirlpool" => definition.whirlpool = Some(value),
+                "clmm_pool" => definition.clmm_pool = Some(value),
+                "openbook_market" => definition.openbook_market = Some(value),
+                "binance_symbol" => definition.binance_symbol = Some(value),
+                "min_price" => definition.min_price = Some(parse_value("min_price", &value)?),
+                "max_price" => definition.max_price = Some(parse_value("max_price", &value)?),
+                other => return Err(PairError::UnknownKey(other.to_string())),
+            }
+        }
+        definition.try_into()
+    }
+}
+
+fn parse_value<T: FromStr>(key: &'static str, value: &str) -> Result<T, PairError> {
+    value.parse().map_err(|_| PairError::InvalidValue {
+        key,
+        value: value.to_string(),
+    })
+}
+
+/// Trading pair monitored for arbitrage
+///
+/// A cheap copyable handle to a pair definition: one of the built-in pairs or a pair
+/// registered with [`TradingPair::define`]. Pairs compare and hash by name.
+#[derive(Clone, Copy)]
+pub struct TradingPair(&'static PairSpec);
+
+impl TradingPair {
+    pub const SOL_USDT: TradingPair = TradingPair(&SOL_USDT_SPEC);
+    pub const SOL_USDC: TradingPair = TradingPair(&SOL_USDC_SPEC);
+
+    /// Pairs available without a definition
+    pub const BUILT_IN: [TradingPair; 2] = [Self::SOL_USDT, Self::SOL_USDC];
+
+    /// Register a user-defined pair
+    ///
+    /// Defining a pair again with an identical specification returns the existing pair;
+    /// reusing a name with a different specification is an error.
+    pub fn define(spec: PairSpec) -> Result<Self, PairError> {
+        spec.validate()?;
+        if Self::BUILT_IN.iter().any(|pair| pair.name() == spec.name) {
+            return Err(PairError::Conflict(spec.name.to_string()));
+        }
+
+        let mut defined = DEFINED_PAIRS.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = defined.iter().find(|pair| pair.name() == spec.name) {
+            return if *existing.spec() == spec {
+                Ok(*existing)
+            } else {
+                Err(PairError::Conflict(spec.name.to_string()))
+            };
+        }
+        let pair = TradingPair(Box::leak(Box::new(spec)));
+        defined.push(pair);
+        Ok(pair)
+    }
+
+    /// Look up a built-in or defined pair by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let defined = DEFINED_PAIRS.read().unwrap_or_else(|e| e.into_inner());
+        Self::BUILT_IN
+            .iter()
+            .chain(defined.iter())
+            .find(|pair| pair.name().eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// Full pair definition
+    pub fn spec(self) -> &'static PairSpec {
+        self.0
+    }
+
+    /// Name used to select the pair, e.g. `sol-usdt`
+    pub fn name(self) -> &'static str {
+        &self.0.name
+    }
+
+    /// Human-readable label, e.g. `SOL/USDT`
+    pub fn label(self) -> String {
+        format!("{}/{}", self.0.base_symbol, self.0.quote_symbol)
+    }
+
+    /// Base token symbol, e.g. `SOL`
+    pub fn base_symbol(self) -> &'static str {
+        &self.0.base_symbol
+    }
+
+    /// Quote token symbol, e.g. `USDT`
+    pub fn quote_symbol(self) -> &'static str {
+        &self.0.quote_symbol
+    }
+
+    /// Binance spot symbol, e.g. `SOLUSDT`
+    pub fn binance_symbol(self) -> &'static str {
+        &self.0.binance_symbol
+    }
+
+    /// Whether the base token is SOL, so the pair's own price is the SOL price
+    pub fn base_is_sol(self) -> bool {
+        self.0.base_mint == WSOL_MINT
+    }
+
+    /// Whether the pair is quoted in SOL
+    pub fn quote_is_sol(self) -> bool {
+        self.0.quote_mint == WSOL_MINT
+    }
+}
+
+impl PartialEq for TradingPair {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for TradingPair {}
+
+impl Hash for TradingPair {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+    }
+}
+
+impl fmt::Debug for TradingPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for TradingPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label())
+    }
+}
+
+impl FromStr for TradingPair {
+    type Err = PairError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s.trim()).ok_or_else(|| PairError::UnknownPair(s.to_string()))
+    }
+}
+
+impl Serialize for TradingPair {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for TradingPair {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ConfigError, RawConfig};
+    use clap::Parser;
+
+    const JUP_MINT: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+    const POOL_ADDRESS: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+
+    fn jup_definition() -> String {
+        format!(
+            "base=jup,quote=usdt,base_mint={},quote_mint={},base_decimals=6,quote_decimals=6,whirlpool={},min_price=0.01,max_price=100",
+            JUP_MINT, USDT_MINT, POOL_ADDRESS
+        )
+    }
+
+    #[test]
+    fn test_built_in_pairs() {
+        for pair in TradingPair::BUILT_IN {
+            assert!(pair.spec().validate().is_ok());
+            assert_eq!(TradingPair::from_name(pair.name()), Some(pair));
+        }
+        assert_eq!(TradingPair::SOL_USDT.label(), "SOL/USDT");
+        assert_eq!(TradingPair::SOL_USDC.binance_symbol(), "SOLUSDC");
+        assert_eq!("SOL-USDT".parse::<TradingPair>(), Ok(TradingPair::SOL_USDT));
+        assert_ne!(TradingPair::SOL_USDT, TradingPair::SOL_USDC);
+    }
+
+    #[test]
+    fn test_parse_definition_defaults() {
+        let spec: PairSpec = jup_definition().parse().unwrap();
+        assert_eq!(spec.name, "jup-usdt");
+        assert_eq!(spec.base_symbol, "JUP");
+        assert_eq!(spec.binance_symbol, "JUPUSDT");
+        assert_eq!(spec.base_decimals, 6);
+        assert_eq!(spec.whirlpool.as_deref(), Some(POOL_ADDRESS));
+        assert!(spec.amm_pool.is_none());
+        assert_eq!(spec.price_bounds.unwrap().min_price, 0.01);
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_definition_errors() {
+        assert_eq!(
+            "base=jup,quote".parse::<PairSpec>(),
+            Err(PairError::Syntax("quote".to_string()))
+        );
+        assert_eq!(
+            "base=jup,colour=red".parse::<PairSpec>(),
+            Err(PairError::UnknownKey("colour".to_string()))
+        );
+        assert_eq!(
+            "base=jup,quote=usdt".parse::<PairSpec>(),
+            Err(PairError::MissingKey("base_mint"))
+        );
+        assert!(matches!(
+            "base_decimals=six".parse::<PairSpec>(),
+            Err(PairError::InvalidValue {
+                key: "base_decimals",
+                ..
+            })
+        ));
+
+        let no_venue = jup_definition().replace(&format!("whirlpool={}", POOL_ADDRESS), "");
+        let spec: PairSpec = no_venue.parse().unwrap();
+        assert_eq!(spec.validate(), Err(PairError::NoVenue("jup-usdt".into())));
+
+        let bad_mint = jup_definition().replace(JUP_MINT, "not-a-mint");
+        let spec: PairSpec = bad_mint.parse().unwrap();
+        assert!(matches!(
+            spec.validate(),
+            Err(PairError::InvalidValue {
+                key: "base_mint",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_config_resolves_defined_pairs() {
+        let definition = format!("name=jup-usdt-config,{}", jup_definition());
+        let raw = RawConfig::try_parse_from([
+            "solana-arbitrage-watcher",
+            "--define-pair",
+            &definition,
+            "--pair",
+            "jup-usdt-config,sol-usdt",
+        ])
+        .unwrap();
+        let config = Config::new(&raw).unwrap();
+        assert_eq!(config.pairs.len(), 2);
+        assert_eq!(config.primary_pair().binance_symbol(), "JUPUSDT");
+
+        let raw =
+            RawConfig::try_parse_from(["solana-arbitrage-watcher", "--pair", "doge-usdt"]).unwrap();
+        let errors = Config::new(&raw).unwrap_err();
+        assert!(matches!(
+            errors.errors.as_slice(),
+            [ConfigError::Key { key, source }]
+                if key == "--pair"
+                    && matches!(&**source, ConfigError::Pair(PairError::UnknownPair(name)) if name == "doge-usdt")
+        ));
+
+        // Malformed definitions are rejected by the argument parser, naming the key
+        let error = RawConfig::try_parse_from([
+            "solana-arbitrage-watcher",
+            "--define-pair",
+            "base=jup,colour=red",
+            "--pair",
+            "sol-usdt",
+        ])
+        .unwrap_err();
+        assert!(error.to_string().contains("colour"));
+    }
+
+    #[test]
+    fn test_define_and_look_up() {
+        let spec: PairSpec = format!("name=jup-usdt-test,{}", jup_definition())
+            .parse()
+            .unwrap();
+        let pair = TradingPair::define(spec.clone()).unwrap();
+        assert_eq!(pair.name(), "jup-usdt-test");
+        assert_eq!(pair.label(), "JUP/USDT");
+        assert_eq!(TradingPair::from_name("JUP-USDT-TEST"), Some(pair));
+
+        // Identical redefinition is idempotent, a different one conflicts
+        assert_eq!(TradingPair::define(spec.clone()), Ok(pair));
+        let changed = PairSpec {
+            base_decimals: 8,
+            ..spec
+        };
+        assert_eq!(
+            TradingPair::define(changed),
+            Err(PairError::Conflict("jup-usdt-test".to_string()))
+        );
+
+        let built_in = PairSpec {
+            name: "sol-usdt".into(),
+            ..TradingPair::SOL_USDC.spec().clone()
+        };
+        assert!(TradingPair::define(built_in).is_err());
+
+        let json = serde_json::to_string(&pair).unwrap();
+        assert_eq!(json, "\"jup-usdt-test\"");
+        assert_eq!(serde_json::from_str::<TradingPair>(&json).unwrap(), pair);
+        assert!(serde_json::from_str::<TradingPair>("\"doge-usdt\"").is_err());
+    }
+}
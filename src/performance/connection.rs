@@ -0,0 +1,188 @@
+use crate::websocket::state::{ConnectionState, StateTransition};
+use std::time::{Duration, Instant};
+
+/// Connection statistics of one client, derived from its state transitions
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceConnectionStats {
+    pub state: ConnectionState,
+    /// Share of the time since metrics started that the client was subscribed or streaming
+    pub uptime_pct: f64,
+    /// Seconds spent subscribed or streaming
+    pub connected_seconds: f64,
+    /// From starting the latest connection that streamed to its first price update
+    pub time_to_first_message_ms: Option<f64>,
+    /// Connections lost and then restored
+    pub reconnects: u64,
+    /// Total time from losing a connection to being subscribed again
+    pub reconnect_seconds: f64,
+}
+
+impl SourceConnectionStats {
+    /// Mean time to restore a lost connection, 0 before any reconnect
+    pub fn avg_reconnect_ms(&self) -> f64 {
+        if self.reconnects == 0 {
+            0.0
+        } else {
+            self.reconnect_seconds * 1000.0 / self.reconnects as f64
+        }
+    }
+}
+
+/// Rebuilds a client's connection history from its state transitions
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionTracker {
+    state: ConnectionState,
+    entered: Option<Instant>,
+    /// Time connected before the current state was entered
+    connected: Duration,
+    attempt_started: Option<Instant>,
+    disconnected_at: Option<Instant>,
+    time_to_first_message: Option<Duration>,
+    reconnects: u64,
+    reconnect_time: Duration,
+}
+
+impl ConnectionTracker {
+    /// Apply the client's next transition
+    pub fn record(&mut self, transition: &StateTransition) {
+        let StateTransition {
+            from,
+            to,
+            in_previous,
+            at,
+            ..
+        } = *transition;
+
+        if from.is_connected() {
+            self.connected += in_previous;
+        }
+        if from == ConnectionState::Connecting {
+            self.attempt_started = Some(at.checked_sub(in_previous).unwrap_or(at));
+        }
+        if to == ConnectionState::Streaming {
+            self.time_to_first_message = self.attempt_started.map(|started| at - started);
+        }
+
+        if from.is_connected() && !to.is_connected() {
+            self.disconnected_at = Some(at);
+        }
+        if to.is_connected() {
+            if let Some(disconnected_at) = self.disconnected_at.take() {
+                self.reconnects += 1;
+                self.reconnect_time += at - disconnected_at;
+            }
+        }
+
+        self.state = to;
+        self.entered = Some(at);
+    }
+
+    /// Statistics as of `now`, with uptime relative to `since`
+    pub fn stats_at(&self, since: Instant, now: Instant) -> SourceConnectionStats {
+        let mut connected = self.connected;
+        if let (true, Some(entered)) = (self.state.is_connected(), self.entered) {
+            connected += now.saturating_duration_since(entered);
+        }
+        let elapsed = now.saturating_duration_since(since).as_secs_f64();
+        let uptime_pct = if elapsed > 0.0 {
+            (connected.as_secs_f64() / elapsed * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        SourceConnectionStats {
+            state: self.state,
+            uptime_pct,
+            connected_seconds: connected.as_secs_f64(),
+            time_to_first_message_ms: self
+                .time_to_first_message
+                .map(|duration| duration.as_micros() as f64 / 1000.0),
+            reconnects: self.reconnects,
+            reconnect_seconds: self.reconnect_time.as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds transitions at offsets from a fixed start
+    struct Timeline {
+        start: Instant,
+        tracker: ConnectionTracker,
+        state: ConnectionState,
+        entered: Instant,
+    }
+
+    impl Timeline {
+        fn new() -> Self {
+            let start = Instant::now();
+            Self {
+                start,
+                tracker: ConnectionTracker::default(),
+                state: ConnectionState::Connecting,
+                entered: start,
+            }
+        }
+
+        fn at(&self, ms: u64) -> Instant {
+            self.start + Duration::from_millis(ms)
+        }
+
+        fn go(&mut self, ms: u64, to: ConnectionState) {
+            let at = self.at(ms);
+            self.tracker.record(&StateTransition {
+                source: "binance",
+                from: self.state,
+                to,
+                in_previous: at - self.entered,
+                at,
+            });
+            self.state = to;
+            self.entered = at;
+        }
+    }
+
+    #[test]
+    fn test_uptime_first_message_and_reconnects() {
+        let mut timeline = Timeline::new();
+        timeline.go(200, ConnectionState::Subscribed);
+        timeline.go(300, ConnectionState::Streaming);
+
+        let stats = timeline.tracker.stats_at(timeline.start, timeline.at(1300));
+        assert_eq!(stats.state, ConnectionState::Streaming);
+        assert_eq!(stats.time_to_first_message_ms, Some(300.0));
+        assert!((stats.connected_seconds - 1.1).abs() < 1e-9);
+        assert!((stats.uptime_pct - 1100.0 / 13.0).abs() < 1e-9);
+        assert_eq!(stats.reconnects, 0);
+
+        // Lost at 1.3s, then subscribed again 700ms later
+        timeline.go(1300, ConnectionState::Backoff { attempt: 1 });
+        timeline.go(1800, ConnectionState::Connecting);
+        timeline.go(2000, ConnectionState::Subscribed);
+        timeline.go(2050, ConnectionState::Streaming);
+
+        let stats = timeline.tracker.stats_at(timeline.start, timeline.at(3000));
+        assert_eq!(stats.reconnects, 1);
+        assert!((stats.avg_reconnect_ms() - 700.0).abs() < 1e-6);
+        assert_eq!(stats.time_to_first_message_ms, Some(250.0));
+        // 1.1s before the loss plus 1s since resubscribing
+        assert!((stats.connected_seconds - 2.1).abs() < 1e-9);
+        assert!((stats.uptime_pct - 70.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_never_connected() {
+        let mut timeline = Timeline::new();
+        timeline.go(100, ConnectionState::Backoff { attempt: 1 });
+        timeline.go(500, ConnectionState::Failed);
+
+        let stats = timeline.tracker.stats_at(timeline.start, timeline.at(1000));
+        assert_eq!(stats.state, ConnectionState::Failed);
+        assert_eq!(stats.uptime_pct, 0.0);
+        assert_eq!(stats.time_to_first_message_ms, None);
+        assert_eq!(stats.reconnects, 0);
+        assert_eq!(stats.avg_reconnect_ms(), 0.0);
+    }
+}
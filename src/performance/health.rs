@@ -0,0 +1,264 @@
+use crate::config::TradingPair;
+use crate::price::PriceCache;
+use crate::websocket::state::ConnectionState;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Records when a loop last completed an iteration
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat {
+    last: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Heartbeat {
+    /// Record an iteration now
+    pub fn beat(&self) {
+        *self.lock() = Some(Instant::now());
+    }
+
+    /// Time since the last iteration, if there was one
+    pub fn since_last(&self) -> Option<Duration> {
+        self.lock().map(|last| last.elapsed())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Outcome of one component's check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentHealth {
+    /// e.g. `binance`, `prices/sol-usdt` or `detector/sol-usdt`
+    pub component: String,
+    pub healthy: bool,
+    pub detail: String,
+}
+
+/// Result of a health or readiness check, served as the JSON body
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// `ok` or `unavailable`
+    pub status: &'static str,
+    pub checks: Vec<ComponentHealth>,
+}
+
+impl HealthReport {
+    fn new(checks: Vec<ComponentHealth>) -> Self {
+        let healthy = checks.iter().all(|check| check.healthy);
+        Self {
+            status: if healthy { "ok" } else { "unavailable" },
+            checks,
+        }
+    }
+
+    /// Whether every component passed
+    pub fn is_healthy(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+/// Decides liveness and readiness from the feeds, price caches and detector loops
+///
+/// Liveness only fails on conditions a restart could fix: a client that stopped
+/// reconnecting or a detector loop that stopped ticking. Readiness also needs every
+/// client subscribed or streaming and fresh Binance and DEX prices for every pair.
+#[derive(Debug, Default)]
+pub struct HealthCheck {
+    connections: Vec<(&'static str, watch::Receiver<ConnectionState>)>,
+    price_caches: Vec<(TradingPair, Arc<PriceCache>)>,
+    max_price_age_ms: u64,
+    detectors: Vec<(TradingPair, Heartbeat)>,
+    detector_timeout: Duration,
+}
+
+impl HealthCheck {
+    /// Health check with no components, which always passes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the connection state of each client, by source name
+    pub fn with_connections(
+        mut self,
+        connections: Vec<(&'static str, watch::Receiver<ConnectionState>)>,
+    ) -> Self {
+        self.connections = connections;
+        self
+    }
+
+    /// Require prices no older than `max_age_ms` in each pair's cache
+    pub fn with_price_caches(
+        mut self,
+        price_caches: Vec<(TradingPair, Arc<PriceCache>)>,
+        max_age_ms: u64,
+    ) -> Self {
+        self.price_caches = price_caches;
+        self.max_price_age_ms = max_age_ms;
+        self
+    }
+
+    /// Require each pair's detector loop to tick at least every `timeout`
+    pub fn with_detectors(
+        mut self,
+        detectors: Vec<(TradingPair, Heartbeat)>,
+        timeout: Duration,
+    ) -> Self {
+        self.detectors = detectors;
+        self.detector_timeout = timeout;
+        self
+    }
+
+    /// Whether the watcher is working or can recover without a restart
+    pub fn liveness(&self) -> HealthReport {
+        let mut checks = self.connection_checks(|state| state != ConnectionState::Failed);
+        checks.extend(self.detector_checks());
+        HealthReport::new(checks)
+    }
+
+    /// Whether the watcher is currently detecting on live prices
+    pub fn readiness(&self) -> HealthReport {
+        let mut checks = self.connection_checks(ConnectionState::is_connected);
+
+        checks.extend(self.price_caches.iter().map(|(pair, cache)| {
+            let fresh = cache.has_fresh_prices(self.max_price_age_ms);
+            ComponentHealth {
+                component: format!("prices/{}", pair.name()),
+                healthy: fresh,
+                detail: if fresh {
+                    "fresh Binance and DEX prices".to_string()
+                } else {
+                    format!(
+                        "no fresh Binance price or no fresh DEX price within {}ms",
+                        self.max_price_age_ms
+                    )
+                },
+            }
+        }));
+        checks.extend(self.detector_checks());
+        HealthReport::new(checks)
+    }
+
+    fn connection_checks(&self, healthy: impl Fn(ConnectionState) -> bool) -> Vec<ComponentHealth> {
+        self.connections
+            .iter()
+            .map(|(name, state)| {
+                let state = *state.borrow();
+                ComponentHealth {
+                    component: name.to_string(),
+                    healthy: healthy(state),
+                    detail: state.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn detector_checks(&self) -> impl Iterator<Item = ComponentHealth> + '_ {
+        self.detectors.iter().map(|(pair, heartbeat)| {
+            let (healthy, detail) = match heartbeat.since_last() {
+                Some(since) if since <= self.detector_timeout => {
+                    (true, format!("last check {}ms ago", since.as_millis()))
+                }
+                Some(since) => (
+                    false,
+                    format!(
+                        "no check for {}ms (limit {}ms)",
+                        since.as_millis(),
+                        self.detector_timeout.as_millis()
+                    ),
+                ),
+                None => (false, "no check yet".to_string()),
+            };
+            ComponentHealth {
+                component: format!("detector/{}", pair.name()),
+                healthy,
+                detail,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::{PriceSource, PriceUpdate};
+
+    fn check<'a>(report: &'a HealthReport, component: &str) -> &'a ComponentHealth {
+        report
+            .checks
+            .iter()
+            .find(|check| check.component == component)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_readiness_needs_connections_prices_and_ticking_detectors() {
+        let (binance, binance_state) = watch::channel(ConnectionState::Streaming);
+        let (solana, solana_state) = watch::channel(ConnectionState::Connecting);
+        let cache = Arc::new(PriceCache::new());
+        let heartbeat = Heartbeat::default();
+        let health = HealthCheck::new()
+            .with_connections(vec![("binance", binance_state), ("solana", solana_state)])
+            .with_price_caches(vec![(TradingPair::SOL_USDT, Arc::clone(&cache))], 5000)
+            .with_detectors(
+                vec![(TradingPair::SOL_USDT, heartbeat.clone())],
+                Duration::from_secs(5),
+            );
+
+        let report = health.readiness();
+        assert!(!report.is_healthy());
+        assert_eq!(report.status, "unavailable");
+        assert!(check(&report, "binance").healthy);
+        assert_eq!(check(&report, "solana").detail, "connecting");
+        assert!(!check(&report, "prices/sol-usdt").healthy);
+        assert_eq!(check(&report, "detector/sol-usdt").detail, "no check yet");
+
+        // Subscribed is ready before the first update arrives
+        solana.send_replace(ConnectionState::Subscribed);
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            150.0,
+        ));
+        cache.update(&PriceUpdate::new(
+            PriceSource::Solana,
+            TradingPair::SOL_USDT,
+            150.2,
+        ));
+        heartbeat.beat();
+        assert!(health.readiness().is_healthy());
+
+        // Backing off is not ready but still alive
+        binance.send_replace(ConnectionState::Backoff { attempt: 2 });
+        let report = health.readiness();
+        assert_eq!(
+            check(&report, "binance").detail,
+            "backing off before reconnect attempt 2"
+        );
+        assert!(!report.is_healthy());
+        assert!(health.liveness().is_healthy());
+
+        binance.send_replace(ConnectionState::Failed);
+        assert!(!check(&health.liveness(), "binance").healthy);
+    }
+
+    #[test]
+    fn test_stalled_detector_fails_liveness() {
+        let heartbeat = Heartbeat::default();
+        heartbeat.beat();
+        let health = HealthCheck::new().with_detectors(
+            vec![(TradingPair::SOL_USDT, heartbeat)],
+            Duration::from_millis(20),
+        );
+        assert!(health.liveness().is_healthy());
+
+        std::thread::sleep(Duration::from_millis(40));
+        let report = health.liveness();
+        assert!(!report.is_healthy());
+        assert!(check(&report, "detector/sol-usdt")
+            .detail
+            .starts_with("no check for"));
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the exported latency buckets in seconds
+pub const LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// Default period percentiles are computed over
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Sub-buckets per power of two; values are kept to within 1/32 (about 3%)
+const SUB_BUCKET_BITS: u32 = 5;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+
+/// Largest value resolved, in microseconds (about 71 minutes); larger values count here
+const MAX_MICROS: u64 = (1 << 32) - 1;
+
+/// Number of log buckets needed up to [`MAX_MICROS`]
+const LOG_BUCKETS: usize = bucket_index(MAX_MICROS) + 1;
+
+/// The window is kept as this many slices, so samples expire a slice at a time
+const WINDOW_SLICES: u32 = 6;
+
+/// Index of the log bucket holding `micros`
+///
+/// Values below [`SUB_BUCKETS`] get a bucket each; above that every power of two is
+/// split into [`SUB_BUCKETS`] equal buckets.
+const fn bucket_index(micros: u64) -> usize {
+    if micros < SUB_BUCKETS {
+        return micros as usize;
+    }
+    let shift = 63 - micros.leading_zeros() - SUB_BUCKET_BITS;
+    ((shift as u64 + 1) * SUB_BUCKETS + (micros >> shift) - SUB_BUCKETS) as usize
+}
+
+/// Largest value in microseconds that falls in log bucket `index`
+fn bucket_upper_micros(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let shift = index / SUB_BUCKETS - 1;
+    let sub = index % SUB_BUCKETS + SUB_BUCKETS;
+    ((sub + 1) << shift) - 1
+}
+
+/// Latency distribution of one stage
+///
+/// Counts since startup are kept in the fixed [`LATENCY_BUCKETS`] for export. For
+/// percentiles, samples are also counted in log buckets (HDR-style, about 3% precision)
+/// over a sliding window; the window moves a sixth of its length at a time, so a
+/// percentile covers between one and 7/6 windows of samples.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    window: Duration,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Samples per exported bucket (not cumulative); the last counts samples above every bound
+    totals: [u64; LATENCY_BUCKETS.len() + 1],
+    total_micros: u64,
+    /// Log bucket counts, oldest slice first, each with the time it started
+    slices: VecDeque<(Instant, LogBuckets)>,
+}
+
+#[derive(Debug, Clone)]
+struct LogBuckets {
+    counts: Vec<u64>,
+    count: u64,
+    sum_micros: u64,
+    max_micros: u64,
+}
+
+/// Percentiles of a stage's latency over the metrics window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub p999_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Point-in-time copy of a [`LatencyHistogram`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistogramSnapshot {
+    /// Upper bound in seconds and the number of samples at or below it, since startup
+    pub buckets: Vec<(f64, u64)>,
+    /// Samples since startup
+    pub count: u64,
+    /// Sum of the samples since startup
+    pub sum_seconds: f64,
+    /// Distribution over the window
+    pub window: LatencyPercentiles,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl LogBuckets {
+    fn new() -> Self {
+        Self {
+            counts: vec![0; LOG_BUCKETS],
+            count: 0,
+            sum_micros: 0,
+            max_micros: 0,
+        }
+    }
+
+    fn record(&mut self, micros: u64) {
+        self.counts[bucket_index(micros.min(MAX_MICROS))] += 1;
+        self.count += 1;
+        self.sum_micros = self.sum_micros.saturating_add(micros);
+        self.max_micros = self.max_micros.max(micros);
+    }
+
+    fn merge(&mut self, other: &LogBuckets) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.count += other.count;
+        self.sum_micros = self.sum_micros.saturating_add(other.sum_micros);
+        self.max_micros = self.max_micros.max(other.max_micros);
+    }
+
+    /// Smallest bucket value at or above the given fraction of samples, capped at the max
+    fn value_at(&self, quantile: f64) -> u64 {
+        let rank = ((quantile * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_upper_micros(index).min(self.max_micros);
+            }
+        }
+        self.max_micros
+    }
+
+    fn percentiles(&self) -> LatencyPercentiles {
+        if self.count == 0 {
+            return LatencyPercentiles::default();
+        }
+        let ms = |micros: u64| micros as f64 / 1000.0;
+        LatencyPercentiles {
+            count: self.count,
+            mean_ms: ms(self.sum_micros) / self.count as f64,
+            p50_ms: ms(self.value_at(0.5)),
+            p90_ms: ms(self.value_at(0.9)),
+            p99_ms: ms(self.value_at(0.99)),
+            p999_ms: ms(self.value_at(0.999)),
+            max_ms: ms(self.max_micros),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Histogram reporting percentiles over the last `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Period percentiles are computed over
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Count one sample
+    pub fn record(&self, duration: Duration) {
+        self.record_at(duration, Instant::now());
+    }
+
+    /// Count one sample taken at `now`
+    pub fn record_at(&self, duration: Duration, now: Instant) {
+        let micros = duration.as_micros().min(u64::MAX as u128) as u64;
+        let seconds = duration.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        let mut inner = self.lock();
+        inner.totals[bucket] += 1;
+        inner.total_micros = inner.total_micros.saturating_add(micros);
+
+        let slice = self.slice();
+        let current = match inner.slices.back() {
+            Some((start, _)) => now.saturating_duration_since(*start) < slice,
+            None => false,
+        };
+        if !current {
+            inner.slices.push_back((now, LogBuckets::new()));
+        }
+        self.expire(&mut inner, now);
+        if let Some((_, buckets)) = inner.slices.back_mut() {
+            buckets.record(micros);
+        }
+    }
+
+    /// Counts since startup and percentiles over the window
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        self.snapshot_at(Instant::now())
+    }
+
+    /// Like [`LatencyHistogram::snapshot`], with the window ending at `now`
+    pub fn snapshot_at(&self, now: Instant) -> HistogramSnapshot {
+        let mut inner = self.lock();
+        self.expire(&mut inner, now);
+
+        let mut cumulative = 0;
+        let buckets = LATENCY_BUCKETS
+            .iter()
+            .zip(&inner.totals)
+            .map(|(&bound, count)| {
+                cumulative += count;
+                (bound, cumulative)
+            })
+            .collect();
+        let count = cumulative + inner.totals[LATENCY_BUCKETS.len()];
+
+        let mut window = LogBuckets::new();
+        for (_, slice) in &inner.slices {
+            window.merge(slice);
+        }
+
+        HistogramSnapshot {
+            buckets,
+            count,
+            sum_seconds: inner.total_micros as f64 / 1_000_000.0,
+            window: window.percentiles(),
+        }
+    }
+
+    /// Forget every sample
+    #[cfg(test)]
+    pub fn reset(&self) {
+        *self.lock() = Inner::default();
+    }
+
+    fn slice(&self) -> Duration {
+        (self.window / WINDOW_SLICES).max(Duration::from_millis(1))
+    }
+
+    /// Drop slices that ended before the window
+    fn expire(&self, inner: &mut Inner, now: Instant) {
+        let slice = self.slice();
+        while let Some((start, _)) = inner.slices.front() {
+            if now.saturating_duration_since(*start) < self.window + slice {
+                break;
+            }
+            inner.slices.pop_front();
+        }
+    }
+
+    /// Lock the state, recovering it if a panic poisoned the lock
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_are_cumulative() {
+        let histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_micros(300));
+        histogram.record(Duration::from_millis(1));
+        histogram.record(Duration::from_millis(40));
+        histogram.record(Duration::from_secs(5));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 4);
+        assert_eq!(snapshot.buckets[0], (0.0005, 1));
+        // A sample on a bound falls in that bucket
+        assert_eq!(snapshot.buckets[1], (0.001, 2));
+        assert_eq!(snapshot.buckets[6], (0.05, 3));
+        assert_eq!(snapshot.buckets.last(), Some(&(2.5, 3)));
+        assert!((snapshot.sum_seconds - 5.0413).abs() < 1e-9);
+
+        histogram.reset();
+        assert_eq!(histogram.snapshot().count, 0);
+    }
+
+    #[test]
+    fn test_log_buckets_cover_every_value() {
+        let mut previous = None;
+        for micros in (0..5000).chain([MAX_MICROS - 1, MAX_MICROS]) {
+            let index = bucket_index(micros);
+            assert!(micros <= bucket_upper_micros(index), "{}", micros);
+            if let Some(previous) = previous {
+                assert!(index >= previous);
+            }
+            previous = Some(index);
+        }
+        assert_eq!(bucket_index(MAX_MICROS), LOG_BUCKETS - 1);
+        // Buckets stay within 1/32 of their values
+        let index = bucket_index(1_000_000);
+        assert!(bucket_upper_micros(index) - 1_000_000 < 1_000_000 / 32);
+    }
+
+    #[test]
+    fn test_percentiles_show_the_tail() {
+        let histogram = LatencyHistogram::default();
+        let now = Instant::now();
+        for _ in 0..990 {
+            histogram.record_at(Duration::from_millis(1), now);
+        }
+        for _ in 0..9 {
+            histogram.record_at(Duration::from_millis(50), now);
+        }
+        histogram.record_at(Duration::from_millis(800), now);
+
+        let window = histogram.snapshot_at(now).window;
+        assert_eq!(window.count, 1000);
+        assert!((window.p50_ms - 1.0).abs() <= 1.0 / 32.0, "{:?}", window);
+        assert!((window.p90_ms - 1.0).abs() <= 1.0 / 32.0, "{:?}", window);
+        assert!((window.p99_ms - 1.0).abs() <= 1.0 / 32.0, "{:?}", window);
+        assert!((window.p999_ms - 50.0).abs() <= 50.0 / 32.0, "{:?}", window);
+        assert_eq!(window.max_ms, 800.0);
+        assert!((window.mean_ms - 2.24).abs() < 1e-9, "{:?}", window);
+    }
+
+    #[test]
+    fn test_old_samples_leave_the_window() {
+        let histogram = LatencyHistogram::new(Duration::from_secs(60));
+        let start = Instant::now();
+        histogram.record_at(Duration::from_millis(900), start);
+        histogram.record_at(Duration::from_millis(2), start + Duration::from_secs(30));
+
+        let window = histogram
+            .snapshot_at(start + Duration::from_secs(60))
+            .window;
+        assert_eq!(window.count, 2);
+        assert_eq!(window.max_ms, 900.0);
+
+        // A slice expires once all of it is older than the window
+        let snapshot = histogram.snapshot_at(start + Duration::from_secs(71));
+        assert_eq!(snapshot.window.count, 1);
+        assert_eq!(snapshot.window.max_ms, 2.0);
+        // Counts since startup are unaffected
+        assert_eq!(snapshot.count, 2);
+
+        let snapshot = histogram.snapshot_at(start + Duration::from_secs(200));
+        assert_eq!(snapshot.window, LatencyPercentiles::default());
+        assert_eq!(snapshot.count, 2);
+    }
+}
@@ -1,6 +1,10 @@
+use super::connection::{ConnectionTracker, SourceConnectionStats};
+use super::histogram::{HistogramSnapshot, LatencyHistogram, LatencyPercentiles, DEFAULT_WINDOW};
+use crate::config::TradingPair;
+use crate::price::PriceSource;
+use crate::websocket::state::{ConnectionState, StateTransition};
 use chrono::{DateTime, Utc};
-use log::debug;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
@@ -12,6 +16,7 @@ pub struct PerformanceMetrics {
     pub throughput: ThroughputStats,
     pub connection: ConnectionStats,
     pub processing: ProcessingStats,
+    pub market: MarketStats,
 }
 
 /// Overall performance summary
@@ -19,6 +24,9 @@ pub struct PerformanceMetrics {
 pub struct PerformanceSummary {
     pub uptime_seconds: u64,
     pub total_opportunities: u64,
+    pub solana_messages: u64,
+    pub binance_messages: u64,
+    /// Mean price processing latency over the metrics window
     #[allow(dead_code)]
     pub avg_processing_latency_ms: f64,
     pub solana_msg_rate: f64,
@@ -39,24 +47,120 @@ pub struct ThroughputStats {
 /// Connection reliability statistics
 #[derive(Debug, Clone)]
 pub struct ConnectionStats {
-    pub solana_uptime_pct: f64,
-    pub binance_uptime_pct: f64,
+    /// Share of the time the Solana client was subscribed or streaming, if it is tracked
+    pub solana_uptime_pct: Option<f64>,
+    /// Share of the time the Binance client was subscribed or streaming, if it is tracked
+    pub binance_uptime_pct: Option<f64>,
+    /// Connection attempts after the first, across clients
     pub total_reconnections: u64,
-    #[allow(dead_code)]
+    /// Mean time to restore a lost connection, across clients
     pub avg_reconnect_time_ms: f64,
-    #[allow(dead_code)]
     pub last_connection_failure: Option<DateTime<Utc>>,
+    /// Statistics of each client, by source name
+    pub sources: BTreeMap<&'static str, SourceConnectionStats>,
+    /// Average receive time minus Solana slot time
+    pub solana_clock_skew_ms: f64,
+    /// Average receive time minus Binance event time
+    pub binance_clock_skew_ms: f64,
 }
 
 /// Processing pipeline performance statistics
+///
+/// Latencies are means over the metrics window; `stage_latencies` has their percentiles.
 #[derive(Debug, Clone)]
 pub struct ProcessingStats {
     pub price_processing_latency_ms: f64,
     pub arbitrage_detection_latency_ms: f64,
     pub output_formatting_latency_ms: f64,
     pub total_pipeline_latency_ms: f64,
+    /// Time from receiving a price update to the check it triggered
+    pub event_to_detection_latency_ms: f64,
+    /// Period latency percentiles are computed over
+    pub latency_window: Duration,
     pub messages_processed: u64,
     pub errors_encountered: u64,
+    /// Prices rejected during validation, by reason code
+    pub rejections: BTreeMap<&'static str, u64>,
+    /// Latency distribution of each stage, by stage name
+    pub stage_latencies: Vec<(&'static str, HistogramSnapshot)>,
+}
+
+impl ProcessingStats {
+    /// Latency percentiles of `stage` over the metrics window
+    pub fn percentiles(&self, stage: &str) -> LatencyPercentiles {
+        self.stage_latencies
+            .iter()
+            .find(|(name, _)| *name == stage)
+            .map(|(_, histogram)| histogram.window)
+            .unwrap_or_default()
+    }
+}
+
+/// Pipeline stages with a latency histogram, in report order
+pub const STAGES: [&str; 4] = [
+    "price_processing",
+    "arbitrage_detection",
+    "output_formatting",
+    "event_to_detection",
+];
+
+/// What the detectors have seen in the market
+#[derive(Debug, Clone, Default)]
+pub struct MarketStats {
+    /// Opportunities found, by buy and sell source id
+    pub opportunities_by_direction: BTreeMap<(&'static str, &'static str), u64>,
+    /// Latest DEX/Binance spread percentage, by pair name and DEX source id
+    pub spreads: BTreeMap<(&'static str, &'static str), f64>,
+}
+
+/// Rejection counts as `reason count, ...`, or `none`
+pub fn format_rejections(rejections: &BTreeMap<&'static str, u64>) -> String {
+    if rejections.is_empty() {
+        return "none".to_string();
+    }
+    rejections
+        .iter()
+        .map(|(reason, count)| format!("{} {}", reason, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Uptime percentage as `99.5%`, or `n/a` for an untracked client
+pub fn format_uptime(uptime_pct: Option<f64>) -> String {
+    uptime_pct.map_or("n/a".to_string(), |pct| format!("{:.1}%", pct))
+}
+
+/// A client's connection as `streaming, 99.5% up, first update after 420ms, ...`
+pub fn format_connection(stats: Option<&SourceConnectionStats>) -> String {
+    let Some(stats) = stats else {
+        return "not tracked".to_string();
+    };
+    let first_update = stats
+        .time_to_first_message_ms
+        .map_or("no update yet".to_string(), |ms| {
+            format!("first update after {:.0}ms", ms)
+        });
+    format!(
+        "{}, {:.1}% up, {}, {} reconnects (avg {:.0}ms)",
+        stats.state.name(),
+        stats.uptime_pct,
+        first_update,
+        stats.reconnects,
+        stats.avg_reconnect_ms()
+    )
+}
+
+/// Latency percentiles as `p50 1.00ms | ... | max 9.00ms (n samples)`
+pub fn format_percentiles(percentiles: &LatencyPercentiles) -> String {
+    format!(
+        "p50 {:.2}ms | p90 {:.2}ms | p99 {:.2}ms | p999 {:.2}ms | max {:.2}ms ({} samples)",
+        percentiles.p50_ms,
+        percentiles.p90_ms,
+        percentiles.p99_ms,
+        percentiles.p999_ms,
+        percentiles.max_ms,
+        percentiles.count
+    )
 }
 
 /// Thread-safe metrics collector for tracking performance data
@@ -69,16 +173,19 @@ pub struct MetricsCollector {
     binance_messages: AtomicU64,
     opportunities_found: AtomicU64,
 
-    // Connection tracking
+    // Connection tracking, from each client's state transitions
     reconnection_count: AtomicU64,
-    solana_connection_time: Arc<RwLock<Duration>>,
-    binance_connection_time: Arc<RwLock<Duration>>,
-    _last_reconnect_start: Arc<RwLock<Option<Instant>>>,
+    connections: Arc<RwLock<BTreeMap<&'static str, ConnectionTracker>>>,
+
+    // Processing latency, per stage
+    processing_latency: LatencyHistogram,
+    arbitrage_latency: LatencyHistogram,
+    output_latency: LatencyHistogram,
+    detection_latency: LatencyHistogram,
 
-    // Processing metrics
-    processing_times: Arc<RwLock<VecDeque<Duration>>>,
-    arbitrage_times: Arc<RwLock<VecDeque<Duration>>>,
-    output_times: Arc<RwLock<VecDeque<Duration>>>,
+    // Receive time minus source time, per venue (signed milliseconds)
+    solana_clock_skews: Arc<RwLock<VecDeque<f64>>>,
+    binance_clock_skews: Arc<RwLock<VecDeque<f64>>>,
 
     // Queue depth tracking
     current_queue_depth: AtomicUsize,
@@ -86,7 +193,12 @@ pub struct MetricsCollector {
 
     // Error tracking
     processing_errors: AtomicU64,
+    rejections: Arc<RwLock<BTreeMap<&'static str, u64>>>,
     connection_failures: Arc<RwLock<Vec<DateTime<Utc>>>>,
+
+    // Market state
+    opportunities_by_direction: Arc<RwLock<BTreeMap<(&'static str, &'static str), u64>>>,
+    spreads: Arc<RwLock<BTreeMap<(&'static str, &'static str), f64>>>,
 }
 
 impl Default for MetricsCollector {
@@ -98,22 +210,31 @@ impl Default for MetricsCollector {
 impl MetricsCollector {
     /// Create a new metrics collector
     pub fn new() -> Self {
+        Self::with_window(DEFAULT_WINDOW)
+    }
+
+    /// Create a metrics collector reporting latency percentiles over the last `window`
+    pub fn with_window(window: Duration) -> Self {
         Self {
             start_time: Instant::now(),
             solana_messages: AtomicU64::new(0),
             binance_messages: AtomicU64::new(0),
             opportunities_found: AtomicU64::new(0),
             reconnection_count: AtomicU64::new(0),
-            solana_connection_time: Arc::new(RwLock::new(Duration::ZERO)),
-            binance_connection_time: Arc::new(RwLock::new(Duration::ZERO)),
-            _last_reconnect_start: Arc::new(RwLock::new(None)),
-            processing_times: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
-            arbitrage_times: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
-            output_times: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
+            connections: Arc::new(RwLock::new(BTreeMap::new())),
+            processing_latency: LatencyHistogram::new(window),
+            arbitrage_latency: LatencyHistogram::new(window),
+            output_latency: LatencyHistogram::new(window),
+            detection_latency: LatencyHistogram::new(window),
+            solana_clock_skews: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
+            binance_clock_skews: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
             current_queue_depth: AtomicUsize::new(0),
             max_queue_depth: AtomicUsize::new(0),
             processing_errors: AtomicU64::new(0),
+            rejections: Arc::new(RwLock::new(BTreeMap::new())),
             connection_failures: Arc::new(RwLock::new(Vec::new())),
+            opportunities_by_direction: Arc::new(RwLock::new(BTreeMap::new())),
+            spreads: Arc::new(RwLock::new(BTreeMap::new())),
         }
     }
 
@@ -127,74 +248,81 @@ impl MetricsCollector {
         self.binance_messages.fetch_add(1, Ordering::Relaxed);
     }
 
-    /// Record an arbitrage opportunity found
-    pub fn record_opportunity(&self) {
+    /// Record an arbitrage opportunity found, buying on `buy` and selling on `sell`
+    pub fn record_opportunity(&self, buy: PriceSource, sell: PriceSource) {
         self.opportunities_found.fetch_add(1, Ordering::Relaxed);
-    }
-
-    /// Record a connection reconnection attempt
-    #[allow(dead_code)]
-    pub fn record_reconnection(&self) {
-        self.reconnection_count.fetch_add(1, Ordering::Relaxed);
-        if let Ok(mut start) = self._last_reconnect_start.write() {
-            *start = Some(Instant::now());
+        if let Ok(mut directions) = self.opportunities_by_direction.write() {
+            *directions.entry((buy.id(), sell.id())).or_default() += 1;
         }
     }
 
-    /// Record successful reconnection completion
-    #[allow(dead_code)]
-    pub fn record_reconnection_complete(&self) {
-        if let Ok(start_guard) = self._last_reconnect_start.read() {
-            if let Some(start) = *start_guard {
-                let duration = start.elapsed();
-                // Could track reconnection times for averaging
-                debug!("Reconnection completed in {:?}", duration);
-            }
+    /// Record the latest spread percentage between `dex` and Binance for `pair`
+    pub fn set_spread(&self, pair: TradingPair, dex: PriceSource, spread_percentage: f64) {
+        if let Ok(mut spreads) = self.spreads.write() {
+            spreads.insert((pair.name(), dex.id()), spread_percentage);
         }
     }
 
-    /// Record connection uptime for Solana
-    #[allow(dead_code)]
-    pub fn record_solana_uptime(&self, connected_duration: Duration) {
-        if let Ok(mut uptime) = self.solana_connection_time.write() {
-            *uptime = connected_duration;
+    /// Record a client's connection state transition
+    ///
+    /// Uptime, time to first message and reconnect durations are derived from the
+    /// transitions; entering Connecting counts as a reconnection and entering Backoff or
+    /// Failed as a connection failure.
+    pub fn record_connection_transition(&self, transition: &StateTransition) {
+        if let Ok(mut connections) = self.connections.write() {
+            connections
+                .entry(transition.source)
+                .or_default()
+                .record(transition);
+        }
+        match transition.to {
+            ConnectionState::Connecting => self.record_reconnection(),
+            ConnectionState::Backoff { .. } | ConnectionState::Failed => {
+                self.record_connection_failure()
+            }
+            ConnectionState::Subscribed | ConnectionState::Streaming => {}
         }
     }
 
-    /// Record connection uptime for Binance
-    #[allow(dead_code)]
-    pub fn record_binance_uptime(&self, connected_duration: Duration) {
-        if let Ok(mut uptime) = self.binance_connection_time.write() {
-            *uptime = connected_duration;
-        }
+    fn record_reconnection(&self) {
+        self.reconnection_count.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Record price processing latency
     pub fn record_processing_time(&self, duration: Duration) {
-        if let Ok(mut times) = self.processing_times.write() {
-            times.push_back(duration);
-            if times.len() > 1000 {
-                times.pop_front();
-            }
-        }
+        self.processing_latency.record(duration);
     }
 
     /// Record arbitrage detection latency
     pub fn record_arbitrage_time(&self, duration: Duration) {
-        if let Ok(mut times) = self.arbitrage_times.write() {
-            times.push_back(duration);
-            if times.len() > 1000 {
-                times.pop_front();
-            }
-        }
+        self.arbitrage_latency.record(duration);
     }
 
     /// Record output formatting latency
     pub fn record_output_time(&self, duration: Duration) {
-        if let Ok(mut times) = self.output_times.write() {
-            times.push_back(duration);
-            if times.len() > 1000 {
-                times.pop_front();
+        self.output_latency.record(duration);
+    }
+
+    /// Record the time from a price update's event time to the check it triggered
+    pub fn record_detection_latency(&self, duration: Duration) {
+        self.detection_latency.record(duration);
+    }
+
+    /// Record how long after its slot time a Solana update was received
+    pub fn record_solana_clock_skew(&self, skew_ms: f64) {
+        Self::push_skew(&self.solana_clock_skews, skew_ms);
+    }
+
+    /// Record how long after its event time a Binance update was received
+    pub fn record_binance_clock_skew(&self, skew_ms: f64) {
+        Self::push_skew(&self.binance_clock_skews, skew_ms);
+    }
+
+    fn push_skew(skews: &RwLock<VecDeque<f64>>, skew_ms: f64) {
+        if let Ok(mut skews) = skews.write() {
+            skews.push_back(skew_ms);
+            if skews.len() > 1000 {
+                skews.pop_front();
             }
         }
     }
@@ -215,9 +343,23 @@ impl MetricsCollector {
         self.processing_errors.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a price rejected during validation
+    pub fn record_rejection(&self, reason: &'static str) {
+        if let Ok(mut rejections) = self.rejections.write() {
+            *rejections.entry(reason).or_default() += 1;
+        }
+    }
+
+    /// Prices rejected during validation so far, by reason code
+    pub fn rejections(&self) -> BTreeMap<&'static str, u64> {
+        self.rejections
+            .read()
+            .map(|rejections| rejections.clone())
+            .unwrap_or_default()
+    }
+
     /// Record a connection failure
-    #[allow(dead_code)]
-    pub fn record_connection_failure(&self) {
+    fn record_connection_failure(&self) {
         if let Ok(mut failures) = self.connection_failures.write() {
             failures.push(Utc::now());
             // Keep only recent failures (last 100)
@@ -232,13 +374,11 @@ impl MetricsCollector {
         self.start_time.elapsed().as_secs()
     }
 
-    /// Calculate average from a duration queue
-    fn avg_duration_ms(durations: &VecDeque<Duration>) -> f64 {
-        if durations.is_empty() {
-            0.0
-        } else {
-            let total_ms: u64 = durations.iter().map(|d| d.as_millis() as u64).sum();
-            total_ms as f64 / durations.len() as f64
+    /// Average of a skew queue
+    fn avg_skew_ms(skews: &RwLock<VecDeque<f64>>) -> f64 {
+        match skews.read() {
+            Ok(skews) if !skews.is_empty() => skews.iter().sum::<f64>() / skews.len() as f64,
+            _ => 0.0,
         }
     }
 
@@ -276,42 +416,40 @@ impl MetricsCollector {
             0.0
         };
 
-        // Processing times
-        let processing_avg = if let Ok(times) = self.processing_times.read() {
-            Self::avg_duration_ms(&times)
-        } else {
-            0.0
-        };
-
-        let arbitrage_avg = if let Ok(times) = self.arbitrage_times.read() {
-            Self::avg_duration_ms(&times)
-        } else {
-            0.0
-        };
-
-        let output_avg = if let Ok(times) = self.output_times.read() {
-            Self::avg_duration_ms(&times)
-        } else {
-            0.0
-        };
+        // Processing latency per stage
+        let stage_latencies: Vec<(&'static str, HistogramSnapshot)> = STAGES
+            .into_iter()
+            .zip([
+                &self.processing_latency,
+                &self.arbitrage_latency,
+                &self.output_latency,
+                &self.detection_latency,
+            ])
+            .map(|(stage, histogram)| (stage, histogram.snapshot()))
+            .collect();
+        let processing_avg = stage_latencies[0].1.window.mean_ms;
+        let arbitrage_avg = stage_latencies[1].1.window.mean_ms;
+        let output_avg = stage_latencies[2].1.window.mean_ms;
+        let detection_latency_avg = stage_latencies[3].1.window.mean_ms;
 
         // Connection stats
-        let solana_uptime_pct = if let Ok(uptime_duration) = self.solana_connection_time.read() {
-            if uptime > 0 {
-                (uptime_duration.as_secs() as f64 / uptime_f64) * 100.0
-            } else {
-                0.0
-            }
-        } else {
-            0.0
-        };
-
-        let binance_uptime_pct = if let Ok(uptime_duration) = self.binance_connection_time.read() {
-            if uptime > 0 {
-                (uptime_duration.as_secs() as f64 / uptime_f64) * 100.0
-            } else {
-                0.0
-            }
+        let sources: BTreeMap<&'static str, SourceConnectionStats> = self
+            .connections
+            .read()
+            .map(|connections| {
+                let now = Instant::now();
+                connections
+                    .iter()
+                    .map(|(&source, tracker)| (source, tracker.stats_at(self.start_time, now)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let (reconnects, reconnect_seconds) =
+            sources.values().fold((0, 0.0), |(count, seconds), stats| {
+                (count + stats.reconnects, seconds + stats.reconnect_seconds)
+            });
+        let avg_reconnect_time_ms = if reconnects > 0 {
+            reconnect_seconds * 1000.0 / reconnects as f64
         } else {
             0.0
         };
@@ -337,6 +475,8 @@ impl MetricsCollector {
             summary: PerformanceSummary {
                 uptime_seconds: uptime,
                 total_opportunities: opportunities,
+                solana_messages: solana_msgs,
+                binance_messages: binance_msgs,
                 avg_processing_latency_ms: processing_avg,
                 solana_msg_rate: solana_rate,
                 binance_msg_rate: binance_rate,
@@ -349,19 +489,38 @@ impl MetricsCollector {
                 processing_efficiency_pct: efficiency,
             },
             connection: ConnectionStats {
-                solana_uptime_pct,
-                binance_uptime_pct,
+                solana_uptime_pct: sources.get("solana").map(|stats| stats.uptime_pct),
+                binance_uptime_pct: sources.get("binance").map(|stats| stats.uptime_pct),
                 total_reconnections: self.reconnection_count.load(Ordering::Relaxed),
-                avg_reconnect_time_ms: 0.0, // Could be enhanced to track this
+                avg_reconnect_time_ms,
                 last_connection_failure: last_failure,
+                solana_clock_skew_ms: Self::avg_skew_ms(&self.solana_clock_skews),
+                binance_clock_skew_ms: Self::avg_skew_ms(&self.binance_clock_skews),
+                sources,
             },
             processing: ProcessingStats {
                 price_processing_latency_ms: processing_avg,
                 arbitrage_detection_latency_ms: arbitrage_avg,
                 output_formatting_latency_ms: output_avg,
                 total_pipeline_latency_ms: processing_avg + arbitrage_avg + output_avg,
+                event_to_detection_latency_ms: detection_latency_avg,
+                latency_window: self.processing_latency.window(),
                 messages_processed: total_msgs,
                 errors_encountered: errors,
+                rejections: self.rejections(),
+                stage_latencies,
+            },
+            market: MarketStats {
+                opportunities_by_direction: self
+                    .opportunities_by_direction
+                    .read()
+                    .map(|directions| directions.clone())
+                    .unwrap_or_default(),
+                spreads: self
+                    .spreads
+                    .read()
+                    .map(|spreads| spreads.clone())
+                    .unwrap_or_default(),
             },
         }
     }
@@ -377,18 +536,35 @@ impl MetricsCollector {
         self.max_queue_depth.store(0, Ordering::Relaxed);
         self.processing_errors.store(0, Ordering::Relaxed);
 
-        if let Ok(mut times) = self.processing_times.write() {
-            times.clear();
+        if let Ok(mut skews) = self.solana_clock_skews.write() {
+            skews.clear();
         }
-        if let Ok(mut times) = self.arbitrage_times.write() {
-            times.clear();
-        }
-        if let Ok(mut times) = self.output_times.write() {
-            times.clear();
+        if let Ok(mut skews) = self.binance_clock_skews.write() {
+            skews.clear();
         }
         if let Ok(mut failures) = self.connection_failures.write() {
             failures.clear();
         }
+        if let Ok(mut connections) = self.connections.write() {
+            connections.clear();
+        }
+        if let Ok(mut rejections) = self.rejections.write() {
+            rejections.clear();
+        }
+        for histogram in [
+            &self.processing_latency,
+            &self.arbitrage_latency,
+            &self.output_latency,
+            &self.detection_latency,
+        ] {
+            histogram.reset();
+        }
+        if let Ok(mut directions) = self.opportunities_by_direction.write() {
+            directions.clear();
+        }
+        if let Ok(mut spreads) = self.spreads.write() {
+            spreads.clear();
+        }
     }
 }
 
@@ -411,7 +587,7 @@ mod tests {
 
         collector.record_solana_message();
         collector.record_binance_message();
-        collector.record_opportunity();
+        collector.record_opportunity(PriceSource::Solana, PriceSource::Binance);
 
         assert_eq!(collector.solana_messages.load(Ordering::Relaxed), 1);
         assert_eq!(collector.binance_messages.load(Ordering::Relaxed), 1);
@@ -426,13 +602,110 @@ mod tests {
         collector.record_processing_time(duration);
         collector.record_arbitrage_time(duration);
         collector.record_output_time(duration);
+        collector.record_detection_latency(duration);
 
         let metrics = collector.get_metrics();
         assert!(metrics.processing.price_processing_latency_ms > 0.0);
+        assert_eq!(metrics.processing.event_to_detection_latency_ms, 50.0);
         assert!(metrics.processing.arbitrage_detection_latency_ms > 0.0);
         assert!(metrics.processing.output_formatting_latency_ms > 0.0);
     }
 
+    #[test]
+    fn test_stage_percentiles() {
+        let collector = MetricsCollector::with_window(Duration::from_secs(30));
+        for _ in 0..99 {
+            collector.record_arbitrage_time(Duration::from_micros(100));
+        }
+        collector.record_arbitrage_time(Duration::from_millis(40));
+
+        let processing = collector.get_metrics().processing;
+        assert_eq!(processing.latency_window, Duration::from_secs(30));
+        let percentiles = processing.percentiles("arbitrage_detection");
+        assert_eq!(percentiles.count, 100);
+        assert!(percentiles.p50_ms < 0.11, "{:?}", percentiles);
+        assert!(percentiles.p99_ms < 0.11, "{:?}", percentiles);
+        assert_eq!(percentiles.max_ms, 40.0);
+        // The mean is pulled up by the single slow check
+        assert!((processing.arbitrage_detection_latency_ms - 0.499).abs() < 1e-9);
+        assert_eq!(processing.percentiles("price_processing").count, 0);
+    }
+
+    #[test]
+    fn test_clock_skew_per_venue() {
+        let collector = MetricsCollector::new();
+
+        collector.record_binance_clock_skew(-4.0);
+        collector.record_binance_clock_skew(10.0);
+        collector.record_solana_clock_skew(350.0);
+
+        let metrics = collector.get_metrics();
+        assert_eq!(metrics.connection.binance_clock_skew_ms, 3.0);
+        assert_eq!(metrics.connection.solana_clock_skew_ms, 350.0);
+
+        collector.reset();
+        assert_eq!(collector.get_metrics().connection.solana_clock_skew_ms, 0.0);
+    }
+
+    #[test]
+    fn test_connection_stats_from_transitions() {
+        let collector = MetricsCollector::new();
+        let start = Instant::now();
+        let mut state = ConnectionState::Connecting;
+        let mut entered = start;
+        let mut go = |to, at_ms| {
+            let at = start + Duration::from_millis(at_ms);
+            collector.record_connection_transition(&StateTransition {
+                source: "binance",
+                from: state,
+                to,
+                in_previous: at - entered,
+                at,
+            });
+            state = to;
+            entered = at;
+        };
+        go(ConnectionState::Subscribed, 10);
+        go(ConnectionState::Streaming, 20);
+        go(ConnectionState::Backoff { attempt: 1 }, 30);
+        go(ConnectionState::Connecting, 60);
+        go(ConnectionState::Subscribed, 80);
+
+        let connection = collector.get_metrics().connection;
+        assert_eq!(connection.total_reconnections, 1);
+        assert!(connection.last_connection_failure.is_some());
+        assert_eq!(connection.avg_reconnect_time_ms, 50.0);
+        assert!(connection.binance_uptime_pct.is_some());
+        assert_eq!(connection.solana_uptime_pct, None);
+        let binance = &connection.sources["binance"];
+        assert_eq!(binance.state, ConnectionState::Subscribed);
+        assert_eq!(binance.time_to_first_message_ms, Some(20.0));
+        assert_eq!(binance.reconnects, 1);
+    }
+
+    #[test]
+    fn test_opportunities_by_direction_and_spreads() {
+        let collector = MetricsCollector::new();
+
+        collector.record_opportunity(PriceSource::Orca, PriceSource::Binance);
+        collector.record_opportunity(PriceSource::Orca, PriceSource::Binance);
+        collector.record_opportunity(PriceSource::Binance, PriceSource::Solana);
+        collector.set_spread(TradingPair::SOL_USDT, PriceSource::Orca, 0.4);
+        collector.set_spread(TradingPair::SOL_USDT, PriceSource::Orca, 0.25);
+        collector.record_processing_time(Duration::from_millis(3));
+
+        let metrics = collector.get_metrics();
+        assert_eq!(metrics.summary.total_opportunities, 3);
+        let directions = &metrics.market.opportunities_by_direction;
+        assert_eq!(directions[&("orca", "binance")], 2);
+        assert_eq!(directions[&("binance", "solana")], 1);
+        assert_eq!(metrics.market.spreads[&("sol-usdt", "orca")], 0.25);
+
+        let (stage, histogram) = &metrics.processing.stage_latencies[0];
+        assert_eq!(*stage, "price_processing");
+        assert_eq!(histogram.count, 1);
+    }
+
     #[test]
     fn test_queue_depth_tracking() {
         let collector = MetricsCollector::new();
@@ -452,7 +725,7 @@ mod tests {
         // Simulate some activity
         collector.record_solana_message();
         collector.record_binance_message();
-        collector.record_opportunity();
+        collector.record_opportunity(PriceSource::Solana, PriceSource::Binance);
         collector.record_processing_time(Duration::from_millis(10));
 
         let metrics = collector.get_metrics();
@@ -497,7 +770,7 @@ mod tests {
         let collector = MetricsCollector::new();
 
         collector.record_solana_message();
-        collector.record_opportunity();
+        collector.record_opportunity(PriceSource::Solana, PriceSource::Binance);
         collector.record_error();
 
         collector.reset();
@@ -1,4 +1,9 @@
+pub mod connection;
+pub mod health;
+pub mod histogram;
 pub mod metrics;
 pub mod monitor;
+pub mod prometheus;
+pub mod server;
 
 pub use monitor::*;
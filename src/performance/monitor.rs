@@ -1,4 +1,8 @@
-use super::metrics::{MetricsCollector, PerformanceMetrics};
+use super::histogram::DEFAULT_WINDOW;
+use super::metrics::{
+    format_connection, format_percentiles, format_rejections, format_uptime, MetricsCollector,
+    PerformanceMetrics,
+};
 use log::{info, warn};
 use std::sync::Arc;
 use std::time::Duration;
@@ -22,6 +26,8 @@ pub struct MonitorConfig {
     /// Whether to log detailed metrics (default: false)
     #[allow(dead_code)]
     pub detailed_logging: bool,
+    /// Period latency percentiles are computed over (default: 60 seconds)
+    pub metrics_window: Duration,
 }
 
 impl Default for MonitorConfig {
@@ -30,6 +36,7 @@ impl Default for MonitorConfig {
             reporting_interval: Duration::from_secs(60),
             enabled: true,
             detailed_logging: false,
+            metrics_window: DEFAULT_WINDOW,
         }
     }
 }
@@ -38,7 +45,7 @@ impl PerformanceMonitor {
     /// Create a new performance monitor with the given configuration
     pub fn new(config: MonitorConfig) -> Self {
         Self {
-            metrics: Arc::new(MetricsCollector::new()),
+            metrics: Arc::new(MetricsCollector::with_window(config.metrics_window)),
             reporting_interval: config.reporting_interval,
             enabled: config.enabled,
         }
@@ -110,17 +117,27 @@ impl PerformanceMonitor {
             summary.binance_msg_rate,
             throughput.current_queue_depth
         );
+        let p50_p99 = |stage| {
+            let percentiles = processing.percentiles(stage);
+            format!("{:.1}/{:.1}ms", percentiles.p50_ms, percentiles.p99_ms)
+        };
+        info!(
+            "Latency p50/p99: Processing {} | Arbitrage {} | Output {} | Event to detection {}",
+            p50_p99("price_processing"),
+            p50_p99("arbitrage_detection"),
+            p50_p99("output_formatting"),
+            p50_p99("event_to_detection")
+        );
         info!(
-            "Latency: Processing {:.1}ms | Arbitrage {:.1}ms | Output {:.1}ms | Total {:.1}ms",
-            processing.price_processing_latency_ms,
-            processing.arbitrage_detection_latency_ms,
-            processing.output_formatting_latency_ms,
-            processing.total_pipeline_latency_ms
+            "Total pipeline: {:.1}ms avg | Clock skew: SOL {:.1}ms | BIN {:.1}ms",
+            processing.total_pipeline_latency_ms,
+            connection.solana_clock_skew_ms,
+            connection.binance_clock_skew_ms
         );
         info!(
-            "Connection: SOL {:.1}% | BIN {:.1}% | Reconnects: {} | Efficiency: {:.1}%",
-            connection.solana_uptime_pct,
-            connection.binance_uptime_pct,
+            "Connection: SOL {} | BIN {} | Reconnects: {} | Efficiency: {:.1}%",
+            format_uptime(connection.solana_uptime_pct),
+            format_uptime(connection.binance_uptime_pct),
             connection.total_reconnections,
             throughput.processing_efficiency_pct
         );
@@ -135,6 +152,10 @@ impl PerformanceMonitor {
                 0.0
             }
         );
+        info!(
+            "Rejected prices: {}",
+            format_rejections(&processing.rejections)
+        );
         info!("=== END SUMMARY ===");
     }
 
@@ -177,17 +198,18 @@ impl PerformanceMonitor {
         }
 
         // Check connection uptime (warn if < 90%)
-        if connection.solana_uptime_pct < 90.0 && metrics.summary.uptime_seconds > 60 {
+        let low_uptime = |pct: &f64| *pct < 90.0 && metrics.summary.uptime_seconds > 60;
+        if let Some(pct) = connection.solana_uptime_pct.filter(low_uptime) {
             warn!(
                 "Low Solana connection uptime: {:.1}% (frequent disconnections)",
-                connection.solana_uptime_pct
+                pct
             );
         }
 
-        if connection.binance_uptime_pct < 90.0 && metrics.summary.uptime_seconds > 60 {
+        if let Some(pct) = connection.binance_uptime_pct.filter(low_uptime) {
             warn!(
                 "Low Binance connection uptime: {:.1}% (frequent disconnections)",
-                connection.binance_uptime_pct
+                pct
             );
         }
 
@@ -225,23 +247,28 @@ MESSAGE THROUGHPUT:
 - Binance Rate: {:.2} messages/second
 - Opportunities: {:.2} per hour
 
-PROCESSING PERFORMANCE:
-- Price Processing: {:.2}ms avg
-- Arbitrage Detection: {:.2}ms avg
-- Output Formatting: {:.2}ms avg
+PROCESSING PERFORMANCE (last {}s):
+- Price Processing: {}
+- Arbitrage Detection: {}
+- Output Formatting: {}
+- Event to Detection: {}
 - Total Pipeline: {:.2}ms avg
 - Processing Efficiency: {:.1}%
 
 CONNECTION RELIABILITY:
-- Solana Uptime: {:.1}%
-- Binance Uptime: {:.1}%
+- Solana: {}
+- Binance: {}
 - Total Reconnections: {}
+- Avg Reconnect Time: {:.0}ms
 - Last Failure: {}
+- Solana Clock Skew: {:.2}ms avg
+- Binance Clock Skew: {:.2}ms avg
 
 QUEUE MANAGEMENT:
 - Current Queue Depth: {}
 - Processing Errors: {}
 - Error Rate: {:.2}%
+- Rejected Prices: {}
 
 PERFORMANCE STATUS:
 {}
@@ -255,17 +282,22 @@ PERFORMANCE STATUS:
             summary.solana_msg_rate,
             summary.binance_msg_rate,
             throughput.opportunities_per_hour,
-            processing.price_processing_latency_ms,
-            processing.arbitrage_detection_latency_ms,
-            processing.output_formatting_latency_ms,
+            processing.latency_window.as_secs(),
+            format_percentiles(&processing.percentiles("price_processing")),
+            format_percentiles(&processing.percentiles("arbitrage_detection")),
+            format_percentiles(&processing.percentiles("output_formatting")),
+            format_percentiles(&processing.percentiles("event_to_detection")),
             processing.total_pipeline_latency_ms,
             throughput.processing_efficiency_pct,
-            connection.solana_uptime_pct,
-            connection.binance_uptime_pct,
+            format_connection(connection.sources.get("solana")),
+            format_connection(connection.sources.get("binance")),
             connection.total_reconnections,
+            connection.avg_reconnect_time_ms,
             connection
                 .last_connection_failure
                 .map_or("None".to_string(), |dt| dt.to_rfc3339()),
+            connection.solana_clock_skew_ms,
+            connection.binance_clock_skew_ms,
             throughput.current_queue_depth,
             processing.errors_encountered,
             if processing.messages_processed > 0 {
@@ -274,6 +306,7 @@ PERFORMANCE STATUS:
             } else {
                 0.0
             },
+            format_rejections(&processing.rejections),
             Self::get_performance_status(&metrics)
         )
     }
@@ -295,8 +328,8 @@ PERFORMANCE STATUS:
         } else if processing.price_processing_latency_ms > 10.0
             || processing.arbitrage_detection_latency_ms > 5.0
             || throughput.processing_efficiency_pct < 95.0
-            || connection.solana_uptime_pct < 90.0
-            || connection.binance_uptime_pct < 90.0
+            || connection.solana_uptime_pct.is_some_and(|pct| pct < 90.0)
+            || connection.binance_uptime_pct.is_some_and(|pct| pct < 90.0)
         {
             "WARNING - Performance issues detected"
         } else if throughput.messages_per_second > 1.0
@@ -348,6 +381,7 @@ impl TimedOperation for Arc<MetricsCollector> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::price::PriceSource;
     use std::thread;
     use tokio::test as tokio_test;
 
@@ -363,9 +397,14 @@ mod tests {
             reporting_interval: Duration::from_secs(30),
             enabled: false,
             detailed_logging: true,
+            metrics_window: Duration::from_secs(10),
         };
         let monitor = PerformanceMonitor::new(config);
         assert!(!monitor.is_enabled());
+        assert_eq!(
+            monitor.get_current_metrics().processing.latency_window,
+            Duration::from_secs(10)
+        );
     }
 
     #[test]
@@ -374,7 +413,7 @@ mod tests {
         let metrics_ref = monitor.metrics();
 
         metrics_ref.record_solana_message();
-        metrics_ref.record_opportunity();
+        metrics_ref.record_opportunity(PriceSource::Orca, PriceSource::Binance);
 
         let current_metrics = monitor.get_current_metrics();
         assert_eq!(current_metrics.summary.total_opportunities, 1);
@@ -389,11 +428,15 @@ mod tests {
         // Generate some test data
         metrics_ref.record_solana_message();
         metrics_ref.record_binance_message();
-        metrics_ref.record_opportunity();
+        metrics_ref.record_opportunity(PriceSource::Orca, PriceSource::Binance);
         metrics_ref.record_processing_time(Duration::from_millis(5));
 
         let report = monitor.generate_detailed_report();
         assert!(report.contains("DETAILED PERFORMANCE REPORT"));
+        assert!(report.contains("PROCESSING PERFORMANCE (last 60s):"));
+        assert!(report.contains(
+            "- Price Processing: p50 5.00ms | p90 5.00ms | p99 5.00ms | p999 5.00ms | max 5.00ms (1 samples)"
+        ));
         assert!(report.contains("Total Messages: 2"));
         assert!(report.contains("Total Opportunities: 1"));
     }
@@ -441,7 +484,7 @@ mod tests {
 
         // Generate some activity
         metrics_ref.record_solana_message();
-        metrics_ref.record_opportunity();
+        metrics_ref.record_opportunity(PriceSource::Orca, PriceSource::Binance);
 
         // Test metrics retrieval
         let current = monitor.get_current_metrics();
@@ -0,0 +1,356 @@
+use super::histogram::HistogramSnapshot;
+use super::metrics::PerformanceMetrics;
+use crate::websocket::state::ConnectionState;
+use std::fmt::Write;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Render a metrics snapshot in the Prometheus text exposition format
+pub fn render(metrics: &PerformanceMetrics) -> String {
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "arb_messages_total",
+        "counter",
+        "WebSocket messages received, by source",
+    );
+    sample(
+        &mut out,
+        "arb_messages_total",
+        &[("source", "solana")],
+        metrics.summary.solana_messages as f64,
+    );
+    sample(
+        &mut out,
+        "arb_messages_total",
+        &[("source", "binance")],
+        metrics.summary.binance_messages as f64,
+    );
+
+    header(
+        &mut out,
+        "arb_reconnections_total",
+        "counter",
+        "WebSocket reconnection attempts",
+    );
+    sample(
+        &mut out,
+        "arb_reconnections_total",
+        &[],
+        metrics.connection.total_reconnections as f64,
+    );
+
+    let sources = &metrics.connection.sources;
+    header(
+        &mut out,
+        "arb_connection_state",
+        "gauge",
+        "Connection state of each client, 1 for the current state",
+    );
+    for (source, stats) in sources {
+        for state in ConnectionState::NAMES {
+            let current = if stats.state.name() == state {
+                1.0
+            } else {
+                0.0
+            };
+            sample(
+                &mut out,
+                "arb_connection_state",
+                &[("source", source), ("state", state)],
+                current,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "arb_connection_uptime_ratio",
+        "gauge",
+        "Share of the time each client was subscribed or streaming",
+    );
+    for (source, stats) in sources {
+        sample(
+            &mut out,
+            "arb_connection_uptime_ratio",
+            &[("source", source)],
+            stats.uptime_pct / 100.0,
+        );
+    }
+
+    header(
+        &mut out,
+        "arb_time_to_first_message_seconds",
+        "gauge",
+        "Time from starting the latest connection to its first price update",
+    );
+    for (source, stats) in sources {
+        if let Some(ms) = stats.time_to_first_message_ms {
+            sample(
+                &mut out,
+                "arb_time_to_first_message_seconds",
+                &[("source", source)],
+                ms / 1000.0,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "arb_reconnect_duration_seconds",
+        "summary",
+        "Time from losing a connection to being subscribed again",
+    );
+    for (source, stats) in sources {
+        sample(
+            &mut out,
+            "arb_reconnect_duration_seconds_sum",
+            &[("source", source)],
+            stats.reconnect_seconds,
+        );
+        sample(
+            &mut out,
+            "arb_reconnect_duration_seconds_count",
+            &[("source", source)],
+            stats.reconnects as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "arb_stage_latency_seconds",
+        "histogram",
+        "Latency of each pipeline stage",
+    );
+    for (stage, histogram) in &metrics.processing.stage_latencies {
+        render_histogram(&mut out, "arb_stage_latency_seconds", stage, histogram);
+    }
+
+    header(
+        &mut out,
+        "arb_stage_latency_window_seconds",
+        "gauge",
+        &format!(
+            "Latency percentiles of each pipeline stage over the last {}s; quantile 1 is the max",
+            metrics.processing.latency_window.as_secs()
+        ),
+    );
+    for (stage, histogram) in &metrics.processing.stage_latencies {
+        let window = &histogram.window;
+        for (quantile, ms) in [
+            ("0.5", window.p50_ms),
+            ("0.9", window.p90_ms),
+            ("0.99", window.p99_ms),
+            ("0.999", window.p999_ms),
+            ("1", window.max_ms),
+        ] {
+            sample(
+                &mut out,
+                "arb_stage_latency_window_seconds",
+                &[("stage", stage), ("quantile", quantile)],
+                ms / 1000.0,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "arb_opportunities_total",
+        "counter",
+        "Arbitrage opportunities found, by buy and sell source",
+    );
+    for ((buy, sell), count) in &metrics.market.opportunities_by_direction {
+        sample(
+            &mut out,
+            "arb_opportunities_total",
+            &[("buy", buy), ("sell", sell)],
+            *count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "arb_spread_percent",
+        "gauge",
+        "Latest spread between a DEX and Binance, in percent",
+    );
+    for ((pair, dex), spread) in &metrics.market.spreads {
+        sample(
+            &mut out,
+            "arb_spread_percent",
+            &[("pair", pair), ("dex", dex)],
+            *spread,
+        );
+    }
+
+    header(
+        &mut out,
+        "arb_price_rejections_total",
+        "counter",
+        "Prices rejected during validation, by reason",
+    );
+    for (reason, count) in &metrics.processing.rejections {
+        sample(
+            &mut out,
+            "arb_price_rejections_total",
+            &[("reason", reason)],
+            *count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "arb_uptime_seconds",
+        "gauge",
+        "Seconds since the watcher started",
+    );
+    sample(
+        &mut out,
+        "arb_uptime_seconds",
+        &[],
+        metrics.summary.uptime_seconds as f64,
+    );
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn render_histogram(out: &mut String, name: &str, stage: &str, histogram: &HistogramSnapshot) {
+    let bucket = format!("{}_bucket", name);
+    for (bound, count) in &histogram.buckets {
+        let le = bound.to_string();
+        sample(
+            out,
+            &bucket,
+            &[("stage", stage), ("le", &le)],
+            *count as f64,
+        );
+    }
+    sample(
+        out,
+        &bucket,
+        &[("stage", stage), ("le", "+Inf")],
+        histogram.count as f64,
+    );
+    sample(
+        out,
+        &format!("{}_sum", name),
+        &[("stage", stage)],
+        histogram.sum_seconds,
+    );
+    sample(
+        out,
+        &format!("{}_count", name),
+        &[("stage", stage)],
+        histogram.count as f64,
+    );
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TradingPair;
+    use crate::performance::metrics::MetricsCollector;
+    use crate::price::PriceSource;
+    use crate::websocket::state::StateTransition;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_render_exposition_format() {
+        let collector = MetricsCollector::new();
+        collector.record_solana_message();
+        collector.record_binance_message();
+        collector.record_binance_message();
+        collector.record_opportunity(PriceSource::RaydiumClmm, PriceSource::Binance);
+        collector.set_spread(TradingPair::SOL_USDT, PriceSource::RaydiumClmm, 0.5);
+        collector.record_arbitrage_time(Duration::from_millis(2));
+        collector.record_rejection("skew");
+
+        let text = render(&collector.get_metrics());
+        assert!(text.contains("# TYPE arb_messages_total counter\n"));
+        assert!(text.contains("arb_messages_total{source=\"solana\"} 1\n"));
+        assert!(text.contains("arb_messages_total{source=\"binance\"} 2\n"));
+        assert!(text.contains("arb_reconnections_total 0\n"));
+        assert!(text.contains(
+            "arb_stage_latency_seconds_bucket{stage=\"arbitrage_detection\",le=\"0.001\"} 0\n"
+        ));
+        assert!(text.contains(
+            "arb_stage_latency_seconds_bucket{stage=\"arbitrage_detection\",le=\"0.0025\"} 1\n"
+        ));
+        assert!(text.contains(
+            "arb_stage_latency_seconds_bucket{stage=\"arbitrage_detection\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("arb_stage_latency_seconds_count{stage=\"output_formatting\"} 0\n"));
+        assert!(text.contains(
+            "arb_stage_latency_window_seconds{stage=\"arbitrage_detection\",quantile=\"0.99\"} 0.002\n"
+        ));
+        assert!(text.contains(
+            "arb_stage_latency_window_seconds{stage=\"arbitrage_detection\",quantile=\"1\"} 0.002\n"
+        ));
+        assert!(text.contains("arb_opportunities_total{buy=\"raydium_clmm\",sell=\"binance\"} 1\n"));
+        assert!(text.contains("arb_spread_percent{pair=\"sol-usdt\",dex=\"raydium_clmm\"} 0.5\n"));
+        assert!(text.contains("arb_price_rejections_total{reason=\"skew\"} 1\n"));
+    }
+
+    #[test]
+    fn test_render_connection_series() {
+        let collector = MetricsCollector::new();
+        let start = Instant::now();
+        let transition = |from, to, at_ms| StateTransition {
+            source: "solana",
+            from,
+            to,
+            in_previous: Duration::from_millis(100),
+            at: start + Duration::from_millis(at_ms),
+        };
+        collector.record_connection_transition(&transition(
+            ConnectionState::Connecting,
+            ConnectionState::Subscribed,
+            100,
+        ));
+        collector.record_connection_transition(&transition(
+            ConnectionState::Subscribed,
+            ConnectionState::Streaming,
+            200,
+        ));
+
+        let text = render(&collector.get_metrics());
+        assert!(text.contains("arb_connection_state{source=\"solana\",state=\"streaming\"} 1\n"));
+        assert!(text.contains("arb_connection_state{source=\"solana\",state=\"backoff\"} 0\n"));
+        assert!(text.contains("arb_time_to_first_message_seconds{source=\"solana\"} 0.2\n"));
+        assert!(text.contains("arb_reconnect_duration_seconds_count{source=\"solana\"} 0\n"));
+        assert!(text.contains("arb_connection_uptime_ratio{source=\"solana\"} "));
+        assert!(!text.contains("source=\"binance\",state="));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
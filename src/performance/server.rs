@@ -0,0 +1,335 @@
+use super::health::{HealthCheck, HealthReport};
+use super::metrics::MetricsCollector;
+use super::prometheus;
+use log::{debug, info, warn};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head accepted
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Minimal HTTP/1.1 server exposing `/metrics` for Prometheus scrapes, and `/healthz`
+/// and `/readyz` when given a health check
+///
+/// Each connection serves a single request and is then closed.
+pub struct MetricsServer {
+    listener: TcpListener,
+    state: ServerState,
+}
+
+/// What requests are answered from
+struct ServerState {
+    metrics: Arc<MetricsCollector>,
+    health: Option<HealthCheck>,
+}
+
+/// A response ready to be written
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn text(status: &'static str, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", body),
+        }
+    }
+
+    /// 200 with the report when healthy, 503 otherwise
+    fn health(report: &HealthReport) -> Self {
+        Self {
+            status: if report.is_healthy() {
+                "200 OK"
+            } else {
+                "503 Service Unavailable"
+            },
+            content_type: "application/json",
+            body: serde_json::to_string(report).unwrap_or_default() + "\n",
+        }
+    }
+}
+
+impl MetricsServer {
+    /// Listen on `addr`; port 0 picks a free port
+    pub async fn bind(addr: SocketAddr, metrics: Arc<MetricsCollector>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(Self {
+            listener,
+            state: ServerState {
+                metrics,
+                health: None,
+            },
+        })
+    }
+
+    /// Serve `/healthz` and `/readyz` from `health`
+    pub fn with_health(mut self, health: HealthCheck) -> Self {
+        self.state.health = Some(health);
+        self
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept and answer connections until the task is aborted
+    pub async fn run(self) {
+        if let Ok(addr) = self.local_addr() {
+            info!("Serving metrics on http://{}/metrics", addr);
+        }
+        let state = Arc::new(self.state);
+
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!("Failed to accept metrics connection: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+
+            let state = Arc::clone(&state);
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &state).await {
+                    debug!("Metrics request from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: &ServerState) -> io::Result<()> {
+    let request_line = tokio::time::timeout(REQUEST_TIMEOUT, read_request_line(&mut stream))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))??;
+
+    let response = match request_line {
+        Some(line) => route(&line, state),
+        None => Response::text("400 Bad Request", "bad request"),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Answer a request given its request line, e.g. `GET /metrics HTTP/1.1`
+fn route(request_line: &str, state: &ServerState) -> Response {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Response::text("400 Bad Request", "bad request");
+    };
+    let path = target.split('?').next().unwrap_or(target);
+
+    match (method, path, &state.health) {
+        ("GET", "/metrics", _) => Response {
+            status: "200 OK",
+            content_type: prometheus::CONTENT_TYPE,
+            body: prometheus::render(&state.metrics.get_metrics()),
+        },
+        ("GET", "/healthz", Some(health)) => Response::health(&health.liveness()),
+        ("GET", "/readyz", Some(health)) => Response::health(&health.readiness()),
+        (_, "/metrics", _) | (_, "/healthz" | "/readyz", Some(_)) => {
+            Response::text("405 Method Not Allowed", "method not allowed")
+        }
+        _ => Response::text("404 Not Found", "not found"),
+    }
+}
+
+/// Read the request head and return its first line, or `None` if it is malformed
+///
+/// Request bodies are ignored: every supported request is a GET.
+async fn read_request_line(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        if data.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+        if data.len() > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&data);
+    Ok(head
+        .split("\r\n")
+        .next()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TradingPair;
+    use crate::performance::health::Heartbeat;
+    use crate::price::{PriceCache, PriceSource, PriceUpdate};
+    use crate::websocket::state::ConnectionState;
+    use tokio::sync::watch;
+
+    async fn start(metrics: Arc<MetricsCollector>) -> String {
+        let server = MetricsServer::bind("127.0.0.1:0".parse().unwrap(), metrics)
+            .await
+            .unwrap();
+        serve(server)
+    }
+
+    fn serve(server: MetricsServer) -> String {
+        let base_url = format!("http://{}", server.local_addr().unwrap());
+        tokio::spawn(server.run());
+        base_url
+    }
+
+    #[tokio::test]
+    async fn test_scrape_metrics_endpoint() {
+        let metrics = Arc::new(MetricsCollector::new());
+        metrics.record_solana_message();
+        metrics.record_opportunity(PriceSource::Binance, PriceSource::Orca);
+        metrics.record_detection_latency(Duration::from_millis(7));
+        let base_url = start(Arc::clone(&metrics)).await;
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/metrics", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], prometheus::CONTENT_TYPE);
+        let body = response.text().await.unwrap();
+        assert!(body.contains("arb_messages_total{source=\"solana\"} 1\n"));
+        assert!(body.contains("arb_opportunities_total{buy=\"binance\",sell=\"orca\"} 1\n"));
+        assert!(body.contains("arb_stage_latency_seconds_count{stage=\"event_to_detection\"} 1\n"));
+
+        // Each scrape sees the current values
+        metrics.record_solana_message();
+        let body = client
+            .get(format!("{}/metrics?debug=1", base_url))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains("arb_messages_total{source=\"solana\"} 2\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_paths_and_methods() {
+        let base_url = start(Arc::new(MetricsCollector::new())).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/other", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        // Health endpoints only exist with a health check
+        let response = client
+            .get(format!("{}/readyz", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        let response = client
+            .delete(format!("{}/metrics", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 405);
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoints_explain_unhealthy_components() {
+        let (_binance, binance_state) = watch::channel(ConnectionState::Streaming);
+        let cache = Arc::new(PriceCache::new());
+        let heartbeat = Heartbeat::default();
+        heartbeat.beat();
+        let health = HealthCheck::new()
+            .with_connections(vec![("binance", binance_state)])
+            .with_price_caches(vec![(TradingPair::SOL_USDT, Arc::clone(&cache))], 5000)
+            .with_detectors(
+                vec![(TradingPair::SOL_USDT, heartbeat)],
+                Duration::from_secs(60),
+            );
+        let server = MetricsServer::bind(
+            "127.0.0.1:0".parse().unwrap(),
+            Arc::new(MetricsCollector::new()),
+        )
+        .await
+        .unwrap()
+        .with_health(health);
+        let base_url = serve(server);
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/healthz", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let response = client
+            .get(format!("{}/readyz", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "unavailable");
+        let unhealthy: Vec<&str> = body["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|check| check["healthy"] == false)
+            .map(|check| check["component"].as_str().unwrap())
+            .collect();
+        assert_eq!(unhealthy, ["prices/sol-usdt"]);
+
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            150.0,
+        ));
+        cache.update(&PriceUpdate::new(
+            PriceSource::Orca,
+            TradingPair::SOL_USDT,
+            150.1,
+        ));
+        let response = client
+            .get(format!("{}/readyz", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time source for price freshness checks
+#[derive(Debug, Clone, Default)]
+pub enum Clock {
+    /// Wall-clock time
+    #[default]
+    System,
+    /// Time advanced explicitly, for backtests
+    Simulated(Arc<SimulatedClock>),
+}
+
+impl Clock {
+    /// Current time according to this clock
+    pub fn now(&self) -> SystemTime {
+        match self {
+            Clock::System => SystemTime::now(),
+            Clock::Simulated(clock) => clock.now(),
+        }
+    }
+}
+
+impl From<Arc<SimulatedClock>> for Clock {
+    fn from(clock: Arc<SimulatedClock>) -> Self {
+        Clock::Simulated(clock)
+    }
+}
+
+/// Manually set time, shared by every reader
+#[derive(Debug)]
+pub struct SimulatedClock {
+    /// Microseconds since the Unix epoch
+    now_micros: AtomicU64,
+}
+
+impl SimulatedClock {
+    /// Clock starting at `start`
+    pub fn new(start: SystemTime) -> Self {
+        let clock = Self {
+            now_micros: AtomicU64::new(0),
+        };
+        clock.set(start);
+        clock
+    }
+
+    /// Current simulated time
+    pub fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_micros(self.now_micros.load(Ordering::Acquire))
+    }
+
+    /// Move the clock to `time`
+    pub fn set(&self, time: SystemTime) {
+        let micros = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        self.now_micros.store(micros, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_clock() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let simulated = Arc::new(SimulatedClock::new(start));
+        let clock = Clock::from(Arc::clone(&simulated));
+        assert_eq!(clock.now(), start);
+
+        simulated.set(start + Duration::from_millis(1500));
+        assert_eq!(clock.now(), start + Duration::from_millis(1500));
+        assert!(Clock::System.now() > clock.now());
+    }
+}
@@ -0,0 +1,507 @@
+use crate::price::Quote;
+use serde::{Deserialize, Serialize};
+
+/// Trade size, in base tokens, at which AMM curves are quoted in both directions
+pub const AMM_QUOTE_SIZE: f64 = 1.0;
+
+/// Direction of a trade on a DEX, from the point of view of the base token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    /// Quote in, base out (price of base rises)
+    BuyBase,
+    /// Base in, quote out (price of base falls)
+    SellBase,
+}
+
+impl TradeSide {
+    /// The other side of the trade
+    pub fn opposite(self) -> Self {
+        match self {
+            TradeSide::BuyBase => TradeSide::SellBase,
+            TradeSide::SellBase => TradeSide::BuyBase,
+        }
+    }
+}
+
+/// Liquidity available behind a DEX price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LiquidityProfile {
+    /// Concentrated liquidity (tick-based) pool
+    Concentrated(ConcentratedLiquidity),
+    /// Price levels of a central limit order book
+    OrderBook(BookDepth),
+    /// Constant-product (x * y = k) pool reserves
+    ConstantProduct(ConstantProductPool),
+}
+
+impl LiquidityProfile {
+    /// Base token amount that can be traded on `side` within the profile's depth limit
+    ///
+    /// A constant-product pool never runs dry, so its base reserve is used as the bound:
+    /// trading that much moves the average price by half or more.
+    pub fn base_capacity(&self, side: TradeSide, max_ticks: u32) -> Option<f64> {
+        match self {
+            LiquidityProfile::Concentrated(clmm) => Some(clmm.base_capacity(side, max_ticks)),
+            LiquidityProfile::OrderBook(book) => Some(book.total_size(side)),
+            LiquidityProfile::ConstantProduct(pool) => Some(pool.base_reserve),
+        }
+    }
+
+    /// Average fill price for `base_amount` on `side`, if the profile can walk its levels
+    pub fn average_price(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        match self {
+            LiquidityProfile::Concentrated(_) => None,
+            LiquidityProfile::OrderBook(book) => book.average_price(side, base_amount),
+            LiquidityProfile::ConstantProduct(pool) => pool.average_price(side, base_amount),
+        }
+    }
+
+    /// Price impact of trading `base_amount` on `side`, as a percentage of the best price
+    pub fn price_impact(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        match self {
+            LiquidityProfile::Concentrated(_) => None,
+            LiquidityProfile::OrderBook(book) => {
+                let (best, _) = *book.levels(side).first()?;
+                let average = book.average_price(side, base_amount)?;
+                Some((average / best - 1.0).abs() * 100.0)
+            }
+            LiquidityProfile::ConstantProduct(pool) => pool.price_impact(side, base_amount),
+        }
+    }
+
+    /// Whether `average_price` accounts for price impact at any trade size
+    pub fn prices_by_size(&self) -> bool {
+        !matches!(self, LiquidityProfile::Concentrated(_))
+    }
+
+    /// Whether `average_price` already includes the venue's trading fee
+    pub fn includes_fee(&self) -> bool {
+        matches!(self, LiquidityProfile::ConstantProduct(_))
+    }
+}
+
+/// Reserves of a constant-product pool in whole tokens, with its swap fee
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstantProductPool {
+    pub base_reserve: f64,
+    pub quote_reserve: f64,
+    /// Fee taken from the input amount, as a fraction (0.0025 = 0.25%)
+    pub fee_rate: f64,
+}
+
+impl ConstantProductPool {
+    /// Spot price of the base token, before fees
+    pub fn spot_price(&self) -> f64 {
+        self.quote_reserve / self.base_reserve
+    }
+
+    /// Exact quote tokens paid (buy) or received (sell) when swapping `base_amount`
+    ///
+    /// The fee is charged on the input side, as the pool does: on the quote paid
+    /// when buying and on the base sold when selling.
+    pub fn swap_quote_amount(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        let (x, y) = (self.base_reserve, self.quote_reserve);
+        if base_amount <= 0.0 || x <= 0.0 || y <= 0.0 {
+            return None;
+        }
+
+        match side {
+            TradeSide::BuyBase => {
+                if base_amount >= x {
+                    return None;
+                }
+                let quote_in = y * base_amount / (x - base_amount);
+                Some(quote_in / (1.0 - self.fee_rate))
+            }
+            TradeSide::SellBase => {
+                let base_in = base_amount * (1.0 - self.fee_rate);
+                Some(y * base_in / (x + base_in))
+            }
+        }
+    }
+
+    /// Average fill price for `base_amount` on `side`, fee included
+    pub fn average_price(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        self.swap_quote_amount(side, base_amount)
+            .map(|quote| quote / base_amount)
+    }
+
+    /// Price impact of the swap against the spot price, excluding the fee
+    pub fn price_impact(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        let without_fee = Self {
+            fee_rate: 0.0,
+            ..self.clone()
+        };
+        let average = without_fee.average_price(side, base_amount)?;
+        Some((average / self.spot_price() - 1.0).abs() * 100.0)
+    }
+}
+
+/// Price levels of an order book as (price, base size), best level first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookDepth {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl BookDepth {
+    /// Levels a taker on `side` trades against: buys take asks, sells take bids
+    pub fn levels(&self, side: TradeSide) -> &[(f64, f64)] {
+        match side {
+            TradeSide::BuyBase => &self.asks,
+            TradeSide::SellBase => &self.bids,
+        }
+    }
+
+    /// Total base size resting on the side a taker on `side` trades against
+    pub fn total_size(&self, side: TradeSide) -> f64 {
+        self.levels(side).iter().map(|(_, size)| size).sum()
+    }
+
+    /// Volume-weighted fill price for `base_amount`, walking levels from the best one
+    ///
+    /// If the book is thinner than `base_amount`, the price covers what is available.
+    pub fn average_price(&self, side: TradeSide, base_amount: f64) -> Option<f64> {
+        let mut remaining = base_amount;
+        let mut filled = 0.0;
+        let mut notional = 0.0;
+        for &(price, size) in self.levels(side) {
+            if remaining <= 0.0 {
+                break;
+            }
+            let take = size.min(remaining);
+            filled += take;
+            notional += take * price;
+            remaining -= take;
+        }
+
+        (filled > 0.0).then(|| notional / filled)
+    }
+}
+
+/// Snapshot of a concentrated liquidity pool around the current price
+///
+/// Amounts follow the Uniswap v3 convention: `sqrt_price` is the square root of
+/// token1 atoms per token0 atom, and `liquidity` is in the same sqrt-atom units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConcentratedLiquidity {
+    /// Current sqrt price (token1 atoms per token0 atom)
+    pub sqrt_price: f64,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    /// Current tick index
+    pub tick_current: i32,
+    /// Whether the base token is token0 of the pool
+    pub base_is_token0: bool,
+    /// Decimals of the base token
+    pub base_decimals: u8,
+    /// Initialized ticks as (tick index, liquidity net), sorted by tick index
+    pub ticks: Vec<(i32, i128)>,
+    /// Tick range [lower, upper) covered by known tick arrays
+    pub covered_range: (i32, i32),
+}
+
+impl ConcentratedLiquidity {
+    /// Sqrt price at a tick boundary
+    pub fn sqrt_price_at_tick(tick: i32) -> f64 {
+        1.0001f64.powf(tick as f64 / 2.0)
+    }
+
+    /// Base tokens that can be traded before crossing `max_ticks` initialized ticks
+    ///
+    /// The walk stops early at the edge of the known tick arrays, so the estimate
+    /// is conservative when the surrounding arrays have not been observed yet.
+    pub fn base_capacity(&self, side: TradeSide, max_ticks: u32) -> f64 {
+        // Buying token0 pushes the price up; buying token1 pushes it down
+        let moving_up = (side == TradeSide::BuyBase) == self.base_is_token0;
+
+        let mut liquidity = self.liquidity as f64;
+        let mut sqrt_price = self.sqrt_price;
+        let mut crossed = 0;
+        let mut base_atoms = 0.0;
+
+        let boundaries: Box<dyn Iterator<Item = &(i32, i128)>> = if moving_up {
+            Box::new(
+                self.ticks
+                    .iter()
+                    .filter(|(tick, _)| *tick > self.tick_current),
+            )
+        } else {
+            Box::new(
+                self.ticks
+                    .iter()
+                    .rev()
+                    .filter(|(tick, _)| *tick <= self.tick_current),
+            )
+        };
+        let edge = if moving_up {
+            self.covered_range.1
+        } else {
+            self.covered_range.0
+        };
+
+        let mut boundaries = boundaries.peekable();
+        while crossed < max_ticks {
+            // Next boundary: the next initialized tick, or the edge of known data
+            let (target_tick, liquidity_net, is_initialized) = match boundaries.peek() {
+                Some(&&(tick, net))
+                    if (moving_up && tick < edge) || (!moving_up && tick >= edge) =>
+                {
+                    (tick, net, true)
+                }
+                _ => (edge, 0, false),
+            };
+
+            let target_sqrt = Self::sqrt_price_at_tick(target_tick);
+            base_atoms += self.base_amount_between(liquidity, sqrt_price, target_sqrt);
+            sqrt_price = target_sqrt;
+
+            if !is_initialized {
+                break;
+            }
+            boundaries.next();
+            crossed += 1;
+
+            // Crossing upward adds liquidity_net; crossing downward removes it
+            let delta = if moving_up {
+                liquidity_net
+            } else {
+                -liquidity_net
+            };
+            liquidity = (liquidity + delta as f64).max(0.0);
+        }
+
+        base_atoms / 10f64.powi(self.base_decimals as i32)
+    }
+
+    /// Base token atoms exchanged when moving between two sqrt prices at constant liquidity
+    fn base_amount_between(&self, liquidity: f64, from: f64, to: f64) -> f64 {
+        let (lower, upper) = if from < to { (from, to) } else { (to, from) };
+        if lower <= 0.0 {
+            return 0.0;
+        }
+
+        if self.base_is_token0 {
+            liquidity * (1.0 / lower - 1.0 / upper)
+        } else {
+            liquidity * (upper - lower)
+        }
+    }
+}
+
+/// Quote a constant-product (x * y = k) curve for `base_amount` in each direction
+///
+/// Reserves and amounts are in whole tokens; prices are averages over the trade and
+/// exclude the pool fee. Returns `None` if the pool cannot fill the trade.
+pub fn constant_product_quote(
+    base_reserve: f64,
+    quote_reserve: f64,
+    base_amount: f64,
+) -> Option<Quote> {
+    if base_amount <= 0.0 || base_reserve <= base_amount || quote_reserve <= 0.0 {
+        return None;
+    }
+
+    // Buying pays y / (x - dx) per base token, selling receives y / (x + dx)
+    let ask = quote_reserve / (base_reserve - base_amount);
+    let bid = quote_reserve / (base_reserve + base_amount);
+    Some(Quote::new(bid, ask))
+}
+
+/// Quote a concentrated liquidity pool for `base_amount` in each direction
+///
+/// Assumes the trade stays within the current tick range, so `liquidity` is
+/// constant along the way. `sqrt_price` is in token1 atoms per token0 atom; the
+/// returned prices are in whole quote tokens per base token and exclude fees.
+pub fn concentrated_quote(
+    sqrt_price: f64,
+    liquidity: u128,
+    base_is_token0: bool,
+    base_decimals: u8,
+    quote_decimals: u8,
+    base_amount: f64,
+) -> Option<Quote> {
+    let liquidity = liquidity as f64;
+    let base_atoms = base_amount * 10f64.powi(base_decimals as i32);
+    if liquidity <= 0.0 || sqrt_price <= 0.0 || base_atoms <= 0.0 {
+        return None;
+    }
+
+    // Quote atoms paid when buying and received when selling `base_atoms`
+    let (cost, proceeds) = if base_is_token0 {
+        let inv_up = 1.0 / sqrt_price - base_atoms / liquidity;
+        if inv_up <= 0.0 {
+            return None;
+        }
+        let sqrt_down = 1.0 / (1.0 / sqrt_price + base_atoms / liquidity);
+        (
+            liquidity * (1.0 / inv_up - sqrt_price),
+            liquidity * (sqrt_price - sqrt_down),
+        )
+    } else {
+        let sqrt_down = sqrt_price - base_atoms / liquidity;
+        if sqrt_down <= 0.0 {
+            return None;
+        }
+        let sqrt_up = sqrt_price + base_atoms / liquidity;
+        (
+            liquidity * (1.0 / sqrt_down - 1.0 / sqrt_price),
+            liquidity * (1.0 / sqrt_price - 1.0 / sqrt_up),
+        )
+    };
+
+    let scale = 10f64.powi(base_decimals as i32 - quote_decimals as i32);
+    Some(Quote::new(
+        proceeds / base_atoms * scale,
+        cost / base_atoms * scale,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SOL/USDC-like pool with SOL as token0 sitting at tick 0
+    fn test_pool(ticks: Vec<(i32, i128)>) -> ConcentratedLiquidity {
+        ConcentratedLiquidity {
+            sqrt_price: 1.0,
+            liquidity: 1_000_000_000_000,
+            tick_current: 0,
+            base_is_token0: true,
+            base_decimals: 9,
+            ticks,
+            covered_range: (-600, 600),
+        }
+    }
+
+    #[test]
+    fn test_capacity_up_to_next_tick() {
+        let pool = test_pool(vec![(-100, 1_000_000_000_000), (100, -1_000_000_000_000)]);
+
+        // Buying SOL (token0) moves the price up to tick 100
+        let expected = 1e12 * (1.0 - 1.0 / ConcentratedLiquidity::sqrt_price_at_tick(100)) / 1e9;
+        let capacity = pool.base_capacity(TradeSide::BuyBase, 1);
+        assert!((capacity - expected).abs() < 1e-9);
+
+        // Selling SOL moves the price down to tick -100
+        let expected = 1e12 * (1.0 / ConcentratedLiquidity::sqrt_price_at_tick(-100) - 1.0) / 1e9;
+        let capacity = pool.base_capacity(TradeSide::SellBase, 1);
+        assert!((capacity - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capacity_applies_liquidity_net() {
+        // Liquidity drops to zero above tick 100, so crossing more ticks adds nothing
+        let pool = test_pool(vec![(100, -1_000_000_000_000), (200, 0)]);
+
+        let one_tick = pool.base_capacity(TradeSide::BuyBase, 1);
+        let two_ticks = pool.base_capacity(TradeSide::BuyBase, 2);
+        assert!(one_tick > 0.0);
+        assert!((two_ticks - one_tick).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_capacity_stops_at_known_range() {
+        let mut pool = test_pool(vec![(1_000, 0)]);
+        pool.covered_range = (-600, 600);
+
+        let capacity = pool.base_capacity(TradeSide::BuyBase, 5);
+        let expected = 1e12 * (1.0 - 1.0 / ConcentratedLiquidity::sqrt_price_at_tick(600)) / 1e9;
+        assert!((capacity - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capacity_with_base_as_token1() {
+        let mut pool = test_pool(vec![(-100, 0), (100, 0)]);
+        pool.base_is_token0 = false;
+
+        // Buying token1 moves the price down: amount = L * (sqrt_a - sqrt_b)
+        let expected = 1e12 * (1.0 - ConcentratedLiquidity::sqrt_price_at_tick(-100)) / 1e9;
+        let capacity = pool.base_capacity(TradeSide::BuyBase, 1);
+        assert!((capacity - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_book_depth_average_price() {
+        let book = BookDepth {
+            bids: vec![(99.0, 1.0), (98.0, 2.0)],
+            asks: vec![(101.0, 1.0), (102.0, 3.0)],
+        };
+        let profile = LiquidityProfile::OrderBook(book.clone());
+
+        // 1 @ 101 + 1 @ 102
+        let price = profile.average_price(TradeSide::BuyBase, 2.0).unwrap();
+        assert!((price - 101.5).abs() < 1e-12);
+        assert_eq!(profile.base_capacity(TradeSide::SellBase, 0), Some(3.0));
+
+        // Thinner than requested: average over what rests on the book
+        let price = book.average_price(TradeSide::SellBase, 10.0).unwrap();
+        assert!((price - (99.0 + 2.0 * 98.0) / 3.0).abs() < 1e-12);
+        assert!(BookDepth::default()
+            .average_price(TradeSide::BuyBase, 1.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_constant_product_swap_with_fee() {
+        let pool = ConstantProductPool {
+            base_reserve: 1_000.0,
+            quote_reserve: 150_000.0,
+            fee_rate: 0.0025,
+        };
+
+        // Buying 10 SOL: 150,000 * 10 / 990 before the fee on the USDC paid
+        let cost = pool.swap_quote_amount(TradeSide::BuyBase, 10.0).unwrap();
+        assert!((cost - 150_000.0 * 10.0 / 990.0 / 0.9975).abs() < 1e-9);
+
+        // Selling 10 SOL: fee comes off the SOL sold
+        let proceeds = pool.swap_quote_amount(TradeSide::SellBase, 10.0).unwrap();
+        let base_in = 10.0 * 0.9975;
+        assert!((proceeds - 150_000.0 * base_in / (1_000.0 + base_in)).abs() < 1e-9);
+
+        // Invariant holds after the fee-less part of the swap
+        let k_after = (1_000.0 + base_in) * (150_000.0 - proceeds);
+        assert!((k_after - 150_000_000.0).abs() < 1e-3);
+
+        let impact = pool.price_impact(TradeSide::BuyBase, 10.0).unwrap();
+        assert!((impact - (1_000.0 / 990.0 - 1.0) * 100.0).abs() < 1e-9);
+        assert!(pool
+            .swap_quote_amount(TradeSide::BuyBase, 1_000.0)
+            .is_none());
+
+        let profile = LiquidityProfile::ConstantProduct(pool);
+        assert!(profile.includes_fee());
+        assert_eq!(profile.base_capacity(TradeSide::BuyBase, 3), Some(1_000.0));
+        assert!(profile.prices_by_size());
+    }
+
+    #[test]
+    fn test_constant_product_quote() {
+        // 1,000 SOL against 150,000 USDC
+        let quote = constant_product_quote(1_000.0, 150_000.0, 10.0).unwrap();
+        assert!((quote.ask - 150_000.0 / 990.0).abs() < 1e-9);
+        assert!((quote.bid - 150_000.0 / 1_010.0).abs() < 1e-9);
+        assert!(quote.bid < 150.0 && quote.ask > 150.0);
+
+        assert!(constant_product_quote(10.0, 1_500.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_concentrated_quote_brackets_mid() {
+        // 150 USDC per SOL = 0.15 USDC atoms per SOL atom
+        let sqrt_price = 0.15f64.sqrt();
+        let quote = concentrated_quote(sqrt_price, 1_000_000_000_000, true, 9, 6, 1.0).unwrap();
+        assert!(quote.bid < 150.0 && quote.ask > 150.0);
+        assert!(quote.ask - quote.bid < 1.0);
+
+        // Same pool with the tokens swapped quotes the same market
+        let flipped =
+            concentrated_quote(1.0 / sqrt_price, 1_000_000_000_000, false, 9, 6, 1.0).unwrap();
+        assert!((flipped.bid - quote.bid).abs() < 1e-6);
+        assert!((flipped.ask - quote.ask).abs() < 1e-6);
+
+        // Buying more than the range holds cannot be quoted
+        assert!(concentrated_quote(sqrt_price, 1_000, true, 9, 6, 1.0).is_none());
+    }
+}
@@ -1,5 +1,9 @@
+pub mod clock;
+pub mod liquidity;
 pub mod processor;
 pub mod types;
 
+pub use clock::*;
+pub use liquidity::*;
 pub use processor::*;
 pub use types::*;
@@ -1,6 +1,6 @@
-use crate::config::{Config, MaxPriceAge};
+use crate::config::{Config, MaxPriceAge, DEFAULT_MAX_PRICE_SKEW};
 use crate::performance::metrics::MetricsCollector;
-use crate::price::{PriceCache, PriceSource, SourcePrice};
+use crate::price::{Clock, PriceCache, PriceSource, SourcePrice};
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
@@ -19,11 +19,28 @@ pub enum ProcessorError {
     StaleData { age_ms: u64, max_age_ms: u64 },
     #[error("Invalid price detected: {price}")]
     InvalidPrice { price: f64 },
+    #[error("Prices are misaligned: skew={skew_ms}ms, max={max_skew_ms}ms")]
+    MisalignedPrices { skew_ms: u64, max_skew_ms: u64 },
     #[error("Price cache lock error")]
     CacheLockError,
 }
 
+impl ProcessorError {
+    /// Short stable code for counting rejections by cause
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            ProcessorError::NoFreshData => "no_data",
+            ProcessorError::StaleData { .. } => "stale",
+            ProcessorError::InvalidPrice { .. } => "invalid_price",
+            ProcessorError::MisalignedPrices { .. } => "skew",
+            ProcessorError::CacheLockError => "cache_lock",
+        }
+    }
+}
+
 /// Validated price pair with freshness guarantee
+///
+/// `solana_price` holds the DEX leg; its `source` identifies which pool it came from.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct ValidatedPricePair {
@@ -56,7 +73,7 @@ impl ValidatedPricePair {
     #[allow(dead_code)]
     pub fn higher_price_source(&self) -> PriceSource {
         if self.solana_price.price > self.binance_price.price {
-            PriceSource::Solana
+            self.solana_price.source
         } else {
             PriceSource::Binance
         }
@@ -66,20 +83,27 @@ impl ValidatedPricePair {
     #[allow(dead_code)]
     pub fn lower_price_source(&self) -> PriceSource {
         if self.solana_price.price < self.binance_price.price {
-            PriceSource::Solana
+            self.solana_price.source
         } else {
             PriceSource::Binance
         }
     }
 
     /// Get price for specific source
+    #[allow(dead_code)]
     pub fn get_price(&self, source: PriceSource) -> &SourcePrice {
-        match source {
-            PriceSource::Solana => &self.solana_price,
-            PriceSource::Binance => &self.binance_price,
+        if source.is_cex() {
+            &self.binance_price
+        } else {
+            &self.solana_price
         }
     }
 
+    /// DEX source this pair compares against Binance
+    pub fn dex_source(&self) -> PriceSource {
+        self.solana_price.source
+    }
+
     /// Check if prices are inverted (Solana higher than Binance)
     #[allow(dead_code)]
     pub fn is_inverted(&self) -> bool {
@@ -97,10 +121,12 @@ impl ValidatedPricePair {
 pub struct PriceProcessor {
     price_cache: Arc<PriceCache>,
     max_price_age: MaxPriceAge,
+    max_price_skew: Duration,
     validation_enabled: bool,
     min_price_bound: f64,
     max_price_bound: f64,
     metrics: Option<Arc<MetricsCollector>>,
+    clock: Clock,
 }
 
 impl PriceProcessor {
@@ -110,10 +136,12 @@ impl PriceProcessor {
         Self {
             price_cache,
             max_price_age: config.max_price_age_ms,
+            max_price_skew: config.max_price_skew,
             validation_enabled: true,
             min_price_bound: config.price_bounds.min_price,
             max_price_bound: config.price_bounds.max_price,
             metrics: None,
+            clock: Clock::System,
         }
     }
 
@@ -127,10 +155,12 @@ impl PriceProcessor {
         Self {
             price_cache,
             max_price_age,
+            max_price_skew: DEFAULT_MAX_PRICE_SKEW,
             validation_enabled,
             min_price_bound: 1.0,
             max_price_bound: 10000.0,
             metrics: None,
+            clock: Clock::System,
         }
     }
 
@@ -141,6 +171,19 @@ impl PriceProcessor {
         self
     }
 
+    /// Set the largest allowed difference between the source times of the two legs
+    #[allow(dead_code)]
+    pub fn with_max_price_skew(mut self, max_price_skew: Duration) -> Self {
+        self.max_price_skew = max_price_skew;
+        self
+    }
+
+    /// Judge freshness against `clock` instead of wall-clock time
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Get validated price pair if available and fresh
     pub fn get_validated_prices(&self) -> Result<ValidatedPricePair, ProcessorError> {
         let start_time = std::time::Instant::now();
@@ -159,6 +202,7 @@ impl PriceProcessor {
             self.validate_price_value(&solana_price)?;
             self.validate_price_value(&binance_price)?;
         }
+        self.validate_alignment(&solana_price, &binance_price)?;
 
         let result = ValidatedPricePair::new(solana_price, binance_price);
 
@@ -170,26 +214,76 @@ impl PriceProcessor {
         Ok(result)
     }
 
+    /// Get a validated pair of Binance against every fresh DEX price
+    ///
+    /// DEX prices that are stale, invalid or too far in time from the Binance price are
+    /// skipped; the Binance price must be usable. Each rejected price is counted by
+    /// reason.
+    pub fn get_validated_price_pairs(&self) -> Result<Vec<ValidatedPricePair>, ProcessorError> {
+        let start_time = std::time::Instant::now();
+
+        // Binance and every DEX as of the same instant
+        let snapshot = self.price_cache.snapshot();
+        let binance_price = snapshot
+            .get(PriceSource::Binance)
+            .ok_or(ProcessorError::NoFreshData)?;
+        if let Err(e) = self.validate_price(binance_price) {
+            self.record_rejection(&e);
+            return Err(e);
+        }
+
+        let mut first_error = None;
+        let mut pairs = Vec::new();
+        for dex_price in snapshot.dex_prices() {
+            let validation = self
+                .validate_price(dex_price)
+                .and_then(|()| self.validate_alignment(dex_price, binance_price));
+            match validation {
+                Ok(()) => pairs.push(ValidatedPricePair::new(
+                    SourcePrice::clone(dex_price),
+                    SourcePrice::clone(binance_price),
+                )),
+                Err(e) => {
+                    self.record_rejection(&e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if pairs.is_empty() {
+            return Err(first_error.unwrap_or(ProcessorError::NoFreshData));
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_processing_time(start_time.elapsed());
+        }
+
+        Ok(pairs)
+    }
+
     /// Wait for fresh price data to become available
+    ///
+    /// Prices are re-validated after each cache update rather than on a timer.
     #[allow(dead_code)]
     pub async fn wait_for_fresh_prices(
         &self,
         timeout: Duration,
     ) -> Result<ValidatedPricePair, ProcessorError> {
-        let start = tokio::time::Instant::now();
-        let mut check_interval = interval(Duration::from_millis(100));
-
-        loop {
-            if let Ok(prices) = self.get_validated_prices() {
-                return Ok(prices);
-            }
-
-            if start.elapsed() >= timeout {
-                return Err(ProcessorError::NoFreshData);
+        // Subscribe before the first check so no update is missed in between
+        let mut updates = self.price_cache.subscribe();
+        let wait = async {
+            loop {
+                if let Ok(prices) = self.get_validated_prices() {
+                    return prices;
+                }
+                if updates.changed().await.is_err() {
+                    std::future::pending::<()>().await;
+                }
             }
-
-            check_interval.tick().await;
-        }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| ProcessorError::NoFreshData)
     }
 
     /// Start background cleanup task for stale prices
@@ -221,9 +315,18 @@ impl PriceProcessor {
         Some((solana.age_ms(), binance.age_ms()))
     }
 
-    /// Validate that price is not stale
+    /// Validate freshness and, if enabled, the price value
+    fn validate_price(&self, price: &SourcePrice) -> Result<(), ProcessorError> {
+        self.validate_price_freshness(price)?;
+        if self.validation_enabled {
+            self.validate_price_value(price)?;
+        }
+        Ok(())
+    }
+
+    /// Validate that price is not stale, judged by the time its source produced it
     fn validate_price_freshness(&self, price: &SourcePrice) -> Result<(), ProcessorError> {
-        let age_ms = price.age_ms();
+        let age_ms = price.age_ms_at(self.clock.now());
         let max_age_ms = self.max_price_age.value();
 
         if age_ms > max_age_ms {
@@ -233,6 +336,31 @@ impl PriceProcessor {
         Ok(())
     }
 
+    /// Validate that the two legs were priced close enough in time to be compared
+    fn validate_alignment(
+        &self,
+        dex_price: &SourcePrice,
+        binance_price: &SourcePrice,
+    ) -> Result<(), ProcessorError> {
+        let skew = match dex_price.timestamp.duration_since(binance_price.timestamp) {
+            Ok(skew) => skew,
+            Err(e) => e.duration(),
+        };
+        if skew > self.max_price_skew {
+            return Err(ProcessorError::MisalignedPrices {
+                skew_ms: skew.as_millis() as u64,
+                max_skew_ms: self.max_price_skew.as_millis() as u64,
+            });
+        }
+        Ok(())
+    }
+
+    fn record_rejection(&self, error: &ProcessorError) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rejection(error.reason_code());
+        }
+    }
+
     /// Validate that price value is reasonable
     fn validate_price_value(&self, price: &SourcePrice) -> Result<(), ProcessorError> {
         if !price.price.is_finite() || price.price <= 0.0 {
@@ -245,6 +373,13 @@ impl PriceProcessor {
             return Err(ProcessorError::InvalidPrice { price: price.price });
         }
 
+        // A crossed or non-finite quote would make the bid/ask legs meaningless
+        if let Some(quote) = &price.quote {
+            if !quote.is_valid() {
+                return Err(ProcessorError::InvalidPrice { price: quote.bid });
+            }
+        }
+
         Ok(())
     }
 
@@ -278,15 +413,15 @@ impl PriceProcessor {
 mod tests {
     use super::*;
     use crate::config::TradingPair;
-    use crate::price::{PriceCache, PriceSource, PriceUpdate, SourcePrice};
+    use crate::price::{PriceCache, PriceSource, PriceUpdate, Quote, SourcePrice};
     use crate::test_utils::config::create_test_config;
 
     fn create_test_price_cache() -> Arc<PriceCache> {
         let cache = Arc::new(PriceCache::new());
 
         // Add some test data
-        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SolUsdt, 195.5);
-        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SolUsdt, 195.0);
+        let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 195.5);
+        let binance_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0);
 
         cache.update(&solana_update);
         cache.update(&binance_update);
@@ -373,16 +508,35 @@ mod tests {
         let processor = PriceProcessor::new(cache.clone(), &config);
 
         // Add invalid price data
-        let invalid_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SolUsdt, -1.0);
+        let invalid_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, -1.0);
         cache.update(&invalid_update);
 
-        let valid_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SolUsdt, 195.0);
+        let valid_update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0);
         cache.update(&valid_update);
 
         let result = processor.get_validated_prices();
         assert!(matches!(result, Err(ProcessorError::InvalidPrice { .. })));
     }
 
+    #[test]
+    fn test_crossed_quote_rejected() {
+        let config = create_test_config();
+        let cache = Arc::new(PriceCache::new());
+        let processor = PriceProcessor::new(cache.clone(), &config);
+
+        let crossed = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0)
+            .with_quote(Quote::new(196.0, 194.0));
+        cache.update(&crossed);
+        cache.update(&PriceUpdate::new(
+            PriceSource::Solana,
+            TradingPair::SOL_USDT,
+            190.0,
+        ));
+
+        let result = processor.get_validated_prices();
+        assert!(matches!(result, Err(ProcessorError::InvalidPrice { .. })));
+    }
+
     #[test]
     fn test_price_freshness_check() {
         let config = create_test_config();
@@ -415,6 +569,97 @@ mod tests {
         assert!(!pair.is_inverted()); // Binance higher than Solana
     }
 
+    #[test]
+    fn test_validated_price_pairs_per_dex() {
+        let config = create_test_config();
+        let cache = create_test_price_cache();
+        cache.update(&PriceUpdate::new(
+            PriceSource::Orca,
+            TradingPair::SOL_USDT,
+            194.0,
+        ));
+        let processor = PriceProcessor::new(cache, &config);
+
+        let pairs = processor.get_validated_price_pairs().unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].dex_source(), PriceSource::Solana);
+        assert_eq!(pairs[1].dex_source(), PriceSource::Orca);
+        assert_eq!(pairs[1].lower_price_source(), PriceSource::Orca);
+        assert_eq!(pairs[1].get_price(PriceSource::Orca).price, 194.0);
+    }
+
+    #[test]
+    fn test_validated_price_pairs_skip_invalid_dex() {
+        let config = create_test_config();
+        let cache = Arc::new(PriceCache::new());
+        cache.update(&PriceUpdate::new(
+            PriceSource::Binance,
+            TradingPair::SOL_USDT,
+            195.0,
+        ));
+        cache.update(&PriceUpdate::new(
+            PriceSource::Solana,
+            TradingPair::SOL_USDT,
+            -1.0,
+        ));
+        let processor = PriceProcessor::new(cache.clone(), &config);
+
+        assert!(matches!(
+            processor.get_validated_price_pairs(),
+            Err(ProcessorError::InvalidPrice { .. })
+        ));
+
+        cache.update(&PriceUpdate::new(
+            PriceSource::Orca,
+            TradingPair::SOL_USDT,
+            196.0,
+        ));
+        let pairs = processor.get_validated_price_pairs().unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].dex_source(), PriceSource::Orca);
+    }
+
+    #[test]
+    fn test_misaligned_legs_rejected_with_skew_reason() {
+        let config = create_test_config();
+        let cache = Arc::new(PriceCache::new());
+        let metrics = Arc::new(MetricsCollector::new());
+        let processor = PriceProcessor::new(cache.clone(), &config)
+            .with_max_price_skew(Duration::from_millis(1000))
+            .with_metrics(Arc::clone(&metrics));
+
+        // Both fresh, but the Solana price is 4.9s older than the Binance one
+        let now = std::time::SystemTime::now();
+        cache.update(
+            &PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0)
+                .with_source_time(now),
+        );
+        cache.update(
+            &PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 190.0)
+                .with_source_time(now - Duration::from_millis(4900)),
+        );
+        let error = processor.get_validated_price_pairs().unwrap_err();
+        assert!(matches!(
+            error,
+            ProcessorError::MisalignedPrices {
+                skew_ms: 4900,
+                max_skew_ms: 1000
+            }
+        ));
+        assert_eq!(error.reason_code(), "skew");
+        assert!(processor.get_validated_prices().is_err());
+
+        // An aligned DEX price is still compared
+        cache.update(
+            &PriceUpdate::new(PriceSource::Orca, TradingPair::SOL_USDT, 194.0)
+                .with_source_time(now - Duration::from_millis(300)),
+        );
+        let pairs = processor.get_validated_price_pairs().unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].dex_source(), PriceSource::Orca);
+        assert_eq!(metrics.rejections().get("skew"), Some(&2));
+    }
+
     #[tokio::test]
     async fn test_wait_for_fresh_prices_timeout() {
         let config = create_test_config();
@@ -437,9 +682,9 @@ mod tests {
         let cache_clone = Arc::clone(&cache);
         tokio::spawn(async move {
             sleep(Duration::from_millis(10)).await;
-            let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SolUsdt, 195.5);
+            let solana_update = PriceUpdate::new(PriceSource::Solana, TradingPair::SOL_USDT, 195.5);
             let binance_update =
-                PriceUpdate::new(PriceSource::Binance, TradingPair::SolUsdt, 195.0);
+                PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0);
             cache_clone.update(&solana_update);
             cache_clone.update(&binance_update);
         });
@@ -1,12 +1,13 @@
 use crate::config::TradingPair;
-use log::error;
-use std::sync::{Arc, RwLock};
+use crate::price::{LiquidityProfile, TradeSide};
+use arc_swap::ArcSwap;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
 
-/// Helper function for calculating age in milliseconds
-fn calculate_age_ms(timestamp: SystemTime) -> u64 {
-    SystemTime::now()
-        .duration_since(timestamp)
+/// Helper function for calculating age in milliseconds at `now`
+fn calculate_age_ms(timestamp: SystemTime, now: SystemTime) -> u64 {
+    now.duration_since(timestamp)
         .unwrap_or_default()
         .as_millis()
         .try_into()
@@ -36,34 +37,174 @@ mod systemtime_serde {
     }
 }
 
+/// Optional [`systemtime_serde`] field
+mod optional_systemtime_serde {
+    use serde::{Deserialize, Deserializer};
+    use std::time::SystemTime;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<SystemTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::systemtime_serde")] SystemTime);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(time)| time))
+    }
+}
+
+/// Best bid and ask with optional sizes (in base tokens)
+///
+/// For AMMs the bid/ask are the curve quoted in both directions at a reference size.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Quote {
+    pub bid: f64,
+    pub ask: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bid_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ask_size: Option<f64>,
+}
+
+impl Quote {
+    /// Create a quote without size information
+    pub fn new(bid: f64, ask: f64) -> Self {
+        Self {
+            bid,
+            ask,
+            bid_size: None,
+            ask_size: None,
+        }
+    }
+
+    /// Attach the sizes available at the bid and ask
+    pub fn with_sizes(mut self, bid_size: f64, ask_size: f64) -> Self {
+        self.bid_size = Some(bid_size);
+        self.ask_size = Some(ask_size);
+        self
+    }
+
+    /// Mid price between bid and ask
+    pub fn mid(&self) -> f64 {
+        (self.bid + self.ask) / 2.0
+    }
+
+    /// Price a taker trades at: buys lift the ask, sells hit the bid
+    pub fn price(&self, side: TradeSide) -> f64 {
+        match side {
+            TradeSide::BuyBase => self.ask,
+            TradeSide::SellBase => self.bid,
+        }
+    }
+
+    /// Size available to a taker on `side`, if known
+    pub fn size(&self, side: TradeSide) -> Option<f64> {
+        match side {
+            TradeSide::BuyBase => self.ask_size,
+            TradeSide::SellBase => self.bid_size,
+        }
+    }
+
+    /// Check that both sides are positive, finite and not crossed
+    pub fn is_valid(&self) -> bool {
+        self.bid.is_finite() && self.ask.is_finite() && self.bid > 0.0 && self.ask >= self.bid
+    }
+}
+
 /// Price update from a WebSocket source
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(from = "PriceUpdateRecord")]
 pub struct PriceUpdate {
     pub source: PriceSource,
     pub pair: TradingPair,
     pub price: f64,
+    /// Time the source produced the price: exchange event time or slot time
     #[serde(with = "systemtime_serde")]
     pub timestamp: SystemTime,
+    /// Time the update was received locally
+    #[serde(with = "systemtime_serde")]
+    pub received_at: SystemTime,
+    /// Solana slot the update was observed at (on-chain sources only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    /// Best bid/ask behind the price, if the source provides one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<Quote>,
+    /// Liquidity behind the price (sources that expose depth)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity: Option<LiquidityProfile>,
 }
 
 impl PriceUpdate {
-    /// Create new price update with current timestamp
+    /// Create new price update produced and received now
     pub fn new(source: PriceSource, pair: TradingPair, price: f64) -> Self {
+        let now = SystemTime::now();
         Self {
             source,
             pair,
             price,
-            timestamp: SystemTime::now(),
+            timestamp: now,
+            received_at: now,
+            slot: None,
+            quote: None,
+            liquidity: None,
         }
     }
 
+    /// Set the time the source produced this price
+    pub fn with_source_time(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the time this update was received
+    pub fn with_received_at(mut self, received_at: SystemTime) -> Self {
+        self.received_at = received_at;
+        self
+    }
+
+    /// Move the receive time to `received_at`, keeping the source time as far before it
+    pub fn rebase_to(&mut self, received_at: SystemTime) {
+        self.timestamp = match self.received_at.duration_since(self.timestamp) {
+            Ok(delay) => received_at - delay,
+            Err(e) => received_at + e.duration(),
+        };
+        self.received_at = received_at;
+    }
+
+    /// Receive time minus source time in milliseconds; negative when the source clock is ahead
+    pub fn clock_skew_ms(&self) -> f64 {
+        match self.received_at.duration_since(self.timestamp) {
+            Ok(skew) => skew.as_secs_f64() * 1000.0,
+            Err(e) => -e.duration().as_secs_f64() * 1000.0,
+        }
+    }
+
+    /// Attach the slot this update was observed at
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Attach the bid/ask quote behind this price
+    pub fn with_quote(mut self, quote: Quote) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// Attach the liquidity profile behind this price
+    pub fn with_liquidity(mut self, liquidity: LiquidityProfile) -> Self {
+        self.liquidity = Some(liquidity);
+        self
+    }
+
     /// Validate price value for financial data integrity
     #[allow(dead_code)]
     pub fn is_valid_price(&self) -> bool {
         self.price.is_finite() && self.price > 0.0
     }
 
-    /// Create price update with specific timestamp
+    /// Create price update produced and received at `timestamp`
     #[allow(dead_code)]
     pub fn with_timestamp(
         source: PriceSource,
@@ -76,13 +217,17 @@ impl PriceUpdate {
             pair,
             price,
             timestamp,
+            received_at: timestamp,
+            slot: None,
+            quote: None,
+            liquidity: None,
         }
     }
 
     /// Get age of this price update in milliseconds
     #[allow(dead_code)]
     pub fn age_ms(&self) -> u64 {
-        calculate_age_ms(self.timestamp)
+        calculate_age_ms(self.timestamp, SystemTime::now())
     }
 
     /// Check if this price update is stale based on max age
@@ -92,27 +237,107 @@ impl PriceUpdate {
     }
 }
 
+/// Serialized price update; updates written before receive times were kept have none
+#[derive(serde::Deserialize)]
+struct PriceUpdateRecord {
+    source: PriceSource,
+    pair: TradingPair,
+    price: f64,
+    #[serde(with = "systemtime_serde")]
+    timestamp: SystemTime,
+    #[serde(default, with = "optional_systemtime_serde")]
+    received_at: Option<SystemTime>,
+    #[serde(default)]
+    slot: Option<u64>,
+    #[serde(default)]
+    quote: Option<Quote>,
+    #[serde(default)]
+    liquidity: Option<LiquidityProfile>,
+}
+
+impl From<PriceUpdateRecord> for PriceUpdate {
+    fn from(record: PriceUpdateRecord) -> Self {
+        Self {
+            source: record.source,
+            pair: record.pair,
+            price: record.price,
+            timestamp: record.timestamp,
+            received_at: record.received_at.unwrap_or(record.timestamp),
+            slot: record.slot,
+            quote: record.quote,
+            liquidity: record.liquidity,
+        }
+    }
+}
+
 /// Price source identifier for arbitrage direction calculation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PriceSource {
+    /// Raydium AMM v4 pool on Solana
     Solana,
     Binance,
+    /// Orca Whirlpool (concentrated liquidity) on Solana
+    Orca,
+    /// Raydium concentrated liquidity pool on Solana
+    #[serde(rename = "raydium_clmm")]
+    RaydiumClmm,
+    /// OpenBook v2 central limit order book on Solana
+    #[serde(rename = "openbook")]
+    OpenBook,
 }
 
 impl PriceSource {
+    /// All known price sources
+    #[allow(dead_code)]
+    pub const ALL: [PriceSource; 5] = [
+        PriceSource::Solana,
+        PriceSource::Binance,
+        PriceSource::Orca,
+        PriceSource::RaydiumClmm,
+        PriceSource::OpenBook,
+    ];
+
+    /// On-chain DEX sources compared against Binance
+    pub const DEX_SOURCES: [PriceSource; 4] = [
+        PriceSource::Solana,
+        PriceSource::Orca,
+        PriceSource::RaydiumClmm,
+        PriceSource::OpenBook,
+    ];
+
     /// Get display name for this source
     pub fn display_name(&self) -> &'static str {
         match self {
             PriceSource::Solana => "Solana DEX",
             PriceSource::Binance => "Binance",
+            PriceSource::Orca => "Orca Whirlpool",
+            PriceSource::RaydiumClmm => "Raydium CLMM",
+            PriceSource::OpenBook => "OpenBook",
+        }
+    }
+
+    /// Identifier used in serialized output and metric labels, e.g. `raydium_clmm`
+    pub fn id(&self) -> &'static str {
+        match self {
+            PriceSource::Solana => "solana",
+            PriceSource::Binance => "binance",
+            PriceSource::Orca => "orca",
+            PriceSource::RaydiumClmm => "raydium_clmm",
+            PriceSource::OpenBook => "openbook",
         }
     }
 
     /// Check if this is a DEX source
     #[allow(dead_code)]
     pub fn is_dex(&self) -> bool {
-        matches!(self, PriceSource::Solana)
+        matches!(
+            self,
+            PriceSource::Solana
+                | PriceSource::Orca
+                | PriceSource::RaydiumClmm
+                | PriceSource::OpenBook
+        )
     }
 
     /// Check if this is a CEX source
@@ -129,6 +354,10 @@ pub struct SourcePrice {
     #[allow(dead_code)] // Used for debugging and future features
     pub source: PriceSource,
     pub timestamp: SystemTime,
+    /// Best bid/ask, if the source reports it
+    pub quote: Option<Quote>,
+    /// Liquidity behind the price, if the source reports it
+    pub liquidity: Option<LiquidityProfile>,
 }
 
 impl SourcePrice {
@@ -139,6 +368,8 @@ impl SourcePrice {
             price,
             source,
             timestamp: SystemTime::now(),
+            quote: None,
+            liquidity: None,
         }
     }
 
@@ -148,12 +379,38 @@ impl SourcePrice {
             price: update.price,
             source: update.source,
             timestamp: update.timestamp,
+            quote: update.quote,
+            liquidity: update.liquidity.clone(),
         }
     }
 
+    /// Attach a bid/ask quote
+    #[allow(dead_code)]
+    pub fn with_quote(mut self, quote: Quote) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// Price a taker trades at on `side`, falling back to the single price
+    pub fn execution_price(&self, side: TradeSide) -> f64 {
+        self.quote
+            .map(|quote| quote.price(side))
+            .unwrap_or(self.price)
+    }
+
+    /// Base tokens available to a taker on `side`, if known
+    pub fn available_size(&self, side: TradeSide) -> Option<f64> {
+        self.quote.and_then(|quote| quote.size(side))
+    }
+
     /// Get age of this price data in milliseconds
     pub fn age_ms(&self) -> u64 {
-        calculate_age_ms(self.timestamp)
+        self.age_ms_at(SystemTime::now())
+    }
+
+    /// Age of this price data in milliseconds at `now`
+    pub fn age_ms_at(&self, now: SystemTime) -> u64 {
+        calculate_age_ms(self.timestamp, now)
     }
 
     /// Check if price data is considered stale
@@ -171,11 +428,88 @@ impl SourcePrice {
     }
 }
 
-/// Thread-safe cache for latest price data from each source
+/// Latest update stored in a price cache, as published to subscribers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheEvent {
+    pub source: PriceSource,
+    /// Source time of the update
+    pub timestamp: SystemTime,
+    /// Time the update was received
+    pub received_at: SystemTime,
+}
+
+/// Number of price sources, one cache slot each
+const SOURCE_COUNT: usize = 5;
+
+impl PriceSource {
+    /// Slot of this source in a [`PriceSnapshot`]
+    fn slot(self) -> usize {
+        match self {
+            PriceSource::Solana => 0,
+            PriceSource::Binance => 1,
+            PriceSource::Orca => 2,
+            PriceSource::RaydiumClmm => 3,
+            PriceSource::OpenBook => 4,
+        }
+    }
+}
+
+/// Immutable view of every source's latest price at one instant
+///
+/// Each source has its own version, bumped on every update to it; `sequence` is bumped
+/// on every update to any source.
+#[derive(Debug, Clone, Default)]
+pub struct PriceSnapshot {
+    prices: [Option<Arc<SourcePrice>>; SOURCE_COUNT],
+    versions: [u64; SOURCE_COUNT],
+    sequence: u64,
+}
+
+impl PriceSnapshot {
+    /// Latest price from `source`, if any
+    pub fn get(&self, source: PriceSource) -> Option<&Arc<SourcePrice>> {
+        self.prices[source.slot()].as_ref()
+    }
+
+    /// Number of updates applied to `source`
+    #[allow(dead_code)]
+    pub fn version(&self, source: PriceSource) -> u64 {
+        self.versions[source.slot()]
+    }
+
+    /// Number of updates applied to any source
+    #[allow(dead_code)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Available DEX prices
+    pub fn dex_prices(&self) -> impl Iterator<Item = &Arc<SourcePrice>> {
+        PriceSource::DEX_SOURCES
+            .iter()
+            .filter_map(|&source| self.get(source))
+    }
+
+    fn with_price(&self, price: Arc<SourcePrice>) -> Self {
+        let slot = price.source.slot();
+        let mut next = self.clone();
+        next.prices[slot] = Some(price);
+        next.versions[slot] += 1;
+        next.sequence += 1;
+        next
+    }
+}
+
+/// Lock-free cache for latest price data from each source
+///
+/// Writers publish a new [`PriceSnapshot`] with a compare-and-swap, and readers load the
+/// current one without blocking writers, so every read sees all sources at the same
+/// instant. Every update is also published on a watch channel, so subscribers wake on
+/// new prices instead of polling.
 #[derive(Debug)]
 pub struct PriceCache {
-    solana_price: Arc<RwLock<Option<SourcePrice>>>,
-    binance_price: Arc<RwLock<Option<SourcePrice>>>,
+    snapshot: ArcSwap<PriceSnapshot>,
+    updates: watch::Sender<Option<CacheEvent>>,
 }
 
 impl Default for PriceCache {
@@ -188,69 +522,161 @@ impl PriceCache {
     /// Create new empty price cache
     pub fn new() -> Self {
         Self {
-            solana_price: Arc::new(RwLock::new(None)),
-            binance_price: Arc::new(RwLock::new(None)),
+            snapshot: ArcSwap::from_pointee(PriceSnapshot::default()),
+            updates: watch::channel(None).0,
         }
     }
 
+    /// Receiver notified after every update; the current value counts as seen
+    pub fn subscribe(&self) -> watch::Receiver<Option<CacheEvent>> {
+        self.updates.subscribe()
+    }
+
     /// Update price for a specific source
     pub fn update(&self, update: &PriceUpdate) {
-        let source_price = SourcePrice::from_update(update);
-        match update.source {
-            PriceSource::Solana => match self.solana_price.write() {
-                Ok(mut price) => *price = Some(source_price),
-                Err(_) => error!("Failed to acquire write lock for Solana price"),
-            },
-            PriceSource::Binance => match self.binance_price.write() {
-                Ok(mut price) => *price = Some(source_price),
-                Err(_) => error!("Failed to acquire write lock for Binance price"),
-            },
-        }
+        let price = Arc::new(SourcePrice::from_update(update));
+        self.snapshot
+            .rcu(|current| current.with_price(Arc::clone(&price)));
+        self.updates.send_replace(Some(CacheEvent {
+            source: update.source,
+            timestamp: update.timestamp,
+            received_at: update.received_at,
+        }));
+    }
+
+    /// Current prices of every source, consistent with each other
+    pub fn snapshot(&self) -> Arc<PriceSnapshot> {
+        self.snapshot.load_full()
     }
 
     /// Get current prices from both sources if available
     pub fn get_both_prices(&self) -> Option<(SourcePrice, SourcePrice)> {
-        let solana_lock = self.solana_price.read().ok()?;
-        let binance_lock = self.binance_price.read().ok()?;
-        let solana = solana_lock.clone()?;
-        let binance = binance_lock.clone()?;
-        Some((solana, binance))
+        let snapshot = self.snapshot.load();
+        let solana = snapshot.get(PriceSource::Solana)?;
+        let binance = snapshot.get(PriceSource::Binance)?;
+        Some((SourcePrice::clone(solana), SourcePrice::clone(binance)))
     }
 
     /// Get price for specific source
     #[allow(dead_code)]
-    pub fn get_price(&self, source: PriceSource) -> Option<SourcePrice> {
-        match source {
-            PriceSource::Solana => self.solana_price.read().ok()?.clone(),
-            PriceSource::Binance => self.binance_price.read().ok()?.clone(),
-        }
+    pub fn get_price(&self, source: PriceSource) -> Option<Arc<SourcePrice>> {
+        self.snapshot.load().get(source).cloned()
     }
 
-    /// Check if both prices are available and fresh
+    /// Get all available DEX prices
+    #[allow(dead_code)]
+    pub fn get_dex_prices(&self) -> Vec<Arc<SourcePrice>> {
+        self.snapshot.load().dex_prices().cloned().collect()
+    }
+
+    /// Check if the Binance price and at least one DEX price are available and fresh
     pub fn has_fresh_prices(&self, max_age_ms: u64) -> bool {
-        self.get_both_prices()
-            .map(|(solana, binance)| !solana.is_stale(max_age_ms) && !binance.is_stale(max_age_ms))
-            .unwrap_or(false)
+        let snapshot = self.snapshot.load();
+        let binance_fresh = snapshot
+            .get(PriceSource::Binance)
+            .is_some_and(|binance| !binance.is_stale(max_age_ms));
+
+        binance_fresh && snapshot.dex_prices().any(|dex| !dex.is_stale(max_age_ms))
     }
 
     /// Clear stale prices based on max age
     #[allow(dead_code)]
     pub fn clear_stale_prices(&self, max_age_ms: u64) {
-        match self.solana_price.write() {
-            Ok(mut s) => {
-                if s.as_ref().is_some_and(|p| p.is_stale(max_age_ms)) {
-                    *s = None;
+        self.snapshot.rcu(|current| {
+            let mut next = PriceSnapshot::clone(current);
+            for price in next.prices.iter_mut() {
+                if price.as_ref().is_some_and(|p| p.is_stale(max_age_ms)) {
+                    *price = None;
                 }
             }
-            Err(_) => error!("Failed to acquire write lock for Solana price during cleanup"),
+            next
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_source_and_receive_times() {
+        let source_time = UNIX_EPOCH + Duration::from_millis(1_000_000);
+        let mut update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDT, 195.0)
+            .with_source_time(source_time)
+            .with_received_at(source_time + Duration::from_millis(120));
+        assert_eq!(update.clock_skew_ms(), 120.0);
+
+        let json = serde_json::to_string(&update).unwrap();
+        let read: PriceUpdate = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (read.timestamp, read.received_at),
+            (update.timestamp, update.received_at)
+        );
+
+        // Rebasing keeps the skew, including a source clock ahead of ours
+        update.rebase_to(UNIX_EPOCH + Duration::from_millis(5_000_000));
+        assert_eq!(
+            update.timestamp,
+            UNIX_EPOCH + Duration::from_millis(4_999_880)
+        );
+        update.timestamp = update.received_at + Duration::from_millis(30);
+        assert_eq!(update.clock_skew_ms(), -30.0);
+
+        // Updates serialized without a receive time were received at their timestamp
+        let read: PriceUpdate = serde_json::from_str(
+            r#"{"source":"binance","pair":"sol-usdt","price":195.0,"timestamp":1000}"#,
+        )
+        .unwrap();
+        assert_eq!(read.received_at, UNIX_EPOCH + Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_snapshot_versions_and_consistency() {
+        let cache = PriceCache::new();
+        let pair = TradingPair::SOL_USDT;
+        cache.update(&PriceUpdate::new(PriceSource::Solana, pair, 190.0));
+        cache.update(&PriceUpdate::new(PriceSource::Binance, pair, 195.0));
+        cache.update(&PriceUpdate::new(PriceSource::Binance, pair, 196.0));
+
+        let snapshot = cache.snapshot();
+        assert_eq!(snapshot.version(PriceSource::Solana), 1);
+        assert_eq!(snapshot.version(PriceSource::Binance), 2);
+        assert_eq!(snapshot.version(PriceSource::Orca), 0);
+        assert_eq!(snapshot.sequence(), 3);
+
+        // A snapshot is unaffected by later updates
+        cache.update(&PriceUpdate::new(PriceSource::Binance, pair, 197.0));
+        assert_eq!(snapshot.get(PriceSource::Binance).unwrap().price, 196.0);
+        assert_eq!(cache.get_price(PriceSource::Binance).unwrap().price, 197.0);
+        assert!(cache.get_price(PriceSource::Orca).is_none());
+        assert_eq!(cache.get_dex_prices().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_every_update() {
+        let cache = Arc::new(PriceCache::new());
+        let writers: Vec<_> = [PriceSource::Solana, PriceSource::Binance, PriceSource::Orca]
+            .into_iter()
+            .map(|source| {
+                let cache = Arc::clone(&cache);
+                std::thread::spawn(move || {
+                    for i in 0..1000 {
+                        let price = 100.0 + i as f64;
+                        cache.update(&PriceUpdate::new(source, TradingPair::SOL_USDT, price));
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
         }
-        match self.binance_price.write() {
-            Ok(mut b) => {
-                if b.as_ref().is_some_and(|p| p.is_stale(max_age_ms)) {
-                    *b = None;
-                }
-            }
-            Err(_) => error!("Failed to acquire write lock for Binance price during cleanup"),
+
+        let snapshot = cache.snapshot();
+        assert_eq!(snapshot.sequence(), 3000);
+        for source in [PriceSource::Solana, PriceSource::Binance, PriceSource::Orca] {
+            assert_eq!(snapshot.version(source), 1000);
+            assert_eq!(snapshot.get(source).unwrap().price, 1099.0);
         }
     }
 }
@@ -1,5 +1,5 @@
 #[cfg(test)]
-use crate::config::{Config, RawConfig, TradingPair};
+use crate::config::{Config, RawConfig};
 #[cfg(test)]
 use crate::output::OutputFormat;
 
@@ -16,9 +16,12 @@ pub mod config {
     /// Create a test configuration with custom profit threshold
     pub fn create_test_config_with_threshold(threshold: f64) -> Config {
         let raw = RawConfig {
-            pair: TradingPair::SolUsdt,
+            config: None,
+            pair: vec!["sol-usdt".to_string()],
+            define_pair: Vec::new(),
             threshold,
             max_price_age_ms: 5000,
+            max_price_skew_ms: 2000,
             rpc_url: None,
             helius_api_key: None,
             alchemy_api_key: None,
@@ -27,6 +30,22 @@ pub mod config {
             min_price: 1.0,
             max_price: 10000.0,
             enable_performance_monitor: false,
+            metrics_addr: None,
+            webhook_url: None,
+            min_notional: 0.0,
+            max_notional: None,
+            max_base_inventory: None,
+            max_quote_inventory: None,
+            cex_impact_bps: None,
+            record: None,
+            replay: None,
+            replay_speed: 1.0,
+            debounce_ms: 0,
+            paper_trade: false,
+            paper_latency_ms: 200,
+            command: None,
+            file: Default::default(),
+            from_file: Default::default(),
         };
 
         Config::new(&raw).expect("Valid test configuration")
@@ -42,3 +61,105 @@ pub mod config {
         create_test_config_with_threshold(0.01)
     }
 }
+
+/// Local HTTP stand-in for REST endpoints
+#[cfg(test)]
+pub mod http {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::task::JoinHandle;
+
+    /// A request received by the stand-in
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    pub struct RecordedRequest {
+        pub request_line: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl RecordedRequest {
+        /// Value of the first header named `name`, ignoring case
+        #[allow(dead_code)]
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Serve one canned (status, JSON body) response per connection, in order
+    ///
+    /// Returns the base URL and a handle that resolves to the requests received once
+    /// every response has been served.
+    pub async fn serve(
+        responses: Vec<(u16, String)>,
+    ) -> (String, JoinHandle<Vec<RecordedRequest>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                requests.push(read_request(&mut stream).await);
+
+                let response = format!(
+                    "HTTP/1.1 {} Stand-in\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.shutdown().await.ok();
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> RecordedRequest {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        let header_end = loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            data.extend_from_slice(&buf[..n]);
+            if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            if n == 0 {
+                break data.len();
+            }
+        };
+
+        let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+        let headers: Vec<(String, String)> = head
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect();
+        let content_length = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        while data.len() < header_end + content_length {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+        }
+
+        RecordedRequest {
+            request_line: head.lines().next().unwrap_or_default().to_string(),
+            headers,
+            body: String::from_utf8_lossy(&data[header_end..]).to_string(),
+        }
+    }
+}
@@ -2,11 +2,8 @@ use crate::config::TradingPair;
 use crate::price::PriceSource;
 
 /// Format a trading pair as a human-readable string
-pub fn format_trading_pair(pair: TradingPair) -> &'static str {
-    match pair {
-        TradingPair::SolUsdt => "SOL/USDT",
-        TradingPair::SolUsdc => "SOL/USDC",
-    }
+pub fn format_trading_pair(pair: TradingPair) -> String {
+    pair.label()
 }
 
 /// Format a price source as a human-readable string
@@ -14,6 +11,9 @@ pub fn format_price_source(source: PriceSource) -> &'static str {
     match source {
         PriceSource::Solana => "Solana",
         PriceSource::Binance => "Binance",
+        PriceSource::Orca => "Orca",
+        PriceSource::RaydiumClmm => "Raydium CLMM",
+        PriceSource::OpenBook => "OpenBook",
     }
 }
 
@@ -29,14 +29,15 @@ mod tests {
 
     #[test]
     fn test_format_trading_pair() {
-        assert_eq!(format_trading_pair(TradingPair::SolUsdt), "SOL/USDT");
-        assert_eq!(format_trading_pair(TradingPair::SolUsdc), "SOL/USDC");
+        assert_eq!(format_trading_pair(TradingPair::SOL_USDT), "SOL/USDT");
+        assert_eq!(format_trading_pair(TradingPair::SOL_USDC), "SOL/USDC");
     }
 
     #[test]
     fn test_format_price_source() {
         assert_eq!(format_price_source(PriceSource::Solana), "Solana");
         assert_eq!(format_price_source(PriceSource::Binance), "Binance");
+        assert_eq!(format_price_source(PriceSource::Orca), "Orca");
     }
 
     #[test]
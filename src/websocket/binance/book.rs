@@ -0,0 +1,275 @@
+use crate::price::{BookDepth, Quote};
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors that can occur while maintaining the local order book
+#[derive(Debug, Error, PartialEq)]
+pub enum BookError {
+    #[error("Depth update sequence gap: expected first id {expected}, got {got}")]
+    SequenceGap { expected: u64, got: u64 },
+    #[error("Invalid price level: {0}")]
+    InvalidLevel(String),
+}
+
+/// REST depth snapshot (`GET /api/v3/depth`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: u64,
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+/// Diff depth stream event (`<symbol>@depth`)
+#[derive(Debug, Clone, Deserialize)]
+#[allow(dead_code)]
+pub struct DepthUpdate {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    /// First update id in the event
+    #[serde(rename = "U")]
+    pub first_update_id: u64,
+    /// Final update id in the event
+    #[serde(rename = "u")]
+    pub final_update_id: u64,
+    #[serde(rename = "b")]
+    pub bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    pub asks: Vec<[String; 2]>,
+}
+
+/// Price key ordered by value (prices are always positive and finite)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PriceKey(f64);
+
+impl Eq for PriceKey {}
+
+impl PartialOrd for PriceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Local copy of the Binance order book, kept in sync from a snapshot plus diff events
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    last_update_id: u64,
+    bids: BTreeMap<PriceKey, f64>,
+    asks: BTreeMap<PriceKey, f64>,
+}
+
+impl OrderBook {
+    /// Build the book from a REST snapshot
+    pub fn from_snapshot(snapshot: &DepthSnapshot) -> Result<Self, BookError> {
+        let mut book = Self {
+            last_update_id: snapshot.last_update_id,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        };
+        Self::apply_levels(&mut book.bids, &snapshot.bids)?;
+        Self::apply_levels(&mut book.asks, &snapshot.asks)?;
+        Ok(book)
+    }
+
+    /// Apply a diff event, returning whether it changed the book
+    ///
+    /// Events already covered by the book (`u` <= last update id) are ignored. Any
+    /// event that does not start at or before the next expected id is a gap, and the
+    /// book must be rebuilt from a fresh snapshot.
+    pub fn apply(&mut self, update: &DepthUpdate) -> Result<bool, BookError> {
+        if update.final_update_id <= self.last_update_id {
+            return Ok(false);
+        }
+        let expected = self.last_update_id + 1;
+        if update.first_update_id > expected {
+            return Err(BookError::SequenceGap {
+                expected,
+                got: update.first_update_id,
+            });
+        }
+
+        Self::apply_levels(&mut self.bids, &update.bids)?;
+        Self::apply_levels(&mut self.asks, &update.asks)?;
+        self.last_update_id = update.final_update_id;
+        Ok(true)
+    }
+
+    /// Set or remove (zero quantity) price levels
+    fn apply_levels(
+        side: &mut BTreeMap<PriceKey, f64>,
+        levels: &[[String; 2]],
+    ) -> Result<(), BookError> {
+        for [price, quantity] in levels {
+            let parse = |value: &String| {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite() && *v >= 0.0)
+                    .ok_or_else(|| BookError::InvalidLevel(format!("{} @ {}", quantity, price)))
+            };
+            let (price, quantity) = (parse(price)?, parse(quantity)?);
+
+            if quantity == 0.0 {
+                side.remove(&PriceKey(price));
+            } else {
+                side.insert(PriceKey(price), quantity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Last update id applied to the book
+    #[allow(dead_code)]
+    pub fn last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    /// Best bid as (price, quantity)
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(price, qty)| (price.0, *qty))
+    }
+
+    /// Best ask as (price, quantity)
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.iter().next().map(|(price, qty)| (price.0, *qty))
+    }
+
+    /// Top-of-book quote with sizes, if both sides have orders
+    pub fn quote(&self) -> Option<Quote> {
+        let ((bid, bid_size), (ask, ask_size)) = (self.best_bid()?, self.best_ask()?);
+        Some(Quote::new(bid, ask).with_sizes(bid_size, ask_size))
+    }
+
+    /// The best `levels` price levels on each side, best first
+    pub fn depth(&self, levels: usize) -> BookDepth {
+        BookDepth {
+            bids: self
+                .bids
+                .iter()
+                .rev()
+                .take(levels)
+                .map(|(price, qty)| (price.0, *qty))
+                .collect(),
+            asks: self
+                .asks
+                .iter()
+                .take(levels)
+                .map(|(price, qty)| (price.0, *qty))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::TradeSide;
+
+    fn levels(levels: &[(&str, &str)]) -> Vec<[String; 2]> {
+        levels
+            .iter()
+            .map(|(price, qty)| [price.to_string(), qty.to_string()])
+            .collect()
+    }
+
+    fn update(first: u64, last: u64, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> DepthUpdate {
+        DepthUpdate {
+            event_time: 0,
+            symbol: "SOLUSDT".to_string(),
+            first_update_id: first,
+            final_update_id: last,
+            bids: levels(bids),
+            asks: levels(asks),
+        }
+    }
+
+    fn test_book() -> OrderBook {
+        OrderBook::from_snapshot(&DepthSnapshot {
+            last_update_id: 100,
+            bids: levels(&[("194.90", "5"), ("194.80", "10")]),
+            asks: levels(&[("195.10", "4"), ("195.20", "8")]),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_top_of_book() {
+        let book = test_book();
+        assert_eq!(book.best_bid(), Some((194.90, 5.0)));
+        assert_eq!(book.best_ask(), Some((195.10, 4.0)));
+
+        let quote = book.quote().unwrap();
+        assert_eq!(quote.bid_size, Some(5.0));
+        assert_eq!(quote.ask, 195.10);
+    }
+
+    #[test]
+    fn test_diff_updates_and_removal() {
+        let mut book = test_book();
+
+        // Straddles the snapshot id, so it is the first event to apply
+        let applied = book
+            .apply(&update(95, 101, &[("194.90", "0")], &[("195.05", "1")]))
+            .unwrap();
+        assert!(applied);
+        assert_eq!(book.best_bid(), Some((194.80, 10.0)));
+        assert_eq!(book.best_ask(), Some((195.05, 1.0)));
+        assert_eq!(book.last_update_id(), 101);
+
+        // Already covered by the book
+        assert!(!book.apply(&update(90, 99, &[], &[])).unwrap());
+    }
+
+    #[test]
+    fn test_sequence_gap_detected() {
+        let mut book = test_book();
+        book.apply(&update(101, 105, &[], &[])).unwrap();
+
+        let result = book.apply(&update(107, 110, &[], &[]));
+        assert_eq!(
+            result,
+            Err(BookError::SequenceGap {
+                expected: 106,
+                got: 107
+            })
+        );
+        assert_eq!(book.last_update_id(), 105);
+    }
+
+    #[test]
+    fn test_depth_levels() {
+        let depth = test_book().depth(1);
+        assert_eq!(depth.bids, vec![(194.90, 5.0)]);
+        assert_eq!(depth.asks, vec![(195.10, 4.0)]);
+
+        let full = test_book().depth(10);
+        let price = full.average_price(TradeSide::BuyBase, 6.0).unwrap();
+        assert!((price - (4.0 * 195.10 + 2.0 * 195.20) / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_level_rejected() {
+        let snapshot = DepthSnapshot {
+            last_update_id: 1,
+            bids: levels(&[("abc", "1")]),
+            asks: vec![],
+        };
+        assert!(matches!(
+            OrderBook::from_snapshot(&snapshot),
+            Err(BookError::InvalidLevel(_))
+        ));
+    }
+}
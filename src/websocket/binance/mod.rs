@@ -0,0 +1,1092 @@
+mod book;
+
+use crate::config::TradingPair;
+use crate::price::{LiquidityProfile, PriceSource, PriceUpdate, Quote};
+use crate::websocket::reconnect::{ReconnectConfig, ReconnectError, ReconnectHandler};
+use crate::websocket::record::{Frame, FrameRecorder};
+use crate::websocket::state::{ConnectionState, ConnectionStateMachine, StateTransition};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static NEXT_SUB_ID: AtomicU64 = AtomicU64::new(1);
+use log::{error, info, warn};
+use thiserror::Error;
+use tokio::sync::{broadcast, watch};
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use url::Url;
+
+pub use book::{BookError, DepthSnapshot, DepthUpdate, OrderBook};
+
+/// Number of levels requested in the REST depth snapshot
+const DEPTH_SNAPSHOT_LIMIT: u32 = 1000;
+
+/// Errors that can occur with Binance WebSocket operations
+#[derive(Debug, Error)]
+#[allow(dead_code)]
+pub enum BinanceError {
+    #[error("WebSocket connection error: {0}")]
+    ConnectionError(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("URL parsing error: {0}")]
+    UrlError(#[from] url::ParseError),
+    #[error("Connection timeout: {0:?}")]
+    Timeout(Duration),
+    #[error("Reconnection failed: {0}")]
+    ReconnectFailed(#[from] ReconnectError),
+    #[error("Invalid trading pair: {0:?}")]
+    InvalidTradingPair(TradingPair),
+    #[error("Invalid price data: {0}")]
+    InvalidPrice(String),
+    #[error("Configuration error: {0}")]
+    ConfigurationError(String),
+    #[error("HTTP request error: {0}")]
+    HttpError(#[from] reqwest::Error),
+    #[error("Order book error: {0}")]
+    BookError(#[from] BookError),
+}
+
+/// Binance WebSocket subscription message for ticker streams
+#[derive(Debug, Serialize)]
+#[allow(dead_code)]
+struct SubscribeMessage {
+    method: String,
+    params: Vec<String>,
+    id: u64,
+}
+
+/// Binance WebSocket ticker data response
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct TickerData {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    price: String,
+    #[serde(rename = "E")]
+    event_time: u64,
+    #[serde(rename = "b", default)]
+    bid_price: Option<String>,
+    #[serde(rename = "B", default)]
+    bid_qty: Option<String>,
+    #[serde(rename = "a", default)]
+    ask_price: Option<String>,
+    #[serde(rename = "A", default)]
+    ask_qty: Option<String>,
+}
+
+impl TickerData {
+    /// Best bid/ask from the `b`/`B`/`a`/`A` fields, if present
+    fn quote(&self) -> Result<Option<Quote>, BinanceError> {
+        let (Some(bid), Some(ask)) = (&self.bid_price, &self.ask_price) else {
+            return Ok(None);
+        };
+        let parse = |value: &String| {
+            value
+                .parse::<f64>()
+                .map_err(|_| BinanceError::InvalidPrice(value.clone()))
+        };
+
+        let mut quote = Quote::new(parse(bid)?, parse(ask)?);
+        if let (Some(bid_qty), Some(ask_qty)) = (&self.bid_qty, &self.ask_qty) {
+            quote = quote.with_sizes(parse(bid_qty)?, parse(ask_qty)?);
+        }
+        Ok(Some(quote))
+    }
+}
+
+/// Wall-clock time of a Binance event time (`E`, milliseconds since the Unix epoch)
+///
+/// Events without one are stamped with the local time.
+fn event_time_or_now(event_time: u64) -> SystemTime {
+    if event_time == 0 {
+        return SystemTime::now();
+    }
+    UNIX_EPOCH + Duration::from_millis(event_time)
+}
+
+/// Binance WebSocket stream data wrapper
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct StreamData<T> {
+    stream: String,
+    data: T,
+}
+
+/// Configuration for Binance WebSocket client
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct BinanceConfig {
+    /// WebSocket endpoint URL
+    pub base_url: String,
+    /// Connection timeout
+    pub connection_timeout: Duration,
+    /// Reconnection configuration
+    pub reconnect_config: ReconnectConfig,
+    /// REST API base URL, used for depth snapshots
+    pub rest_url: String,
+    /// Maintain a local order book from the diff depth stream instead of the ticker
+    pub use_depth_stream: bool,
+    /// Number of book levels per side attached to each price update
+    pub depth_levels: usize,
+}
+
+impl Default for BinanceConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "wss://stream.binance.com:443/ws".to_string(),
+            connection_timeout: Duration::from_secs(10),
+            reconnect_config: ReconnectConfig::default(),
+            rest_url: "https://api.binance.com".to_string(),
+            use_depth_stream: true,
+            depth_levels: 20,
+        }
+    }
+}
+
+impl BinanceConfig {
+    /// Create new Binance configuration with custom settings
+    #[allow(dead_code)]
+    pub fn new(base_url: String, connection_timeout: Duration) -> Self {
+        Self {
+            base_url,
+            connection_timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Set reconnection configuration
+    #[allow(dead_code)]
+    pub fn with_reconnect_config(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect_config = config;
+        self
+    }
+
+    /// Set the REST API base URL
+    #[allow(dead_code)]
+    pub fn with_rest_url(mut self, rest_url: String) -> Self {
+        self.rest_url = rest_url;
+        self
+    }
+
+    /// Choose between the diff depth stream (local order book) and the ticker stream
+    #[allow(dead_code)]
+    pub fn with_depth_stream(mut self, enabled: bool) -> Self {
+        self.use_depth_stream = enabled;
+        self
+    }
+
+    /// Set the number of book levels per side attached to price updates
+    #[allow(dead_code)]
+    pub fn with_depth_levels(mut self, levels: usize) -> Self {
+        self.depth_levels = levels;
+        self
+    }
+}
+
+/// Binance WebSocket client for real-time price data
+///
+/// All trading pairs share one socket, subscribed to each pair's stream.
+#[allow(dead_code)]
+pub struct BinanceClient {
+    config: BinanceConfig,
+    trading_pairs: Vec<TradingPair>,
+    reconnect_handler: ReconnectHandler,
+    recorder: Option<FrameRecorder>,
+    state: ConnectionStateMachine,
+}
+
+/// Order book state rebuilt while replaying recorded frames
+#[derive(Debug, Default)]
+pub struct BinanceReplay {
+    books: HashMap<TradingPair, OrderBook>,
+    /// Depth events that hit a sequence gap, applied after the next recorded snapshot
+    resync: HashMap<TradingPair, DepthUpdate>,
+}
+
+impl BinanceClient {
+    /// Create new Binance WebSocket client
+    #[allow(dead_code)]
+    pub fn new(config: BinanceConfig, trading_pair: TradingPair) -> Result<Self, BinanceError> {
+        Self::for_pairs(config, vec![trading_pair])
+    }
+
+    /// Create a client streaming several trading pairs over one connection
+    pub fn for_pairs(
+        config: BinanceConfig,
+        trading_pairs: Vec<TradingPair>,
+    ) -> Result<Self, BinanceError> {
+        if trading_pairs.is_empty() {
+            return Err(BinanceError::ConfigurationError(
+                "No trading pairs to stream".to_string(),
+            ));
+        }
+        let reconnect_handler = ReconnectHandler::new(config.reconnect_config.clone())
+            .map_err(BinanceError::ConfigurationError)?;
+
+        Ok(Self {
+            config,
+            trading_pairs,
+            reconnect_handler,
+            recorder: None,
+            state: ConnectionStateMachine::new("binance"),
+        })
+    }
+
+    /// Record every received frame and depth snapshot
+    pub fn with_recorder(mut self, recorder: FrameRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Watch the connection state
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Receive every later connection state transition
+    pub fn transitions(&self) -> broadcast::Receiver<StateTransition> {
+        self.state.transitions()
+    }
+
+    /// Create client with default configuration
+    #[allow(dead_code)]
+    pub fn with_default(trading_pair: TradingPair) -> Result<Self, BinanceError> {
+        Self::new(BinanceConfig::default(), trading_pair)
+    }
+
+    /// Start the WebSocket client and stream price updates
+    #[allow(dead_code)]
+    pub async fn start<F>(&mut self, mut callback: F) -> Result<(), BinanceError>
+    where
+        F: FnMut(PriceUpdate) + Send,
+    {
+        loop {
+            self.state.transition(ConnectionState::Connecting);
+            match self.connect_and_stream(&mut callback).await {
+                Ok(()) => {
+                    // Normal disconnect, reset reconnection handler
+                    self.reconnect_handler.reset();
+                    self.state.transition(ConnectionState::Failed);
+                    break;
+                }
+                Err(e) => {
+                    error!("Binance WebSocket error: {}", e);
+
+                    // Determine if we should reconnect
+                    match self.reconnect_handler.should_reconnect() {
+                        Ok(delay) => {
+                            let attempt = self.reconnect_handler.attempt_count();
+                            warn!(
+                                "Reconnecting to Binance in {:?} (attempt {})",
+                                delay, attempt
+                            );
+                            self.state.transition(ConnectionState::Backoff { attempt });
+                            sleep(delay).await;
+                        }
+                        Err(reconnect_error) => {
+                            error!("Giving up on Binance reconnection: {}", reconnect_error);
+                            self.state.transition(ConnectionState::Failed);
+                            return Err(BinanceError::ReconnectFailed(reconnect_error));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Connect to Binance WebSocket and stream data
+    #[allow(dead_code)]
+    async fn connect_and_stream<F>(&self, callback: &mut F) -> Result<(), BinanceError>
+    where
+        F: FnMut(PriceUpdate) + Send,
+    {
+        let url = self.build_websocket_url()?;
+
+        // Connect with timeout
+        let (ws_stream, _) = timeout(self.config.connection_timeout, connect_async(&url))
+            .await
+            .map_err(|_| BinanceError::Timeout(self.config.connection_timeout))?
+            .map_err(|e| BinanceError::ConnectionError(Box::new(e)))?;
+        self.record(Frame::BinanceConnected);
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // Subscribe to the ticker or diff depth stream of every pair
+        let subscribe_msg = self.create_subscribe_message()?;
+        let msg_text = serde_json::to_string(&subscribe_msg)?;
+        write
+            .send(Message::Text(msg_text))
+            .await
+            .map_err(|e| BinanceError::ConnectionError(Box::new(e)))?;
+
+        // Depth events buffer on the socket while the snapshots are fetched
+        let mut books = HashMap::new();
+        if self.config.use_depth_stream {
+            for &pair in &self.trading_pairs {
+                books.insert(pair, self.fetch_depth_snapshot(pair).await?);
+            }
+        }
+        self.state.transition(ConnectionState::Subscribed);
+
+        // Process incoming messages
+        while let Some(message) = read.next().await {
+            match message.map_err(|e| BinanceError::ConnectionError(Box::new(e)))? {
+                Message::Text(text) => {
+                    self.record(Frame::Binance { f: text.clone() });
+                    let price_update = if self.config.use_depth_stream {
+                        self.handle_depth_message(&mut books, &text).await?
+                    } else {
+                        self.parse_ticker_message(&text).ok()
+                    };
+                    if let Some(price_update) = price_update {
+                        self.state.transition(ConnectionState::Streaming);
+                        callback(price_update);
+                    }
+                }
+                Message::Ping(payload) => {
+                    write
+                        .send(Message::Pong(payload))
+                        .await
+                        .map_err(|e| BinanceError::ConnectionError(Box::new(e)))?;
+                }
+                Message::Close(_) => {
+                    info!("Binance WebSocket connection closed");
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a REST depth snapshot and build a local order book from it
+    async fn fetch_depth_snapshot(&self, pair: TradingPair) -> Result<OrderBook, BinanceError> {
+        let symbol = Self::trading_pair_to_binance_symbol(pair);
+        let url = format!(
+            "{}/api/v3/depth",
+            self.config.rest_url.trim_end_matches('/')
+        );
+
+        let body = reqwest::Client::new()
+            .get(url)
+            .query(&[
+                ("symbol", symbol.clone()),
+                ("limit", DEPTH_SNAPSHOT_LIMIT.to_string()),
+            ])
+            .timeout(self.config.connection_timeout)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        self.record(Frame::BinanceSnapshot {
+            symbol: symbol.clone(),
+            f: body.clone(),
+        });
+        let snapshot: DepthSnapshot = serde_json::from_str(&body)?;
+
+        info!(
+            "Binance {} depth snapshot loaded (lastUpdateId {})",
+            symbol, snapshot.last_update_id
+        );
+        Ok(OrderBook::from_snapshot(&snapshot)?)
+    }
+
+    /// Apply a depth frame to its pair's local book, resyncing from a new snapshot on a gap
+    async fn handle_depth_message(
+        &self,
+        books: &mut HashMap<TradingPair, OrderBook>,
+        text: &str,
+    ) -> Result<Option<PriceUpdate>, BinanceError> {
+        // Subscription replies and other frames are not depth events
+        let Ok(update) = Self::parse_depth_message(text) else {
+            return Ok(None);
+        };
+        let Some(pair) = self.pair_for_symbol(&update.symbol) else {
+            return Ok(None);
+        };
+        let Some(book) = books.get_mut(&pair) else {
+            return Ok(None);
+        };
+
+        let changed = match book.apply(&update) {
+            Ok(changed) => changed,
+            Err(BookError::SequenceGap { expected, got }) => {
+                warn!(
+                    "Binance {} depth gap (expected {}, got {}), resyncing",
+                    update.symbol, expected, got
+                );
+                *book = self.fetch_depth_snapshot(pair).await?;
+                // Any further gap is picked up by the next event
+                book.apply(&update).unwrap_or(false)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(changed
+            .then(|| self.book_price_update(pair, book, update.event_time))
+            .flatten())
+    }
+
+    /// Process a recorded frame the way the live connection processed it
+    ///
+    /// Depth gaps wait for the snapshot that was fetched to resync, which follows in
+    /// the recording. Updates are stamped as received at `received_at`.
+    pub fn replay_frame(
+        &self,
+        state: &mut BinanceReplay,
+        frame: &Frame,
+        received_at: SystemTime,
+    ) -> Result<Option<PriceUpdate>, BinanceError> {
+        let update = self.replay_frame_update(state, frame)?;
+        Ok(update.map(|update| update.with_received_at(received_at)))
+    }
+
+    /// Price update a recorded frame produces, if any
+    fn replay_frame_update(
+        &self,
+        state: &mut BinanceReplay,
+        frame: &Frame,
+    ) -> Result<Option<PriceUpdate>, BinanceError> {
+        match frame {
+            Frame::BinanceConnected => {
+                state.books.clear();
+                state.resync.clear();
+                Ok(None)
+            }
+            Frame::BinanceSnapshot { symbol, f } => {
+                let Some(pair) = self.pair_for_symbol(symbol) else {
+                    return Ok(None);
+                };
+                let snapshot: DepthSnapshot = serde_json::from_str(f)?;
+                let mut book = OrderBook::from_snapshot(&snapshot)?;
+                let price_update = state
+                    .resync
+                    .remove(&pair)
+                    .filter(|update| book.apply(update).unwrap_or(false))
+                    .and_then(|update| self.book_price_update(pair, &book, update.event_time));
+                state.books.insert(pair, book);
+                Ok(price_update)
+            }
+            Frame::Binance { f } => {
+                let Ok(update) = Self::parse_depth_message(f) else {
+                    // Ticker stream, or a subscription reply
+                    return Ok(self.parse_ticker_message(f).ok());
+                };
+                let Some(pair) = self.pair_for_symbol(&update.symbol) else {
+                    return Ok(None);
+                };
+                let Some(book) = state.books.get_mut(&pair) else {
+                    return Ok(None);
+                };
+                match book.apply(&update) {
+                    Ok(changed) => Ok(changed
+                        .then(|| self.book_price_update(pair, book, update.event_time))
+                        .flatten()),
+                    Err(BookError::SequenceGap { .. }) => {
+                        state.books.remove(&pair);
+                        state.resync.insert(pair, update);
+                        Ok(None)
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Frame::SolanaConnected | Frame::Solana { .. } => Ok(None),
+        }
+    }
+
+    /// Append a frame to the recording, if recording
+    fn record(&self, frame: Frame) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(frame);
+        }
+    }
+
+    /// Parse a diff depth event, wrapped or direct
+    fn parse_depth_message(text: &str) -> Result<DepthUpdate, BinanceError> {
+        if let Ok(stream_data) = serde_json::from_str::<StreamData<DepthUpdate>>(text) {
+            return Ok(stream_data.data);
+        }
+        Ok(serde_json::from_str::<DepthUpdate>(text)?)
+    }
+
+    /// Price update from the local book: mid price, top-of-book quote and depth
+    fn book_price_update(
+        &self,
+        pair: TradingPair,
+        book: &OrderBook,
+        event_time: u64,
+    ) -> Option<PriceUpdate> {
+        let quote = book.quote()?;
+        Some(
+            PriceUpdate::new(PriceSource::Binance, pair, quote.mid())
+                .with_source_time(event_time_or_now(event_time))
+                .with_quote(quote)
+                .with_liquidity(LiquidityProfile::OrderBook(
+                    book.depth(self.config.depth_levels),
+                )),
+        )
+    }
+
+    /// Build WebSocket URL for the connection
+    fn build_websocket_url(&self) -> Result<Url, BinanceError> {
+        let url = Url::parse(&self.config.base_url)?;
+        Ok(url)
+    }
+
+    /// Create subscription message for each pair's ticker or diff depth stream
+    fn create_subscribe_message(&self) -> Result<SubscribeMessage, BinanceError> {
+        let mut params = Vec::new();
+        for &pair in &self.trading_pairs {
+            let symbol = Self::trading_pair_to_binance_symbol(pair).to_lowercase();
+            params.push(if self.config.use_depth_stream {
+                format!("{}@depth@100ms", symbol)
+            } else {
+                format!("{}@ticker", symbol)
+            });
+        }
+
+        Ok(SubscribeMessage {
+            method: "SUBSCRIBE".to_string(),
+            params,
+            id: NEXT_SUB_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    /// Parse ticker message and convert to PriceUpdate
+    fn parse_ticker_message(&self, text: &str) -> Result<PriceUpdate, BinanceError> {
+        // Try parsing as wrapped stream data first
+        if let Ok(stream_data) = serde_json::from_str::<StreamData<TickerData>>(text) {
+            return self.ticker_to_update(&stream_data.data);
+        }
+
+        // Try parsing as direct ticker data
+        if let Ok(ticker_data) = serde_json::from_str::<TickerData>(text) {
+            return self.ticker_to_update(&ticker_data);
+        }
+
+        Err(BinanceError::JsonError(serde_json::Error::io(
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unable to parse as either wrapped or direct ticker data",
+            ),
+        )))
+    }
+
+    /// Convert ticker data to a PriceUpdate carrying the last price and best bid/ask
+    fn ticker_to_update(&self, ticker: &TickerData) -> Result<PriceUpdate, BinanceError> {
+        let price: f64 = ticker
+            .price
+            .parse()
+            .map_err(|_| BinanceError::InvalidPrice(ticker.price.clone()))?;
+
+        let pair = self.pair_for_symbol(&ticker.symbol).ok_or_else(|| {
+            BinanceError::InvalidPrice(format!("unknown symbol {}", ticker.symbol))
+        })?;
+        let mut update = PriceUpdate::new(PriceSource::Binance, pair, price)
+            .with_source_time(event_time_or_now(ticker.event_time));
+        if let Some(quote) = ticker.quote()? {
+            update = update.with_quote(quote);
+        }
+        Ok(update)
+    }
+
+    /// Convert TradingPair to Binance symbol format
+    fn trading_pair_to_binance_symbol(pair: TradingPair) -> String {
+        pair.binance_symbol().to_string()
+    }
+
+    /// Streamed trading pair a Binance symbol belongs to
+    fn pair_for_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.trading_pairs
+            .iter()
+            .copied()
+            .find(|pair| pair.binance_symbol().eq_ignore_ascii_case(symbol))
+    }
+
+    /// Trading pairs streamed by this client
+    #[allow(dead_code)]
+    pub fn trading_pairs(&self) -> &[TradingPair] {
+        &self.trading_pairs
+    }
+
+    /// Get current reconnection attempt count
+    #[allow(dead_code)]
+    pub fn reconnect_attempts(&self) -> usize {
+        self.reconnect_handler.attempt_count()
+    }
+
+    /// Get elapsed time since first reconnection attempt
+    #[allow(dead_code)]
+    pub fn reconnect_elapsed_time(&self) -> Option<Duration> {
+        self.reconnect_handler.elapsed_time()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::TradeSide;
+    use crate::test_utils::http;
+
+    #[test]
+    fn test_binance_config_creation() {
+        let config = BinanceConfig::default();
+        assert_eq!(config.base_url, "wss://stream.binance.com:443/ws");
+        assert_eq!(config.connection_timeout, Duration::from_secs(10));
+
+        let custom_config = BinanceConfig::new(
+            "wss://testnet.binance.vision/ws".to_string(),
+            Duration::from_secs(5),
+        );
+        assert_eq!(custom_config.base_url, "wss://testnet.binance.vision/ws");
+        assert_eq!(custom_config.connection_timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_binance_client_creation() {
+        let config = BinanceConfig::default();
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT);
+        assert!(client.is_ok());
+
+        let default_client = BinanceClient::with_default(TradingPair::SOL_USDC);
+        assert!(default_client.is_ok());
+    }
+
+    #[test]
+    fn test_trading_pair_to_symbol() {
+        let config = BinanceConfig::default();
+        let client =
+            BinanceClient::for_pairs(config, vec![TradingPair::SOL_USDT, TradingPair::SOL_USDC])
+                .unwrap();
+
+        assert_eq!(
+            BinanceClient::trading_pair_to_binance_symbol(TradingPair::SOL_USDT),
+            "SOLUSDT"
+        );
+        assert_eq!(
+            client.pair_for_symbol("SOLUSDC"),
+            Some(TradingPair::SOL_USDC)
+        );
+        assert_eq!(
+            client.pair_for_symbol("solusdt"),
+            Some(TradingPair::SOL_USDT)
+        );
+        assert_eq!(client.pair_for_symbol("BTCUSDT"), None);
+
+        assert!(BinanceClient::for_pairs(BinanceConfig::default(), vec![]).is_err());
+    }
+
+    #[test]
+    fn test_subscribe_message_creation() {
+        let config = BinanceConfig::default().with_depth_stream(false);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+        let msg = client.create_subscribe_message().unwrap();
+
+        assert_eq!(msg.method, "SUBSCRIBE");
+        assert_eq!(msg.params, vec!["solusdt@ticker"]);
+        assert!(msg.id >= 1);
+
+        // The local order book is maintained from the diff depth stream by default
+        let client = BinanceClient::with_default(TradingPair::SOL_USDT).unwrap();
+        let msg = client.create_subscribe_message().unwrap();
+        assert_eq!(msg.params, vec!["solusdt@depth@100ms"]);
+
+        // Every pair is subscribed on the same socket
+        let client = BinanceClient::for_pairs(
+            BinanceConfig::default(),
+            vec![TradingPair::SOL_USDT, TradingPair::SOL_USDC],
+        )
+        .unwrap();
+        let msg = client.create_subscribe_message().unwrap();
+        assert_eq!(
+            msg.params,
+            vec!["solusdt@depth@100ms", "solusdc@depth@100ms"]
+        );
+    }
+
+    #[test]
+    fn test_ticker_routed_by_symbol() {
+        let client = BinanceClient::for_pairs(
+            BinanceConfig::default().with_depth_stream(false),
+            vec![TradingPair::SOL_USDT, TradingPair::SOL_USDC],
+        )
+        .unwrap();
+
+        let ticker = |symbol: &str| format!(r#"{{"s":"{}","c":"195.50","E":1}}"#, symbol);
+        let update = client.parse_ticker_message(&ticker("SOLUSDC")).unwrap();
+        assert_eq!(update.pair, TradingPair::SOL_USDC);
+        let update = client.parse_ticker_message(&ticker("SOLUSDT")).unwrap();
+        assert_eq!(update.pair, TradingPair::SOL_USDT);
+        assert!(client.parse_ticker_message(&ticker("BTCUSDT")).is_err());
+    }
+
+    #[test]
+    fn test_ticker_message_parsing() {
+        let client = BinanceClient::with_default(TradingPair::SOL_USDT).unwrap();
+
+        let ticker_json = r#"{
+            "stream": "solusdt@ticker",
+            "data": {
+                "s": "SOLUSDT",
+                "c": "195.50",
+                "E": 1699123456789
+            }
+        }"#;
+
+        let price_update = client.parse_ticker_message(ticker_json).unwrap();
+
+        assert_eq!(price_update.source, PriceSource::Binance);
+        assert_eq!(price_update.pair, TradingPair::SOL_USDT);
+        assert_eq!(price_update.price, 195.50);
+        // Source time is the event time; receive time is local
+        assert_eq!(
+            price_update.timestamp,
+            UNIX_EPOCH + Duration::from_millis(1699123456789)
+        );
+        assert!(price_update.received_at > price_update.timestamp);
+    }
+
+    #[test]
+    fn test_ticker_bid_ask_parsing() {
+        let client = BinanceClient::with_default(TradingPair::SOL_USDT).unwrap();
+
+        let ticker_json = r#"{
+            "s": "SOLUSDT",
+            "c": "195.50",
+            "E": 1699123456789,
+            "b": "195.49",
+            "B": "120.5",
+            "a": "195.51",
+            "A": "80.25"
+        }"#;
+
+        let quote = client
+            .parse_ticker_message(ticker_json)
+            .unwrap()
+            .quote
+            .unwrap();
+        assert_eq!(quote.bid, 195.49);
+        assert_eq!(quote.ask, 195.51);
+        assert_eq!(quote.bid_size, Some(120.5));
+        assert_eq!(quote.ask_size, Some(80.25));
+
+        let bad_bid = ticker_json.replace("195.49", "not_a_price");
+        assert!(client.parse_ticker_message(&bad_bid).is_err());
+    }
+
+    #[test]
+    fn test_url_building() {
+        let config = BinanceConfig::default();
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        let url = client.build_websocket_url().unwrap();
+        assert_eq!(url.as_str(), "wss://stream.binance.com/ws");
+    }
+
+    #[test]
+    fn test_reconnect_config_integration() {
+        let reconnect_config =
+            ReconnectConfig::new(Duration::from_millis(500), Duration::from_secs(30), 1.5);
+
+        let binance_config = BinanceConfig::default().with_reconnect_config(reconnect_config);
+
+        let client = BinanceClient::new(binance_config, TradingPair::SOL_USDT).unwrap();
+
+        assert_eq!(client.reconnect_attempts(), 0);
+        assert!(client.reconnect_elapsed_time().is_none());
+    }
+
+    /// States the client moves through until it stops
+    async fn run_until_stopped(
+        mut client: BinanceClient,
+    ) -> (
+        Result<(), BinanceError>,
+        Vec<(ConnectionState, ConnectionState)>,
+    ) {
+        let mut transitions = client.transitions();
+        let result = client.start(|_| {}).await;
+        let mut seen = Vec::new();
+        while let Ok(transition) = transitions.try_recv() {
+            seen.push((transition.from, transition.to));
+        }
+        (result, seen)
+    }
+
+    #[tokio::test]
+    async fn test_state_machine_fails_after_giving_up() {
+        // A port nothing listens on refuses the connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        drop(listener);
+
+        let reconnect =
+            ReconnectConfig::new(Duration::from_millis(1), Duration::from_millis(1), 2.0)
+                .with_max_attempts(Some(1))
+                .with_jitter(false);
+        let config = BinanceConfig::new(url, Duration::from_secs(1))
+            .with_depth_stream(false)
+            .with_reconnect_config(reconnect);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+        assert_eq!(*client.state().borrow(), ConnectionState::Connecting);
+
+        let (result, transitions) = run_until_stopped(client).await;
+        assert!(matches!(result, Err(BinanceError::ReconnectFailed(_))));
+        assert_eq!(
+            transitions,
+            [
+                (
+                    ConnectionState::Connecting,
+                    ConnectionState::Backoff { attempt: 1 }
+                ),
+                (
+                    ConnectionState::Backoff { attempt: 1 },
+                    ConnectionState::Connecting
+                ),
+                (ConnectionState::Connecting, ConnectionState::Failed),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_state_machine_streams_after_first_update() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let ticker = r#"{"stream":"solusdt@ticker","data":{"s":"SOLUSDT","c":"150.25","E":1}}"#;
+            ws.send(Message::Text(ticker.to_string())).await.unwrap();
+            ws.close(None).await.ok();
+        });
+
+        let config = BinanceConfig::new(url, Duration::from_secs(1)).with_depth_stream(false);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        let (result, transitions) = run_until_stopped(client).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            transitions,
+            [
+                (ConnectionState::Connecting, ConnectionState::Subscribed),
+                (ConnectionState::Subscribed, ConnectionState::Streaming),
+                (ConnectionState::Streaming, ConnectionState::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_price_format() {
+        let client = BinanceClient::with_default(TradingPair::SOL_USDT).unwrap();
+
+        let invalid_json = r#"{
+            "stream": "solusdt@ticker",
+            "data": {
+                "s": "SOLUSDT",
+                "c": "invalid_price",
+                "E": 1699123456789
+            }
+        }"#;
+
+        let result = client.parse_ticker_message(invalid_json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_direct_ticker_message_parsing() {
+        let client = BinanceClient::with_default(TradingPair::SOL_USDT).unwrap();
+
+        // Test direct ticker payload (no wrapper)
+        let direct_ticker_json = r#"{
+            "s": "SOLUSDT",
+            "c": "189.75",
+            "E": 1699123456789
+        }"#;
+
+        // This should now succeed as we support direct ticker format
+        let result = client.parse_ticker_message(direct_ticker_json);
+        assert!(result.is_ok());
+        let price_update = result.unwrap();
+        assert_eq!(price_update.source, PriceSource::Binance);
+        assert_eq!(price_update.pair, TradingPair::SOL_USDT);
+        assert_eq!(price_update.price, 189.75);
+
+        // Test the expected wrapped format
+        let wrapped_ticker_json = r#"{
+            "stream": "solusdt@ticker",
+            "data": {
+                "s": "SOLUSDT",
+                "c": "189.75",
+                "E": 1699123456789
+            }
+        }"#;
+
+        let price_update = client.parse_ticker_message(wrapped_ticker_json).unwrap();
+        assert_eq!(price_update.source, PriceSource::Binance);
+        assert_eq!(price_update.pair, TradingPair::SOL_USDT);
+        assert_eq!(price_update.price, 189.75);
+    }
+
+    fn depth_frame(first: u64, last: u64, bids: &str, asks: &str) -> String {
+        format!(
+            r#"{{"stream":"solusdt@depth@100ms","data":{{"e":"depthUpdate","E":1699123456789,"s":"SOLUSDT","U":{},"u":{},"b":{},"a":{}}}}}"#,
+            first, last, bids, asks
+        )
+    }
+
+    #[tokio::test]
+    async fn test_depth_snapshot_and_resync() {
+        let snapshot = |id: u64, bid: &str| {
+            format!(
+                r#"{{"lastUpdateId":{},"bids":[["{}","5"],["194.00","20"]],"asks":[["195.10","4"],["196.00","10"]]}}"#,
+                id, bid
+            )
+        };
+        let (rest_url, requests) = http::serve(vec![
+            (200, snapshot(100, "194.90")),
+            (200, snapshot(200, "194.95")),
+        ])
+        .await;
+
+        let config = BinanceConfig::default().with_rest_url(rest_url);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+        let book = client
+            .fetch_depth_snapshot(TradingPair::SOL_USDT)
+            .await
+            .unwrap();
+        assert_eq!(book.last_update_id(), 100);
+        let mut books = HashMap::from([(TradingPair::SOL_USDT, book)]);
+
+        // Subscription replies are skipped
+        let reply = r#"{"result":null,"id":1}"#;
+        assert!(client
+            .handle_depth_message(&mut books, reply)
+            .await
+            .unwrap()
+            .is_none());
+
+        // The first event straddles the snapshot id
+        let frame = depth_frame(99, 102, r#"[["194.90","0"]]"#, "[]");
+        let update = client
+            .handle_depth_message(&mut books, &frame)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.source, PriceSource::Binance);
+        let quote = update.quote.unwrap();
+        assert_eq!((quote.bid, quote.ask), (194.00, 195.10));
+        assert!((update.price - 194.55).abs() < 1e-9);
+
+        // Selling 10 SOL walks the book instead of using the last price
+        let liquidity = update.liquidity.unwrap();
+        let price = liquidity.average_price(TradeSide::SellBase, 10.0).unwrap();
+        assert_eq!(price, 194.00);
+
+        // A gap triggers a new snapshot
+        let frame = depth_frame(150, 201, "[]", r#"[["195.10","0"]]"#);
+        let update = client
+            .handle_depth_message(&mut books, &frame)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(books[&TradingPair::SOL_USDT].last_update_id(), 201);
+        let quote = update.quote.unwrap();
+        assert_eq!((quote.bid, quote.ask), (194.95, 196.00));
+
+        let requests = requests.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0]
+            .request_line
+            .starts_with("GET /api/v3/depth?symbol=SOLUSDT&limit=1000 "));
+    }
+
+    #[tokio::test]
+    async fn test_replay_recorded_frames() {
+        let snapshot = |id: u64, bid: &str| {
+            format!(
+                r#"{{"lastUpdateId":{},"bids":[["{}","5"],["194.00","20"]],"asks":[["195.10","4"],["196.00","10"]]}}"#,
+                id, bid
+            )
+        };
+        let (rest_url, _requests) = http::serve(vec![(200, snapshot(100, "194.90"))]).await;
+
+        // Snapshots are recorded as fetched
+        let path = std::env::temp_dir().join(format!("arb-binance-{}.ndjson", std::process::id()));
+        let recorder = FrameRecorder::create(&path).unwrap();
+        let config = BinanceConfig::default().with_rest_url(rest_url);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT)
+            .unwrap()
+            .with_recorder(recorder.clone());
+        client
+            .fetch_depth_snapshot(TradingPair::SOL_USDT)
+            .await
+            .unwrap();
+        recorder.flush().unwrap();
+        let recorded: Vec<_> = crate::websocket::record::read_frames(&path)
+            .unwrap()
+            .map(|frame| frame.unwrap().frame)
+            .collect();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(
+            recorded,
+            vec![Frame::BinanceSnapshot {
+                symbol: "SOLUSDT".to_string(),
+                f: snapshot(100, "194.90"),
+            }]
+        );
+
+        let mut state = BinanceReplay::default();
+        let received_at = UNIX_EPOCH + Duration::from_millis(1699123456900);
+        let mut replay = |frame: Frame| {
+            client
+                .replay_frame(&mut state, &frame, received_at)
+                .unwrap()
+        };
+        let binance = |f: String| Frame::Binance { f };
+
+        assert!(replay(Frame::BinanceConnected).is_none());
+        assert!(replay(recorded[0].clone()).is_none());
+        assert!(replay(binance(r#"{"result":null,"id":1}"#.to_string())).is_none());
+
+        let update = replay(binance(depth_frame(99, 102, r#"[["194.90","0"]]"#, "[]"))).unwrap();
+        let quote = update.quote.unwrap();
+        assert_eq!((quote.bid, quote.ask), (194.00, 195.10));
+        assert_eq!(update.received_at, received_at);
+        assert_eq!(update.clock_skew_ms(), 111.0);
+
+        // A gap waits for the resync snapshot that follows it in the recording
+        let gap = depth_frame(150, 201, "[]", r#"[["195.10","0"]]"#);
+        assert!(replay(binance(gap)).is_none());
+        let update = replay(Frame::BinanceSnapshot {
+            symbol: "SOLUSDT".to_string(),
+            f: snapshot(200, "194.95"),
+        })
+        .unwrap();
+        let quote = update.quote.unwrap();
+        assert_eq!((quote.bid, quote.ask), (194.95, 196.00));
+
+        // Ticker frames go through the ticker parser
+        let ticker = r#"{"stream":"solusdt@ticker","data":{"s":"SOLUSDT","c":"189.75","E":1}}"#;
+        assert_eq!(replay(binance(ticker.to_string())).unwrap().price, 189.75);
+    }
+
+    #[tokio::test]
+    async fn test_depth_snapshot_http_error() {
+        let (rest_url, _requests) = http::serve(vec![(503, "{}".to_string())]).await;
+        let config = BinanceConfig::default().with_rest_url(rest_url);
+        let client = BinanceClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        assert!(matches!(
+            client.fetch_depth_snapshot(TradingPair::SOL_USDT).await,
+            Err(BinanceError::HttpError(_))
+        ));
+    }
+}
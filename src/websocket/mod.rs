@@ -1,25 +1,38 @@
 pub mod binance;
 pub mod reconnect;
+pub mod record;
 pub mod solana;
+pub mod state;
 
+use crate::config::reload::LiveSettings;
 use crate::config::{Config, TradingPair};
 use crate::performance::metrics::MetricsCollector;
-use crate::price::PriceCache;
+use crate::price::{PriceCache, PriceUpdate};
+use record::{read_frames, Frame, FrameRecorder, RecordError};
+use state::{ConnectionState, StateTransition};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
+use tokio::sync::{broadcast, watch};
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Price caches keyed by trading pair
+pub type PriceCaches = HashMap<TradingPair, Arc<PriceCache>>;
 
 /// Type alias for complex startup return type
 type StartupResult = (
-    Arc<PriceCache>,
+    PriceCaches,
     JoinHandle<Result<(), BinanceError>>,
     JoinHandle<Result<(), SolanaError>>,
 );
 
-pub use binance::{BinanceClient, BinanceConfig, BinanceError};
+pub use binance::{BinanceClient, BinanceConfig, BinanceError, BinanceReplay};
 // ReconnectHandler is available but not currently used in public API
 #[allow(unused_imports)]
-pub use solana::{SolanaClient, SolanaConfig, SolanaError};
+pub use solana::{SolanaClient, SolanaConfig, SolanaError, SolanaReplay};
 
 /// Errors that can occur in the WebSocket connection manager
 #[derive(Debug, Error)]
@@ -33,16 +46,22 @@ pub enum ConnectionManagerError {
     JoinError(#[from] tokio::task::JoinError),
     #[error("All connections failed")]
     AllConnectionsFailed,
+    #[error("Recording error: {0}")]
+    RecordError(#[from] RecordError),
 }
 
 /// WebSocket connection manager that coordinates multiple price sources
+///
+/// One Binance combined stream and one Solana socket carry every configured pair;
+/// updates are routed into a price cache per pair.
 #[allow(dead_code)]
 pub struct ConnectionManager {
     binance_client: BinanceClient,
     solana_client: SolanaClient,
-    price_cache: Arc<PriceCache>,
-    trading_pair: TradingPair,
+    price_caches: PriceCaches,
+    trading_pairs: Vec<TradingPair>,
     metrics: Option<Arc<MetricsCollector>>,
+    recorder: Option<FrameRecorder>,
 }
 
 impl ConnectionManager {
@@ -50,35 +69,67 @@ impl ConnectionManager {
     #[allow(dead_code)]
     pub fn new(config: &Config) -> Result<Self, ConnectionManagerError> {
         // Create Binance client with default configuration
-        log::info!("Creating Binance client for pair: {:?}", config.pair);
-        let binance_client = BinanceClient::with_default(config.pair)?;
+        log::info!("Creating Binance client for pairs: {:?}", config.pairs);
+        let binance_config =
+            BinanceConfig::default().with_reconnect_config(config.reconnect.clone());
+        let binance_client = BinanceClient::for_pairs(binance_config, config.pairs.clone())?;
         log::info!("Binance client created successfully");
 
         // Create Solana client from RPC providers in config with price bounds
-        let solana_client = SolanaClient::from_providers_with_bounds(
-            config.rpc_providers.clone(),
-            config.pair,
-            config.price_bounds,
-        )?;
+        let solana_config =
+            SolanaConfig::new(config.rpc_providers.clone(), Duration::from_secs(10))
+                .with_price_bounds(config.price_bounds)
+                .with_reconnect_config(config.reconnect.clone());
+        let solana_client = SolanaClient::for_pairs(solana_config, config.pairs.clone())?;
+
+        match &config.record {
+            Some(path) => {
+                let recorder = FrameRecorder::create(path)?;
+                log::info!("Recording raw frames to {}", path.display());
+                let binance_client = binance_client.with_recorder(recorder.clone());
+                let solana_client = solana_client.with_recorder(recorder.clone());
+                let mut manager = Self::from_clients(config, binance_client, solana_client);
+                manager.recorder = Some(recorder);
+                Ok(manager)
+            }
+            None => Ok(Self::from_clients(config, binance_client, solana_client)),
+        }
+    }
 
-        let price_cache = Arc::new(PriceCache::new());
+    fn from_clients(
+        config: &Config,
+        binance_client: BinanceClient,
+        solana_client: SolanaClient,
+    ) -> Self {
+        let price_caches = config
+            .pairs
+            .iter()
+            .map(|&pair| (pair, Arc::new(PriceCache::new())))
+            .collect();
 
-        Ok(Self {
+        Self {
             binance_client,
             solana_client,
-            price_cache,
-            trading_pair: config.pair,
+            price_caches,
+            trading_pairs: config.pairs.clone(),
             metrics: None,
-        })
+            recorder: None,
+        }
     }
 
     /// Start all WebSocket connections and return price cache and shutdown handles
     #[allow(dead_code)]
     pub fn start_with_handles(mut self) -> StartupResult {
-        let price_cache = Arc::clone(&self.price_cache);
+        let price_caches = self.price_caches.clone();
+
+        // Derive connection metrics from each client's state transitions
+        if let Some(metrics) = &self.metrics {
+            spawn_transition_recorder(self.binance_client.transitions(), Arc::clone(metrics));
+            spawn_transition_recorder(self.solana_client.transitions(), Arc::clone(metrics));
+        }
 
         // Start Binance connection
-        let binance_cache = Arc::clone(&price_cache);
+        let binance_caches = price_caches.clone();
         let binance_metrics = self.metrics.clone();
         log::info!("Starting Binance WebSocket client...");
         let binance_handle: JoinHandle<Result<(), BinanceError>> = tokio::spawn(async move {
@@ -88,9 +139,9 @@ impl ConnectionManager {
                 .start(move |price_update| {
                     log::debug!("Received Binance price update: {:?}", price_update);
                     if let Some(metrics) = &binance_metrics {
-                        metrics.record_binance_message();
+                        record_update_metrics(metrics, &price_update);
                     }
-                    binance_cache.update(&price_update);
+                    route_update(&binance_caches, &price_update);
                 })
                 .await;
 
@@ -101,26 +152,97 @@ impl ConnectionManager {
         });
 
         // Start Solana connection
-        let solana_cache = Arc::clone(&price_cache);
+        let solana_caches = price_caches.clone();
         let solana_metrics = self.metrics.clone();
         let solana_handle: JoinHandle<Result<(), SolanaError>> = tokio::spawn(async move {
             self.solana_client
                 .start(move |price_update| {
                     if let Some(metrics) = &solana_metrics {
-                        metrics.record_solana_message();
+                        record_update_metrics(metrics, &price_update);
                     }
-                    solana_cache.update(&price_update);
+                    route_update(&solana_caches, &price_update);
                 })
                 .await
         });
 
-        (price_cache, binance_handle, solana_handle)
+        (price_caches, binance_handle, solana_handle)
+    }
+
+    /// Feed a recording through the clients' parsers instead of connecting
+    ///
+    /// Frames are delivered at their recorded spacing divided by `speed`, and updates are
+    /// moved to the replay's wall clock keeping their recorded clock skew. The handle
+    /// resolves to the number of frames replayed once the recording is exhausted.
+    pub fn start_replay(
+        self,
+        path: PathBuf,
+        speed: f64,
+    ) -> (PriceCaches, JoinHandle<Result<usize, RecordError>>) {
+        let price_caches = self.price_caches.clone();
+        let caches = price_caches.clone();
+        let metrics = self.metrics.clone();
+        let mut replayer = self.into_replayer();
+
+        let handle = tokio::spawn(async move {
+            let start = Instant::now();
+            let mut first_frame_time = None;
+            let mut replayed = 0;
+
+            log::info!("Replaying {} at {}x", path.display(), speed);
+            for recorded in read_frames(&path)? {
+                let recorded = recorded?;
+                let first = *first_frame_time.get_or_insert(recorded.t);
+                let offset = Duration::from_micros(recorded.t.saturating_sub(first));
+                tokio::time::sleep_until(start + offset.div_f64(speed)).await;
+
+                match replayer.replay(&recorded.frame, recorded.received_at()) {
+                    Ok(Some(mut price_update)) => {
+                        price_update.rebase_to(SystemTime::now());
+                        if let Some(metrics) = &metrics {
+                            record_update_metrics(metrics, &price_update);
+                        }
+                        route_update(&caches, &price_update);
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::debug!("Ignoring replayed frame: {}", e),
+                }
+                replayed += 1;
+            }
+
+            log::info!("Replay finished after {} frames", replayed);
+            Ok(replayed)
+        });
+
+        (price_caches, handle)
+    }
+
+    /// Parsers of both clients, for processing recorded frames without connecting
+    pub fn into_replayer(self) -> FrameReplayer {
+        FrameReplayer {
+            binance_client: self.binance_client,
+            solana_client: self.solana_client,
+            binance_state: BinanceReplay::default(),
+            solana_state: SolanaReplay::default(),
+        }
+    }
+
+    /// Watch the connection state of each client, by source name
+    pub fn connection_states(&self) -> Vec<(&'static str, watch::Receiver<ConnectionState>)> {
+        vec![
+            ("binance", self.binance_client.state()),
+            ("solana", self.solana_client.state()),
+        ]
+    }
+
+    /// Recorder writing raw frames, when recording
+    pub fn recorder(&self) -> Option<FrameRecorder> {
+        self.recorder.clone()
     }
 
-    /// Start all WebSocket connections and return price cache (legacy method)
+    /// Start all WebSocket connections and return price caches (legacy method)
     #[allow(dead_code)]
-    pub async fn start(self) -> Result<Arc<PriceCache>, ConnectionManagerError> {
-        let (price_cache, binance_handle, solana_handle) = self.start_with_handles();
+    pub async fn start(self) -> Result<PriceCaches, ConnectionManagerError> {
+        let (price_caches, binance_handle, solana_handle) = self.start_with_handles();
 
         // Monitor connections
         tokio::spawn(async move {
@@ -146,7 +268,7 @@ impl ConnectionManager {
             }
         });
 
-        Ok(price_cache)
+        Ok(price_caches)
     }
 
     /// Create connection manager with custom WebSocket configurations
@@ -156,23 +278,16 @@ impl ConnectionManager {
         binance_config: BinanceConfig,
         solana_config: SolanaConfig,
     ) -> Result<Self, ConnectionManagerError> {
-        let binance_client = BinanceClient::new(binance_config, config.pair)?;
-        let solana_client = SolanaClient::new(solana_config, config.pair)?;
-        let price_cache = Arc::new(PriceCache::new());
+        let binance_client = BinanceClient::for_pairs(binance_config, config.pairs.clone())?;
+        let solana_client = SolanaClient::for_pairs(solana_config, config.pairs.clone())?;
 
-        Ok(Self {
-            binance_client,
-            solana_client,
-            price_cache,
-            trading_pair: config.pair,
-            metrics: None,
-        })
+        Ok(Self::from_clients(config, binance_client, solana_client))
     }
 
-    /// Get trading pair
+    /// Get the monitored trading pairs
     #[allow(dead_code)]
-    pub fn trading_pair(&self) -> TradingPair {
-        self.trading_pair
+    pub fn trading_pairs(&self) -> &[TradingPair] {
+        &self.trading_pairs
     }
 
     /// Set metrics collector for performance monitoring
@@ -182,18 +297,94 @@ impl ConnectionManager {
         self
     }
 
-    /// Get price cache (for testing)
+    /// Check Solana prices against the bounds of the reloadable settings
+    pub fn with_live_settings(mut self, settings: watch::Receiver<LiveSettings>) -> Self {
+        self.solana_client = self.solana_client.with_live_settings(settings);
+        self
+    }
+
+    /// Get the price cache for a pair (for testing)
     #[allow(dead_code)]
-    pub fn price_cache(&self) -> Arc<PriceCache> {
-        Arc::clone(&self.price_cache)
+    pub fn price_cache(&self, pair: TradingPair) -> Option<Arc<PriceCache>> {
+        self.price_caches.get(&pair).cloned()
+    }
+}
+
+/// Feeds recorded frames through the client that received them
+pub struct FrameReplayer {
+    binance_client: BinanceClient,
+    solana_client: SolanaClient,
+    binance_state: BinanceReplay,
+    solana_state: SolanaReplay,
+}
+
+impl FrameReplayer {
+    /// Process one recorded frame received at `received_at`, returning the price update
+    /// it produced, if any
+    pub fn replay(
+        &mut self,
+        frame: &Frame,
+        received_at: SystemTime,
+    ) -> Result<Option<PriceUpdate>, ConnectionManagerError> {
+        match frame {
+            Frame::BinanceConnected | Frame::Binance { .. } | Frame::BinanceSnapshot { .. } => {
+                Ok(self
+                    .binance_client
+                    .replay_frame(&mut self.binance_state, frame, received_at)?)
+            }
+            Frame::SolanaConnected | Frame::Solana { .. } => {
+                Ok(self
+                    .solana_client
+                    .replay_frame(&mut self.solana_state, frame, received_at)?)
+            }
+        }
+    }
+}
+
+/// Count a received update and its clock skew against its venue
+fn record_update_metrics(metrics: &MetricsCollector, update: &PriceUpdate) {
+    if update.source.is_cex() {
+        metrics.record_binance_message();
+        metrics.record_binance_clock_skew(update.clock_skew_ms());
+    } else {
+        metrics.record_solana_message();
+        metrics.record_solana_clock_skew(update.clock_skew_ms());
+    }
+}
+
+/// Record connection state transitions until the client is dropped
+fn spawn_transition_recorder(
+    mut transitions: broadcast::Receiver<StateTransition>,
+    metrics: Arc<MetricsCollector>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match transitions.recv().await {
+                Ok(transition) => metrics.record_connection_transition(&transition),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("Missed {} connection state transitions", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Store an update in the cache of its trading pair
+fn route_update(caches: &PriceCaches, update: &PriceUpdate) {
+    match caches.get(&update.pair) {
+        Some(cache) => cache.update(update),
+        None => log::debug!("Dropping update for unmonitored pair {:?}", update.pair),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::TradingPair;
+    use crate::config::{RawConfig, TradingPair};
+    use crate::price::PriceSource;
     use crate::test_utils::config::create_test_config;
+    use clap::Parser;
 
     #[test]
     fn test_connection_manager_creation() {
@@ -202,7 +393,7 @@ mod tests {
         assert!(manager.is_ok());
 
         let manager = manager.unwrap();
-        assert_eq!(manager.trading_pair(), TradingPair::SolUsdt);
+        assert_eq!(manager.trading_pairs(), &[TradingPair::SOL_USDT]);
     }
 
     #[test]
@@ -220,9 +411,90 @@ mod tests {
     fn test_price_cache_access() {
         let config = create_test_config();
         let manager = ConnectionManager::new(&config).unwrap();
-        let cache = manager.price_cache();
+        let cache = manager.price_cache(TradingPair::SOL_USDT).unwrap();
 
         // Cache should be empty initially
         assert!(cache.get_both_prices().is_none());
+        assert!(manager.price_cache(TradingPair::SOL_USDC).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_routes_recorded_frames() {
+        let path = std::env::temp_dir().join(format!("arb-replay-{}.ndjson", std::process::id()));
+        let ticker = |price: &str| Frame::Binance {
+            f: format!(
+                r#"{{"stream":"solusdt@ticker","data":{{"s":"SOLUSDT","c":"{}","E":1}}}}"#,
+                price
+            ),
+        };
+        let lines: Vec<String> = [
+            (1_000_000, Frame::BinanceConnected),
+            (1_000_000, Frame::SolanaConnected),
+            (1_100_000, ticker("190.5")),
+            (
+                1_150_000,
+                Frame::Solana {
+                    f: "{}".to_string(),
+                },
+            ),
+            (1_200_000, ticker("191.25")),
+        ]
+        .into_iter()
+        .map(|(t, frame)| serde_json::to_string(&record::RecordedFrame { t, frame }).unwrap())
+        .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let config = create_test_config();
+        let manager = ConnectionManager::new(&config).unwrap();
+        let started = Instant::now();
+        let (caches, handle) = manager.start_replay(path.clone(), 10.0);
+        assert_eq!(handle.await.unwrap().unwrap(), 5);
+        std::fs::remove_file(&path).ok();
+
+        // 200ms of recording at 10x
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        let price = caches[&TradingPair::SOL_USDT]
+            .get_price(PriceSource::Binance)
+            .unwrap();
+        assert_eq!(price.price, 191.25);
+    }
+
+    #[test]
+    fn test_record_option_creates_recording() {
+        let path = std::env::temp_dir().join(format!("arb-record-{}.ndjson", std::process::id()));
+        let mut config = create_test_config();
+        config.record = Some(path.clone());
+        let manager = ConnectionManager::new(&config).unwrap();
+        assert!(manager.recorder().is_some());
+        assert!(path.exists());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_updates_routed_to_pair_cache() {
+        // Repeated pairs are monitored once
+        let raw = RawConfig::try_parse_from([
+            "solana-arbitrage-watcher",
+            "--pair",
+            "sol-usdt,sol-usdc,sol-usdt",
+        ])
+        .unwrap();
+        let config = Config::new(&raw).unwrap();
+        let manager = ConnectionManager::new(&config).unwrap();
+        assert_eq!(
+            manager.trading_pairs(),
+            &[TradingPair::SOL_USDT, TradingPair::SOL_USDC]
+        );
+
+        let caches = manager.price_caches.clone();
+        let update = PriceUpdate::new(PriceSource::Binance, TradingPair::SOL_USDC, 150.0);
+        route_update(&caches, &update);
+
+        assert!(caches[&TradingPair::SOL_USDC]
+            .get_price(PriceSource::Binance)
+            .is_some());
+        assert!(caches[&TradingPair::SOL_USDT]
+            .get_price(PriceSource::Binance)
+            .is_none());
     }
 }
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// How often buffered frames are written out while recording
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Errors reading or writing a frame recording
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("Recording I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid recorded frame on line {line}: {source}")]
+    Parse {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+/// A raw frame as received, or a connection event that resets parser state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "s", rename_all = "snake_case")]
+pub enum Frame {
+    /// New Binance connection; local books are rebuilt from fresh snapshots
+    BinanceConnected,
+    /// Binance WebSocket text frame
+    Binance { f: String },
+    /// Binance REST depth snapshot body for `symbol`
+    BinanceSnapshot { symbol: String, f: String },
+    /// New Solana connection; subscriptions are renewed
+    SolanaConnected,
+    /// Solana WebSocket text frame
+    Solana { f: String },
+}
+
+/// One line of a recording: a frame and its receive time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Receive time in microseconds since the Unix epoch
+    pub t: u64,
+    #[serde(flatten)]
+    pub frame: Frame,
+}
+
+impl RecordedFrame {
+    /// Receive time as a wall-clock time
+    pub fn received_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_micros(self.t)
+    }
+}
+
+struct RecorderState {
+    writer: BufWriter<File>,
+    last_flush: Instant,
+}
+
+/// Appends received frames to a newline-delimited JSON log
+///
+/// Clones share the same file, so both clients can record into one log.
+#[derive(Clone)]
+pub struct FrameRecorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl std::fmt::Debug for FrameRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameRecorder").finish_non_exhaustive()
+    }
+}
+
+impl FrameRecorder {
+    /// Create (or truncate) a recording at `path`
+    pub fn create(path: &Path) -> Result<Self, RecordError> {
+        let file = File::create(path)?;
+        Ok(Self {
+            state: Arc::new(Mutex::new(RecorderState {
+                writer: BufWriter::new(file),
+                last_flush: Instant::now(),
+            })),
+        })
+    }
+
+    /// Record a frame received now
+    ///
+    /// Write errors are logged; recording never interrupts streaming.
+    pub fn record(&self, frame: Frame) {
+        let t = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let line = match serde_json::to_string(&RecordedFrame { t, frame }) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Cannot encode recorded frame: {}", e);
+                return;
+            }
+        };
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut result = writeln!(state.writer, "{}", line);
+        if result.is_ok() && state.last_flush.elapsed() >= FLUSH_INTERVAL {
+            state.last_flush = Instant::now();
+            result = state.writer.flush();
+        }
+        if let Err(e) = result {
+            log::warn!("Cannot write recorded frame: {}", e);
+        }
+    }
+
+    /// Write out buffered frames
+    pub fn flush(&self) -> Result<(), RecordError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.last_flush = Instant::now();
+        Ok(state.writer.flush()?)
+    }
+}
+
+/// Read the frames of a recording in order
+pub fn read_frames(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<RecordedFrame, RecordError>>, RecordError> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(index, line)| {
+            serde_json::from_str(&line?).map_err(|source| RecordError::Parse {
+                line: index + 1,
+                source,
+            })
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_frames() {
+        let path = std::env::temp_dir().join(format!("arb-frames-{}.ndjson", std::process::id()));
+        let recorder = FrameRecorder::create(&path).unwrap();
+        let frames = vec![
+            Frame::BinanceConnected,
+            Frame::BinanceSnapshot {
+                symbol: "SOLUSDT".to_string(),
+                f: r#"{"lastUpdateId":1,"bids":[],"asks":[]}"#.to_string(),
+            },
+            Frame::Solana {
+                f: "{\"jsonrpc\":\"2.0\",\n\"result\":7,\"id\":1}".to_string(),
+            },
+        ];
+        for frame in &frames {
+            recorder.clone().record(frame.clone());
+        }
+        recorder.flush().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+        assert!(contents.starts_with("{\"t\":"));
+        assert!(contents.contains("\"s\":\"binance_snapshot\""));
+
+        let read: Vec<RecordedFrame> = read_frames(&path)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            read.iter().map(|r| r.frame.clone()).collect::<Vec<_>>(),
+            frames
+        );
+        assert!(read.windows(2).all(|w| w[0].t <= w[1].t));
+
+        std::fs::write(
+            &path,
+            "{\"t\":1,\"s\":\"solana\",\"f\":\"{}\"}\n\nnot json\n",
+        )
+        .unwrap();
+        let errors: Vec<_> = read_frames(&path).unwrap().collect();
+        assert!(errors[0].is_ok());
+        assert!(matches!(errors[1], Err(RecordError::Parse { line: 3, .. })));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,303 @@
+use super::{base_is_first_mint, price_from_sqrt_price_x64, SolanaError};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Raydium CLMM program id
+pub const CLMM_PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+
+/// Number of ticks stored in one tick array account
+pub const TICKS_PER_ARRAY: i32 = 60;
+
+/// Status bit that disables swaps when set
+const STATUS_SWAP_DISABLED: u8 = 1 << 4;
+
+/// Leading fields of a Raydium CLMM `PoolState` account (1544 bytes on-chain)
+///
+/// Only the fields up to `status` are decoded; rewards, the tick array bitmap
+/// and fee totals that follow are not needed for pricing.
+#[derive(Debug, Clone, Default, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct ClmmPoolState {
+    /// Anchor account discriminator
+    pub discriminator: [u8; 8],
+    /// PDA bump
+    pub bump: [u8; 1],
+    /// AMM config account
+    pub amm_config: [u8; 32],
+    /// Pool creator
+    pub owner: [u8; 32],
+    /// Token 0 mint (32 bytes)
+    pub token_mint_0: [u8; 32],
+    /// Token 1 mint (32 bytes)
+    pub token_mint_1: [u8; 32],
+    /// Token 0 vault (32 bytes)
+    pub token_vault_0: [u8; 32],
+    /// Token 1 vault (32 bytes)
+    pub token_vault_1: [u8; 32],
+    /// Observation account
+    pub observation_key: [u8; 32],
+    /// Token 0 decimals
+    pub mint_decimals_0: u8,
+    /// Token 1 decimals
+    pub mint_decimals_1: u8,
+    /// Tick spacing
+    pub tick_spacing: u16,
+    /// Liquidity currently in range
+    pub liquidity: u128,
+    /// Q64.64 square root of the current price (token1 per token0)
+    pub sqrt_price_x64: u128,
+    /// Current tick index
+    pub tick_current: i32,
+    /// Padding
+    pub padding3: u16,
+    /// Padding
+    pub padding4: u16,
+    /// Q64.64 global fee growth for token 0
+    pub fee_growth_global_0_x64: u128,
+    /// Q64.64 global fee growth for token 1
+    pub fee_growth_global_1_x64: u128,
+    /// Protocol fees owed in token 0
+    pub protocol_fees_token_0: u64,
+    /// Protocol fees owed in token 1
+    pub protocol_fees_token_1: u64,
+    /// Cumulative token 0 swapped in
+    pub swap_in_amount_token_0: u128,
+    /// Cumulative token 1 swapped out
+    pub swap_out_amount_token_1: u128,
+    /// Cumulative token 1 swapped in
+    pub swap_in_amount_token_1: u128,
+    /// Cumulative token 0 swapped out
+    pub swap_out_amount_token_0: u128,
+    /// Bit flags disabling pool operations (bit 4 = swap)
+    pub status: u8,
+}
+
+impl ClmmPoolState {
+    /// Size of the decoded leading fields in bytes
+    pub const HEADER_LEN: usize = 390;
+
+    /// Decode the pool state from raw account data
+    pub fn decode(data: &[u8]) -> Result<Self, SolanaError> {
+        let bytes = data.get(..Self::HEADER_LEN).ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for CLMM pool".to_string())
+        })?;
+        Self::try_from_slice(bytes)
+            .map_err(|e| SolanaError::PoolParsingError(format!("CLMM pool decode error: {}", e)))
+    }
+
+    /// Check whether swaps are enabled on this pool
+    pub fn is_swap_enabled(&self) -> bool {
+        self.status & STATUS_SWAP_DISABLED == 0
+    }
+
+    /// Whether the pair's base token is token 0, given the pair's base and quote mints
+    pub fn base_is_token0(&self, base_mint: &str, quote_mint: &str) -> Result<bool, SolanaError> {
+        base_is_first_mint(
+            "CLMM pool",
+            &self.token_mint_0,
+            &self.token_mint_1,
+            base_mint,
+            quote_mint,
+        )
+    }
+
+    /// Price of `base_mint` in `quote_mint`, adjusted for decimals
+    pub fn price_of(&self, base_mint: &str, quote_mint: &str) -> Result<f64, SolanaError> {
+        let price_0_in_1 = price_from_sqrt_price_x64(
+            self.sqrt_price_x64,
+            self.mint_decimals_0,
+            self.mint_decimals_1,
+        )?;
+
+        if self.base_is_token0(base_mint, quote_mint)? {
+            Ok(price_0_in_1)
+        } else {
+            Ok(1.0 / price_0_in_1)
+        }
+    }
+
+    /// Number of ticks covered by one tick array
+    pub fn tick_array_span(&self) -> i32 {
+        TICKS_PER_ARRAY * self.tick_spacing.max(1) as i32
+    }
+
+    /// Start index of the tick array containing `tick`
+    pub fn tick_array_start_index(&self, tick: i32) -> i32 {
+        tick.div_euclid(self.tick_array_span()) * self.tick_array_span()
+    }
+}
+
+/// A single tick inside a tick array (168 bytes)
+#[derive(Debug, Clone, Default, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct TickState {
+    /// Tick index
+    pub tick: i32,
+    /// Liquidity added when crossing the tick upward
+    pub liquidity_net: i128,
+    /// Total liquidity referencing the tick (zero if uninitialized)
+    pub liquidity_gross: u128,
+    /// Q64.64 fee growth outside for token 0
+    pub fee_growth_outside_0_x64: u128,
+    /// Q64.64 fee growth outside for token 1
+    pub fee_growth_outside_1_x64: u128,
+    /// Q64.64 reward growth outside
+    pub reward_growths_outside_x64: [u128; 3],
+    /// Padding
+    pub padding: [u32; 13],
+}
+
+/// Raydium CLMM tick array account (10240 bytes)
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct TickArrayState {
+    /// Anchor account discriminator
+    pub discriminator: [u8; 8],
+    /// Pool this array belongs to
+    pub pool_id: [u8; 32],
+    /// Index of the first tick in the array
+    pub start_tick_index: i32,
+    /// Ticks in the array
+    pub ticks: [TickState; TICKS_PER_ARRAY as usize],
+    /// Number of initialized ticks
+    pub initialized_tick_count: u8,
+    /// Epoch of the last update
+    pub recent_epoch: u64,
+    /// Padding
+    pub padding: [u8; 107],
+}
+
+impl TickArrayState {
+    /// Size of the on-chain account in bytes
+    pub const LEN: usize = 10240;
+
+    /// Offset of `pool_id`, used to filter tick arrays by pool
+    pub const POOL_ID_OFFSET: usize = 8;
+
+    /// Decode a tick array from raw account data
+    pub fn decode(data: &[u8]) -> Result<Self, SolanaError> {
+        let bytes = data.get(..Self::LEN).ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for tick array".to_string())
+        })?;
+        Self::try_from_slice(bytes)
+            .map_err(|e| SolanaError::PoolParsingError(format!("Tick array decode error: {}", e)))
+    }
+
+    /// Pool address in base58 form
+    pub fn pool_address(&self) -> String {
+        bs58::encode(self.pool_id).into_string()
+    }
+
+    /// Initialized ticks as (tick index, liquidity net)
+    pub fn initialized_ticks(&self) -> Vec<(i32, i128)> {
+        self.ticks
+            .iter()
+            .filter(|tick| tick.liquidity_gross != 0)
+            .map(|tick| (tick.tick, tick.liquidity_net))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{USDC_MINT, USDT_MINT};
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn test_tick_array(start: i32, initialized: &[(i32, i128)]) -> TickArrayState {
+        let mut ticks: [TickState; TICKS_PER_ARRAY as usize] =
+            std::array::from_fn(|_| TickState::default());
+        for (slot, &(tick, net)) in ticks.iter_mut().zip(initialized) {
+            slot.tick = tick;
+            slot.liquidity_net = net;
+            slot.liquidity_gross = net.unsigned_abs();
+        }
+
+        TickArrayState {
+            discriminator: [0; 8],
+            pool_id: [7; 32],
+            start_tick_index: start,
+            ticks,
+            initialized_tick_count: initialized.len() as u8,
+            recent_epoch: 0,
+            padding: [0; 107],
+        }
+    }
+
+    #[test]
+    fn test_pool_state_field_offsets() {
+        let state = ClmmPoolState {
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            tick_spacing: 10,
+            liquidity: 0x1111,
+            sqrt_price_x64: 0x2222,
+            tick_current: -5,
+            status: STATUS_SWAP_DISABLED,
+            ..Default::default()
+        };
+        let data = borsh::to_vec(&state).unwrap();
+
+        assert_eq!(data.len(), ClmmPoolState::HEADER_LEN);
+        assert_eq!(data[233], 9);
+        assert_eq!(data[234], 6);
+        assert_eq!(&data[235..237], &10u16.to_le_bytes());
+        assert_eq!(&data[237..253], &0x1111u128.to_le_bytes());
+        assert_eq!(&data[253..269], &0x2222u128.to_le_bytes());
+        assert_eq!(&data[269..273], &(-5i32).to_le_bytes());
+        assert_eq!(data[389], STATUS_SWAP_DISABLED);
+
+        let decoded = ClmmPoolState::decode(&[data, vec![0; 1154]].concat()).unwrap();
+        assert!(!decoded.is_swap_enabled());
+    }
+
+    #[test]
+    fn test_pool_price_orientation() {
+        let mut state = ClmmPoolState {
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            sqrt_price_x64: ((0.15f64).sqrt() * 2f64.powi(64)) as u128,
+            ..Default::default()
+        };
+        state
+            .token_mint_0
+            .copy_from_slice(&bs58::decode(SOL_MINT).into_vec().unwrap());
+
+        state
+            .token_mint_1
+            .copy_from_slice(&bs58::decode(USDC_MINT).into_vec().unwrap());
+
+        assert!((state.price_of(SOL_MINT, USDC_MINT).unwrap() - 150.0).abs() < 1e-6);
+        // USDC priced in SOL
+        assert!((state.price_of(USDC_MINT, SOL_MINT).unwrap() - 1.0 / 150.0).abs() < 1e-9);
+        assert!(state.price_of("UnknownMint", USDC_MINT).is_err());
+        assert!(state.price_of(SOL_MINT, USDT_MINT).is_err());
+    }
+
+    #[test]
+    fn test_tick_array_start_index() {
+        let state = ClmmPoolState {
+            tick_spacing: 10,
+            ..Default::default()
+        };
+        assert_eq!(state.tick_array_span(), 600);
+        assert_eq!(state.tick_array_start_index(599), 0);
+        assert_eq!(state.tick_array_start_index(600), 600);
+        assert_eq!(state.tick_array_start_index(-1), -600);
+    }
+
+    #[test]
+    fn test_tick_array_decoding() {
+        let array = test_tick_array(-600, &[(-590, 500), (-10, -500)]);
+        let data = borsh::to_vec(&array).unwrap();
+        assert_eq!(data.len(), TickArrayState::LEN);
+
+        let decoded = TickArrayState::decode(&data).unwrap();
+        assert_eq!(decoded.start_tick_index, -600);
+        assert_eq!(
+            decoded.pool_address(),
+            bs58::encode([7u8; 32]).into_string()
+        );
+        assert_eq!(decoded.initialized_ticks(), vec![(-590, 500), (-10, -500)]);
+    }
+}
@@ -0,0 +1,2042 @@
+use crate::config::reload::LiveSettings;
+use crate::config::{PriceBounds, RpcProvider, TradingPair, DEFAULT_MAX_PRICE, DEFAULT_MIN_PRICE};
+use crate::price::{
+    concentrated_quote, constant_product_quote, ConcentratedLiquidity, ConstantProductPool,
+    LiquidityProfile, PriceSource, PriceUpdate, Quote, AMM_QUOTE_SIZE,
+};
+use crate::websocket::reconnect::{ReconnectConfig, ReconnectError, ReconnectHandler};
+use crate::websocket::record::{Frame, FrameRecorder};
+use crate::websocket::state::{ConnectionState, ConnectionStateMachine, StateTransition};
+use base64::prelude::*;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+use tokio::sync::{broadcast, watch};
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+mod clmm;
+mod openbook;
+mod raydium;
+mod slots;
+mod whirlpool;
+
+pub use clmm::{ClmmPoolState, TickArrayState, CLMM_PROGRAM_ID};
+pub use openbook::{best_level, BookLevel, BookSideKind, OpenBookMarket};
+pub use raydium::{decode_token_account_amount, RaydiumPoolState};
+use slots::SlotClock;
+pub use whirlpool::WhirlpoolState;
+
+/// Tick arrays kept on each side of the current CLMM tick array
+const TICK_ARRAY_WINDOW: i32 = 2;
+
+/// Convert a Q64.64 sqrt price (token B atoms per token A atom) to a decimal-adjusted price
+fn price_from_sqrt_price_x64(
+    sqrt_price_x64: u128,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<f64, SolanaError> {
+    if sqrt_price_x64 == 0 {
+        return Err(SolanaError::PoolParsingError(
+            "Sqrt price is zero".to_string(),
+        ));
+    }
+
+    // Squaring the Q64.64 sqrt price yields B atoms per A atom
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let raw_price = sqrt_price * sqrt_price;
+
+    Ok(raw_price * 10f64.powi(decimals_a as i32 - decimals_b as i32))
+}
+
+/// Whether the pair's base token is the first of a pool's two mints
+///
+/// Errors unless the pool holds exactly `base_mint` and `quote_mint`, in either order, so
+/// that a pool for other tokens is never priced as this pair.
+fn base_is_first_mint(
+    pool: &str,
+    first: &[u8; 32],
+    second: &[u8; 32],
+    base_mint: &str,
+    quote_mint: &str,
+) -> Result<bool, SolanaError> {
+    let (first, second) = (
+        bs58::encode(first).into_string(),
+        bs58::encode(second).into_string(),
+    );
+    if first == base_mint && second == quote_mint {
+        Ok(true)
+    } else if first == quote_mint && second == base_mint {
+        Ok(false)
+    } else {
+        Err(SolanaError::PoolParsingError(format!(
+            "{} holds {}/{}, not {}/{}",
+            pool, first, second, base_mint, quote_mint
+        )))
+    }
+}
+
+/// Errors that can occur with Solana WebSocket operations
+#[derive(Debug, Error)]
+#[allow(dead_code)]
+pub enum SolanaError {
+    #[error("WebSocket connection error: {0}")]
+    ConnectionError(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("URL parsing error: {0}")]
+    UrlError(#[from] url::ParseError),
+    #[error("Connection timeout: {0:?}")]
+    Timeout(Duration),
+    #[error("Reconnection failed: {0}")]
+    ReconnectFailed(#[from] ReconnectError),
+    #[error("Invalid trading pair: {0:?}")]
+    InvalidTradingPair(TradingPair),
+    #[error("No RPC providers available")]
+    NoProvidersAvailable,
+    #[error("All RPC providers failed")]
+    AllProvidersFailed,
+    #[error("Invalid account data received")]
+    InvalidAccountData,
+    #[error("Pool data parsing error: {0}")]
+    PoolParsingError(String),
+}
+
+/// Role of a subscribed account in price derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccountRole {
+    Pool,
+    BaseVault,
+    QuoteVault,
+    Whirlpool,
+    ClmmPool,
+    ClmmTickArrays,
+    OpenBookMarket,
+    OpenBookBids,
+    OpenBookAsks,
+}
+
+/// Latest vault balance together with the slot it was observed at
+#[derive(Debug, Clone, Copy)]
+struct VaultBalance {
+    amount: u64,
+    slot: u64,
+}
+
+/// Per-pair subscription routing and pool/vault snapshots
+#[derive(Debug)]
+struct PoolSession {
+    /// Trading pair the subscribed pools quote
+    pair: TradingPair,
+    /// Request id -> role, awaiting subscription confirmation
+    pending: HashMap<u64, AccountRole>,
+    /// Subscription id -> role
+    subscriptions: HashMap<u64, AccountRole>,
+    /// Requests queued for sending on the socket
+    outgoing: Vec<AccountSubscribeRequest>,
+    next_request_id: u64,
+    pool_state: Option<RaydiumPoolState>,
+    vaults_requested: bool,
+    base_vault: Option<VaultBalance>,
+    quote_vault: Option<VaultBalance>,
+    clmm_pool: Option<ClmmPoolState>,
+    /// Tick array start index -> initialized ticks (tick index, liquidity net)
+    clmm_tick_arrays: BTreeMap<i32, Vec<(i32, i128)>>,
+    openbook_market: Option<OpenBookMarket>,
+    /// Best bid/ask; `Some(None)` means the side was observed empty
+    best_bid: Option<Option<BookLevel>>,
+    best_ask: Option<Option<BookLevel>>,
+}
+
+impl PoolSession {
+    /// Create an empty session for `pair`
+    fn new(pair: TradingPair) -> Self {
+        Self {
+            pair,
+            pending: HashMap::new(),
+            subscriptions: HashMap::new(),
+            outgoing: Vec::new(),
+            next_request_id: 0,
+            pool_state: None,
+            vaults_requested: false,
+            base_vault: None,
+            quote_vault: None,
+            clmm_pool: None,
+            clmm_tick_arrays: BTreeMap::new(),
+            openbook_market: None,
+            best_bid: None,
+            best_ask: None,
+        }
+    }
+
+    /// Queue an accountSubscribe request for the given address and role
+    fn subscribe(&mut self, address: String, role: AccountRole) {
+        self.next_request_id += 1;
+        let id = self.next_request_id;
+        self.pending.insert(id, role);
+        self.outgoing
+            .push(AccountSubscribeRequest::account_subscribe(id, address));
+    }
+
+    /// Queue a programSubscribe request for accounts of `program` matching `filters`
+    fn subscribe_program(&mut self, program: &str, filters: serde_json::Value, role: AccountRole) {
+        self.next_request_id += 1;
+        let id = self.next_request_id;
+        self.pending.insert(id, role);
+        self.outgoing
+            .push(AccountSubscribeRequest::program_subscribe(
+                id, program, filters,
+            ));
+    }
+
+    /// Take all queued outgoing requests
+    fn take_outgoing(&mut self) -> Vec<AccountSubscribeRequest> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    /// Record a subscription confirmation (request id -> subscription id)
+    fn confirm(&mut self, request_id: u64, subscription_id: u64) {
+        if let Some(role) = self.pending.remove(&request_id) {
+            self.subscriptions.insert(subscription_id, role);
+        }
+    }
+}
+
+/// Pool sessions of several trading pairs multiplexed over one socket
+///
+/// Each session numbers its own requests; they are renumbered on the way out so ids stay
+/// unique on the socket, and confirmations are routed back to the session that asked.
+#[derive(Debug)]
+struct SharedSession {
+    sessions: Vec<PoolSession>,
+    /// Socket request id -> (session index, session request id)
+    pending: HashMap<u64, (usize, u64)>,
+    /// Subscription id -> session index
+    routes: HashMap<u64, usize>,
+    next_request_id: u64,
+    /// Wall-clock times of the slots notifications arrive for
+    slots: SlotClock,
+}
+
+impl SharedSession {
+    fn new(sessions: Vec<PoolSession>) -> Self {
+        Self {
+            sessions,
+            pending: HashMap::new(),
+            routes: HashMap::new(),
+            next_request_id: 0,
+            slots: SlotClock::default(),
+        }
+    }
+
+    /// Take every session's queued requests, renumbered for the socket
+    fn take_outgoing(&mut self) -> Vec<AccountSubscribeRequest> {
+        let mut outgoing = Vec::new();
+        for (index, session) in self.sessions.iter_mut().enumerate() {
+            for mut request in session.take_outgoing() {
+                self.next_request_id += 1;
+                self.pending
+                    .insert(self.next_request_id, (index, request.id));
+                request.id = self.next_request_id;
+                outgoing.push(request);
+            }
+        }
+        outgoing
+    }
+
+    /// Record a subscription confirmation in the session that requested it
+    fn confirm(&mut self, request_id: u64, subscription_id: u64) {
+        if let Some((index, session_request_id)) = self.pending.remove(&request_id) {
+            self.sessions[index].confirm(session_request_id, subscription_id);
+            self.routes.insert(subscription_id, index);
+        }
+    }
+
+    /// Session owning a subscription
+    fn session_mut(&mut self, subscription_id: u64) -> Option<&mut PoolSession> {
+        let index = *self.routes.get(&subscription_id)?;
+        self.sessions.get_mut(index)
+    }
+}
+
+/// Solana JSON-RPC request for account or program subscription
+#[derive(Debug, Serialize)]
+#[allow(dead_code)]
+struct AccountSubscribeRequest {
+    jsonrpc: String,
+    id: u64,
+    method: String,
+    params: serde_json::Value,
+}
+
+impl AccountSubscribeRequest {
+    /// Build an accountSubscribe request with base64 encoding at confirmed commitment
+    fn account_subscribe(id: u64, address: String) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            method: "accountSubscribe".to_string(),
+            params: serde_json::json!([
+                address,
+                {
+                    "encoding": "base64",
+                    "commitment": "confirmed"
+                }
+            ]),
+        }
+    }
+
+    /// Build a programSubscribe request with base64 encoding at confirmed commitment
+    fn program_subscribe(id: u64, program: &str, filters: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            method: "programSubscribe".to_string(),
+            params: serde_json::json!([
+                program,
+                {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "filters": filters
+                }
+            ]),
+        }
+    }
+}
+
+/// Solana JSON-RPC response wrapper
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct JsonRpcResponse<T> {
+    jsonrpc: String,
+    id: Option<u64>,
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+/// Solana JSON-RPC error structure
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+/// JSON-RPC notification envelope (`{"method": "accountNotification", "params": {...}}`)
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct NotificationEnvelope {
+    method: String,
+    params: serde_json::Value,
+}
+
+/// Solana account notification structure
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct AccountNotification {
+    subscription: u64,
+    result: AccountData,
+}
+
+/// Solana account data structure
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct AccountData {
+    context: Context,
+    value: AccountValue,
+}
+
+/// Solana program notification structure
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct ProgramNotification {
+    subscription: u64,
+    result: ProgramAccountData,
+}
+
+/// Program notification payload: the changed account and its address
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct ProgramAccountData {
+    context: Context,
+    value: KeyedAccount,
+}
+
+/// Account together with its address
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct KeyedAccount {
+    pubkey: String,
+    account: AccountValue,
+}
+
+/// Solana context information
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Context {
+    slot: u64,
+}
+
+/// Solana account value structure
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct AccountValue {
+    data: Option<serde_json::Value>,
+    executable: bool,
+    lamports: u64,
+    owner: String,
+    #[serde(rename = "rentEpoch")]
+    rent_epoch: u64,
+}
+
+/// Configuration for Solana WebSocket client
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct SolanaConfig {
+    /// RPC providers with priority ordering
+    pub rpc_providers: Vec<RpcProvider>,
+    /// Connection timeout
+    pub connection_timeout: Duration,
+    /// Reconnection configuration
+    pub reconnect_config: ReconnectConfig,
+    /// Account address to monitor for price data (primary pair only)
+    pub account_address: Option<String>,
+    /// Orca Whirlpool address to monitor (overrides the known pool for the primary pair)
+    pub whirlpool_address: Option<String>,
+    /// Raydium CLMM pool address to monitor (overrides the known pool for the primary pair)
+    pub clmm_address: Option<String>,
+    /// OpenBook v2 market address to monitor (overrides the known market for the primary pair)
+    pub openbook_market: Option<String>,
+    /// Price bounds for validation, unless the client follows live settings
+    pub price_bounds: PriceBounds,
+}
+
+impl Default for SolanaConfig {
+    fn default() -> Self {
+        Self {
+            rpc_providers: vec![
+                RpcProvider {
+                    name: "Public Solana (Limited)".to_string(),
+                    websocket_url: "wss://api.mainnet-beta.solana.com/"
+                        .parse()
+                        .expect("Invalid default RPC URL"),
+                    priority: 1,
+                    provider_type: crate::config::RpcProviderType::Public,
+                },
+                RpcProvider {
+                    name: "Solana Devnet (Fallback)".to_string(),
+                    websocket_url: "wss://api.devnet.solana.com/"
+                        .parse()
+                        .expect("Invalid default RPC URL"),
+                    priority: 2,
+                    provider_type: crate::config::RpcProviderType::Public,
+                },
+            ],
+            connection_timeout: Duration::from_secs(10),
+            reconnect_config: ReconnectConfig::default(),
+            account_address: None,
+            whirlpool_address: None,
+            clmm_address: None,
+            openbook_market: None,
+            price_bounds: PriceBounds::new(DEFAULT_MIN_PRICE, DEFAULT_MAX_PRICE)
+                .expect("Valid default price bounds"),
+        }
+    }
+}
+
+impl SolanaConfig {
+    /// Create new Solana configuration with custom providers
+    #[allow(dead_code)]
+    pub fn new(rpc_providers: Vec<RpcProvider>, connection_timeout: Duration) -> Self {
+        Self {
+            rpc_providers,
+            connection_timeout,
+            reconnect_config: ReconnectConfig::default(),
+            account_address: None,
+            whirlpool_address: None,
+            clmm_address: None,
+            openbook_market: None,
+            price_bounds: PriceBounds::new(DEFAULT_MIN_PRICE, DEFAULT_MAX_PRICE)
+                .expect("Valid default price bounds"),
+        }
+    }
+
+    /// Set reconnection configuration
+    #[allow(dead_code)]
+    pub fn with_reconnect_config(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect_config = config;
+        self
+    }
+
+    /// Set account address to monitor
+    #[allow(dead_code)]
+    pub fn with_account_address(mut self, address: String) -> Self {
+        self.account_address = Some(address);
+        self
+    }
+
+    /// Set Orca Whirlpool address to monitor
+    #[allow(dead_code)]
+    pub fn with_whirlpool_address(mut self, address: String) -> Self {
+        self.whirlpool_address = Some(address);
+        self
+    }
+
+    /// Set Raydium CLMM pool address to monitor
+    #[allow(dead_code)]
+    pub fn with_clmm_address(mut self, address: String) -> Self {
+        self.clmm_address = Some(address);
+        self
+    }
+
+    /// Set OpenBook v2 market address to monitor
+    #[allow(dead_code)]
+    pub fn with_openbook_market(mut self, address: String) -> Self {
+        self.openbook_market = Some(address);
+        self
+    }
+
+    /// Set price bounds for validation
+    #[allow(dead_code)]
+    pub fn with_price_bounds(mut self, price_bounds: PriceBounds) -> Self {
+        self.price_bounds = price_bounds;
+        self
+    }
+}
+
+/// Solana WebSocket client for real-time price data from DEX pools
+///
+/// The pools of every trading pair are subscribed over one socket. The first pair is the
+/// primary pair, which the address overrides in `SolanaConfig` apply to.
+#[allow(dead_code)]
+pub struct SolanaClient {
+    config: SolanaConfig,
+    trading_pairs: Vec<TradingPair>,
+    reconnect_handler: ReconnectHandler,
+    current_provider_index: usize,
+    recorder: Option<FrameRecorder>,
+    state: ConnectionStateMachine,
+    /// Reloadable settings, whose price bounds replace the configured ones
+    live_settings: Option<watch::Receiver<LiveSettings>>,
+}
+
+/// Subscription state rebuilt while replaying recorded frames
+#[derive(Debug, Default)]
+pub struct SolanaReplay {
+    shared: Option<SharedSession>,
+}
+
+impl SolanaClient {
+    /// Create new Solana WebSocket client
+    #[allow(dead_code)]
+    pub fn new(config: SolanaConfig, trading_pair: TradingPair) -> Result<Self, SolanaError> {
+        Self::for_pairs(config, vec![trading_pair])
+    }
+
+    /// Create a client monitoring the pools of several trading pairs over one connection
+    pub fn for_pairs(
+        config: SolanaConfig,
+        trading_pairs: Vec<TradingPair>,
+    ) -> Result<Self, SolanaError> {
+        if config.rpc_providers.is_empty() {
+            return Err(SolanaError::NoProvidersAvailable);
+        }
+        if trading_pairs.is_empty() {
+            return Err(SolanaError::PoolParsingError(
+                "No trading pairs to monitor".to_string(),
+            ));
+        }
+
+        let reconnect_handler =
+            ReconnectHandler::new(config.reconnect_config.clone()).map_err(|e| {
+                SolanaError::JsonError(serde_json::Error::io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    e,
+                )))
+            })?;
+
+        Ok(Self {
+            config,
+            trading_pairs,
+            reconnect_handler,
+            current_provider_index: 0,
+            recorder: None,
+            state: ConnectionStateMachine::new("solana"),
+            live_settings: None,
+        })
+    }
+
+    /// Record every received frame
+    pub fn with_recorder(mut self, recorder: FrameRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Check prices against the bounds of the reloadable settings, so that a config
+    /// reload applies to them without a restart
+    pub fn with_live_settings(mut self, settings: watch::Receiver<LiveSettings>) -> Self {
+        self.live_settings = Some(settings);
+        self
+    }
+
+    /// Watch the connection state
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Receive every later connection state transition
+    pub fn transitions(&self) -> broadcast::Receiver<StateTransition> {
+        self.state.transitions()
+    }
+
+    /// Create client with default configuration
+    #[allow(dead_code)]
+    pub fn with_default(trading_pair: TradingPair) -> Result<Self, SolanaError> {
+        Self::new(SolanaConfig::default(), trading_pair)
+    }
+
+    /// Create client from RPC providers
+    #[allow(dead_code)]
+    pub fn from_providers(
+        rpc_providers: Vec<RpcProvider>,
+        trading_pair: TradingPair,
+    ) -> Result<Self, SolanaError> {
+        let config = SolanaConfig::new(rpc_providers, Duration::from_secs(10));
+        Self::new(config, trading_pair)
+    }
+
+    /// Create client for several trading pairs from RPC providers with price bounds
+    #[allow(dead_code)]
+    pub fn from_providers_with_bounds(
+        rpc_providers: Vec<RpcProvider>,
+        trading_pairs: Vec<TradingPair>,
+        price_bounds: PriceBounds,
+    ) -> Result<Self, SolanaError> {
+        let config = SolanaConfig::new(rpc_providers, Duration::from_secs(10))
+            .with_price_bounds(price_bounds);
+        Self::for_pairs(config, trading_pairs)
+    }
+
+    /// Start the WebSocket client and stream price updates
+    #[allow(dead_code)]
+    pub async fn start<F>(&mut self, mut callback: F) -> Result<(), SolanaError>
+    where
+        F: FnMut(PriceUpdate) + Send,
+    {
+        loop {
+            self.state.transition(ConnectionState::Connecting);
+            match self.connect_and_stream(&mut callback).await {
+                Ok(()) => {
+                    // Normal disconnect, reset reconnection handler
+                    self.reconnect_handler.reset();
+                    self.state.transition(ConnectionState::Failed);
+                    break;
+                }
+                Err(e) => {
+                    log::error!("Solana WebSocket error: {}", e);
+
+                    // Try next provider if available
+                    if self.try_next_provider() {
+                        log::info!(
+                            "Switching to provider: {}",
+                            self.get_current_provider().name
+                        );
+                        continue;
+                    }
+
+                    // Determine if we should reconnect
+                    match self.reconnect_handler.should_reconnect() {
+                        Ok(delay) => {
+                            let attempt = self.reconnect_handler.attempt_count();
+                            log::warn!(
+                                "Reconnecting to Solana in {:?} (attempt {})",
+                                delay,
+                                attempt
+                            );
+                            self.state.transition(ConnectionState::Backoff { attempt });
+                            sleep(delay).await;
+                            // Reset provider index for retry
+                            self.current_provider_index = 0;
+                        }
+                        Err(reconnect_error) => {
+                            log::error!("Giving up on Solana reconnection: {}", reconnect_error);
+                            self.state.transition(ConnectionState::Failed);
+                            return Err(SolanaError::ReconnectFailed(reconnect_error));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Connect to Solana WebSocket and stream data
+    #[allow(dead_code)]
+    async fn connect_and_stream<F>(&self, callback: &mut F) -> Result<(), SolanaError>
+    where
+        F: FnMut(PriceUpdate) + Send,
+    {
+        let provider = self.get_current_provider();
+        let url = &provider.websocket_url;
+
+        log::info!("Connecting to Solana via: {}", provider.name);
+
+        // Connect with timeout
+        let (ws_stream, _) = timeout(self.config.connection_timeout, connect_async(url))
+            .await
+            .map_err(|_| SolanaError::Timeout(self.config.connection_timeout))?
+            .map_err(|e| SolanaError::ConnectionError(Box::new(e)))?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Frame::SolanaConnected);
+        }
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // Subscribe to each pair's pool accounts; vault subscriptions follow once decoded
+        let mut shared = self.open_shared()?;
+        self.state.transition(ConnectionState::Subscribed);
+
+        loop {
+            for request in shared.take_outgoing() {
+                let msg_text = serde_json::to_string(&request)?;
+                write
+                    .send(Message::Text(msg_text))
+                    .await
+                    .map_err(|e| SolanaError::ConnectionError(Box::new(e)))?;
+            }
+
+            let Some(message) = read.next().await else {
+                break;
+            };
+
+            match message.map_err(|e| SolanaError::ConnectionError(Box::new(e)))? {
+                Message::Text(text) => {
+                    if let Some(recorder) = &self.recorder {
+                        recorder.record(Frame::Solana { f: text.clone() });
+                    }
+                    let received_at = SystemTime::now();
+                    match self.parse_shared_message(&mut shared, &text, received_at) {
+                        Ok(Some(price_update)) => {
+                            self.state.transition(ConnectionState::Streaming);
+                            callback(price_update);
+                        }
+                        Ok(None) => {}
+                        Err(e) => log::debug!("Ignoring Solana message: {}", e),
+                    }
+                }
+                Message::Ping(payload) => {
+                    write
+                        .send(Message::Pong(payload))
+                        .await
+                        .map_err(|e| SolanaError::ConnectionError(Box::new(e)))?;
+                }
+                Message::Close(_) => {
+                    log::info!("Solana WebSocket connection closed");
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get current RPC provider
+    fn get_current_provider(&self) -> &RpcProvider {
+        &self.config.rpc_providers[self.current_provider_index]
+    }
+
+    /// Try to switch to next available provider
+    fn try_next_provider(&mut self) -> bool {
+        if self.current_provider_index + 1 < self.config.rpc_providers.len() {
+            self.current_provider_index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sessions of every pair on one socket, with their pool subscriptions queued
+    fn open_shared(&self) -> Result<SharedSession, SolanaError> {
+        let sessions = self
+            .trading_pairs
+            .iter()
+            .map(|&pair| self.open_session(pair))
+            .collect::<Result<_, _>>()?;
+        Ok(SharedSession::new(sessions))
+    }
+
+    /// Session for `pair` with its pool subscriptions queued
+    fn open_session(&self, pair: TradingPair) -> Result<PoolSession, SolanaError> {
+        let mut session = PoolSession::new(pair);
+        if let Some(address) = self.pool_address(pair) {
+            session.subscribe(address, AccountRole::Pool);
+        }
+        if let Some(address) = self.whirlpool_address(pair) {
+            session.subscribe(address, AccountRole::Whirlpool);
+        }
+        if let Some(address) = self.clmm_address(pair) {
+            Self::subscribe_clmm(&mut session, address);
+        }
+        if let Some(address) = self.openbook_market_address(pair) {
+            session.subscribe(address, AccountRole::OpenBookMarket);
+        }
+        if session.outgoing.is_empty() {
+            return Err(SolanaError::PoolParsingError(format!(
+                "No Solana venues to monitor for {}",
+                pair.label()
+            )));
+        }
+        Ok(session)
+    }
+
+    /// Configured address override, which only applies to the primary pair
+    fn address_override<'a>(
+        &self,
+        pair: TradingPair,
+        address: &'a Option<String>,
+    ) -> Option<&'a String> {
+        address.as_ref().filter(|_| pair == self.trading_pairs[0])
+    }
+
+    /// Raydium AMM pool to subscribe to, if one is configured or defined for the pair
+    fn pool_address(&self, pair: TradingPair) -> Option<String> {
+        self.address_override(pair, &self.config.account_address)
+            .cloned()
+            .or_else(|| Self::get_pool_address(pair).map(str::to_string))
+    }
+
+    /// Orca Whirlpool to subscribe to, if one is configured or known for the pair
+    fn whirlpool_address(&self, pair: TradingPair) -> Option<String> {
+        self.address_override(pair, &self.config.whirlpool_address)
+            .cloned()
+            .or_else(|| Self::get_whirlpool_address(pair).map(str::to_string))
+    }
+
+    /// Raydium CLMM pool to subscribe to, if one is configured or known for the pair
+    fn clmm_address(&self, pair: TradingPair) -> Option<String> {
+        self.address_override(pair, &self.config.clmm_address)
+            .cloned()
+            .or_else(|| Self::get_clmm_address(pair).map(str::to_string))
+    }
+
+    /// OpenBook market to subscribe to, if one is configured or known for the pair
+    fn openbook_market_address(&self, pair: TradingPair) -> Option<String> {
+        self.address_override(pair, &self.config.openbook_market)
+            .cloned()
+            .or_else(|| Self::get_openbook_market(pair).map(str::to_string))
+    }
+
+    /// Subscribe to a CLMM pool and to all tick arrays belonging to it
+    fn subscribe_clmm(session: &mut PoolSession, address: String) {
+        let filters = serde_json::json!([
+            { "dataSize": TickArrayState::LEN },
+            { "memcmp": { "offset": TickArrayState::POOL_ID_OFFSET, "bytes": address } }
+        ]);
+        session.subscribe(address, AccountRole::ClmmPool);
+        session.subscribe_program(CLMM_PROGRAM_ID, filters, AccountRole::ClmmTickArrays);
+    }
+
+    /// Create account subscription message for the primary pair's pool account
+    #[allow(dead_code)]
+    fn create_account_subscribe_message(&self) -> Result<AccountSubscribeRequest, SolanaError> {
+        let pair = self.trading_pairs[0];
+        let address = self.pool_address(pair).ok_or_else(|| {
+            SolanaError::PoolParsingError(format!("No AMM pool defined for {}", pair.label()))
+        })?;
+        Ok(AccountSubscribeRequest::account_subscribe(1, address))
+    }
+
+    /// Parse a WebSocket text frame for a single session, updating its state
+    ///
+    /// Returns a price update when both vault balances and the pool state are known.
+    #[allow(dead_code)]
+    fn parse_account_message(
+        &self,
+        session: &mut PoolSession,
+        text: &str,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        if let Ok(notification) = serde_json::from_str::<NotificationEnvelope>(text) {
+            return self.handle_notification(session, notification);
+        }
+
+        match Self::parse_confirmation(text)? {
+            Some((request_id, subscription_id)) => {
+                session.confirm(request_id, subscription_id);
+                Ok(None)
+            }
+            None => Err(SolanaError::InvalidAccountData),
+        }
+    }
+
+    /// Process a recorded frame the way the live connection processed it
+    ///
+    /// Requests are numbered as they were live, so recorded confirmations route to the
+    /// same sessions; replay with the pairs and pools that were recorded. Slot times
+    /// are estimated from the recorded receive times.
+    pub fn replay_frame(
+        &self,
+        state: &mut SolanaReplay,
+        frame: &Frame,
+        received_at: SystemTime,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        let text = match frame {
+            Frame::SolanaConnected => {
+                state.shared = None;
+                None
+            }
+            Frame::Solana { f } => Some(f),
+            _ => return Ok(None),
+        };
+        let shared = match &mut state.shared {
+            Some(shared) => shared,
+            None => {
+                // Subscriptions sent on connecting
+                let mut shared = self.open_shared()?;
+                shared.take_outgoing();
+                state.shared.insert(shared)
+            }
+        };
+
+        let result = match text {
+            Some(text) => self.parse_shared_message(shared, text, received_at),
+            None => Ok(None),
+        };
+        // Requests the live client sent after this frame
+        shared.take_outgoing();
+        result
+    }
+
+    /// Parse a text frame from a socket shared by several pairs' sessions
+    ///
+    /// Updates are stamped with the estimated time of their slot and `received_at`.
+    fn parse_shared_message(
+        &self,
+        shared: &mut SharedSession,
+        text: &str,
+        received_at: SystemTime,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        if let Ok(notification) = serde_json::from_str::<NotificationEnvelope>(text) {
+            if let Some(slot) = notification
+                .params
+                .pointer("/result/context/slot")
+                .and_then(serde_json::Value::as_u64)
+            {
+                shared.slots.observe(slot, received_at);
+            }
+            let session = notification
+                .params
+                .get("subscription")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|subscription| shared.session_mut(subscription))
+                .ok_or(SolanaError::InvalidAccountData)?;
+            let update = self.handle_notification(session, notification)?;
+            return Ok(update.map(|update| {
+                // A slot cannot be processed after its notification arrived
+                let slot_time = update
+                    .slot
+                    .and_then(|slot| shared.slots.time_of(slot))
+                    .map_or(received_at, |time| time.min(received_at));
+                update
+                    .with_source_time(slot_time)
+                    .with_received_at(received_at)
+            }));
+        }
+
+        match Self::parse_confirmation(text)? {
+            Some((request_id, subscription_id)) => {
+                shared.confirm(request_id, subscription_id);
+                Ok(None)
+            }
+            None => Err(SolanaError::InvalidAccountData),
+        }
+    }
+
+    /// Route an account or program notification to the session's handlers
+    fn handle_notification(
+        &self,
+        session: &mut PoolSession,
+        notification: NotificationEnvelope,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        match notification.method.as_str() {
+            "accountNotification" => {
+                let params: AccountNotification = serde_json::from_value(notification.params)?;
+                self.handle_account_notification(
+                    session,
+                    params.subscription,
+                    params.result.context.slot,
+                    &params.result.value,
+                )
+            }
+            "programNotification" => {
+                let params: ProgramNotification = serde_json::from_value(notification.params)?;
+                self.handle_account_notification(
+                    session,
+                    params.subscription,
+                    params.result.context.slot,
+                    &params.result.value.account,
+                )
+            }
+            _ => Err(SolanaError::InvalidAccountData),
+        }
+    }
+
+    /// Parse a subscription confirmation into (request id, subscription id)
+    ///
+    /// `{"jsonrpc":"2.0","result":<subscription id>,"id":<request id>}`; subscription
+    /// errors are returned as errors, and any other frame as `None`.
+    fn parse_confirmation(text: &str) -> Result<Option<(u64, u64)>, SolanaError> {
+        let Ok(response) = serde_json::from_str::<JsonRpcResponse<u64>>(text) else {
+            return Ok(None);
+        };
+        if let (Some(request_id), Some(subscription_id)) = (response.id, response.result) {
+            return Ok(Some((request_id, subscription_id)));
+        }
+        match response.error {
+            Some(error) => Err(SolanaError::PoolParsingError(format!(
+                "Subscription error {}: {}",
+                error.code, error.message
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Route an account notification to the pool, vault or tick array it belongs to
+    fn handle_account_notification(
+        &self,
+        session: &mut PoolSession,
+        subscription: u64,
+        slot: u64,
+        account: &AccountValue,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        let role = session
+            .subscriptions
+            .get(&subscription)
+            .copied()
+            .ok_or(SolanaError::InvalidAccountData)?;
+        let data = Self::decode_account_data(account)?;
+
+        match role {
+            AccountRole::Whirlpool => {
+                return self.whirlpool_price(session.pair, &data, slot).map(Some)
+            }
+            AccountRole::ClmmPool => {
+                let pool_state = ClmmPoolState::decode(&data)?;
+                if !pool_state.is_swap_enabled() {
+                    return Err(SolanaError::PoolParsingError(
+                        "CLMM pool swaps are disabled".to_string(),
+                    ));
+                }
+                session.clmm_pool = Some(pool_state);
+                Self::prune_tick_arrays(session);
+                return self.clmm_price(session, slot);
+            }
+            AccountRole::OpenBookMarket => {
+                let market = OpenBookMarket::decode(&data)?;
+                let spec = session.pair.spec();
+                market.check_mints(&spec.base_mint, &spec.quote_mint)?;
+                if session.openbook_market.is_none() {
+                    session.subscribe(market.bids_address(), AccountRole::OpenBookBids);
+                    session.subscribe(market.asks_address(), AccountRole::OpenBookAsks);
+                }
+                session.openbook_market = Some(market);
+                return Ok(None);
+            }
+            AccountRole::OpenBookBids => {
+                session.best_bid = Some(best_level(&data, BookSideKind::Bids)?);
+                return self.openbook_price(session, slot);
+            }
+            AccountRole::OpenBookAsks => {
+                session.best_ask = Some(best_level(&data, BookSideKind::Asks)?);
+                return self.openbook_price(session, slot);
+            }
+            AccountRole::ClmmTickArrays => {
+                let tick_array = TickArrayState::decode(&data)?;
+                if self.clmm_address(session.pair) != Some(tick_array.pool_address()) {
+                    return Err(SolanaError::InvalidAccountData);
+                }
+                session
+                    .clmm_tick_arrays
+                    .insert(tick_array.start_tick_index, tick_array.initialized_ticks());
+                Self::prune_tick_arrays(session);
+                return self.clmm_price(session, slot);
+            }
+            AccountRole::Pool => {
+                let pool_state = RaydiumPoolState::decode(&data)?;
+                let spec = session.pair.spec();
+                pool_state.base_is_coin(&spec.base_mint, &spec.quote_mint)?;
+
+                // Validate that this is an active pool
+                if !pool_state.is_active() {
+                    return Err(SolanaError::PoolParsingError(
+                        "Pool is not active".to_string(),
+                    ));
+                }
+
+                if !session.vaults_requested {
+                    session.subscribe(pool_state.base_vault_address(), AccountRole::BaseVault);
+                    session.subscribe(pool_state.quote_vault_address(), AccountRole::QuoteVault);
+                    session.vaults_requested = true;
+                }
+                session.pool_state = Some(pool_state);
+            }
+            AccountRole::BaseVault => {
+                let amount = decode_token_account_amount(&data)?;
+                session.base_vault = Some(VaultBalance { amount, slot });
+            }
+            AccountRole::QuoteVault => {
+                let amount = decode_token_account_amount(&data)?;
+                session.quote_vault = Some(VaultBalance { amount, slot });
+            }
+        }
+
+        self.price_from_session(session, slot)
+    }
+
+    /// Compute a price update from the session if pool state and both vaults are known
+    fn price_from_session(
+        &self,
+        session: &PoolSession,
+        slot: u64,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        let (Some(pool_state), Some(base), Some(quote)) =
+            (&session.pool_state, session.base_vault, session.quote_vault)
+        else {
+            return Ok(None);
+        };
+
+        let spec = session.pair.spec();
+        let price = pool_state.calculate_price(
+            &spec.base_mint,
+            &spec.quote_mint,
+            base.amount,
+            quote.amount,
+        )?;
+        self.check_price_bounds(session.pair, price)?;
+
+        let observed_slot = slot.max(base.slot).max(quote.slot);
+        let mut update =
+            PriceUpdate::new(PriceSource::Solana, session.pair, price).with_slot(observed_slot);
+
+        let (base_reserve, quote_reserve) = pool_state.pair_reserves(
+            &spec.base_mint,
+            &spec.quote_mint,
+            base.amount,
+            quote.amount,
+        )?;
+        if let Some(quote) = constant_product_quote(base_reserve, quote_reserve, AMM_QUOTE_SIZE) {
+            update = update.with_quote(quote);
+        }
+        Ok(Some(update.with_liquidity(
+            LiquidityProfile::ConstantProduct(ConstantProductPool {
+                base_reserve,
+                quote_reserve,
+                fee_rate: pool_state.fee_rate(),
+            }),
+        )))
+    }
+
+    /// Compute a price update from a Whirlpool account snapshot
+    fn whirlpool_price(
+        &self,
+        pair: TradingPair,
+        data: &[u8],
+        slot: u64,
+    ) -> Result<PriceUpdate, SolanaError> {
+        let whirlpool = WhirlpoolState::decode(data)?;
+        let spec = pair.spec();
+        let price = whirlpool.price_of(
+            &spec.base_mint,
+            &spec.quote_mint,
+            spec.base_decimals,
+            spec.quote_decimals,
+        )?;
+        self.check_price_bounds(pair, price)?;
+
+        let mut update = PriceUpdate::new(PriceSource::Orca, pair, price).with_slot(slot);
+        if let Some(quote) = concentrated_quote(
+            whirlpool.sqrt_price as f64 / 2f64.powi(64),
+            whirlpool.liquidity,
+            whirlpool.base_is_token_a(&spec.base_mint, &spec.quote_mint)?,
+            spec.base_decimals,
+            spec.quote_decimals,
+            AMM_QUOTE_SIZE,
+        ) {
+            update = update.with_quote(quote);
+        }
+        Ok(update)
+    }
+
+    /// Compute a CLMM price update, with depth when the current tick array is known
+    fn clmm_price(
+        &self,
+        session: &PoolSession,
+        slot: u64,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        let Some(pool) = &session.clmm_pool else {
+            return Ok(None);
+        };
+
+        let spec = session.pair.spec();
+        let price = pool.price_of(&spec.base_mint, &spec.quote_mint)?;
+        self.check_price_bounds(session.pair, price)?;
+
+        let mut update =
+            PriceUpdate::new(PriceSource::RaydiumClmm, session.pair, price).with_slot(slot);
+        if let Some(quote) = concentrated_quote(
+            pool.sqrt_price_x64 as f64 / 2f64.powi(64),
+            pool.liquidity,
+            pool.base_is_token0(&spec.base_mint, &spec.quote_mint)?,
+            spec.base_decimals,
+            spec.quote_decimals,
+            AMM_QUOTE_SIZE,
+        ) {
+            update = update.with_quote(quote);
+        }
+        if let Some(liquidity) =
+            Self::clmm_liquidity(session.pair, pool, &session.clmm_tick_arrays)?
+        {
+            update = update.with_liquidity(LiquidityProfile::Concentrated(liquidity));
+        }
+        Ok(Some(update))
+    }
+
+    /// Compute a top-of-book price update once both sides of the book are known
+    fn openbook_price(
+        &self,
+        session: &PoolSession,
+        slot: u64,
+    ) -> Result<Option<PriceUpdate>, SolanaError> {
+        let (Some(market), Some(best_bid), Some(best_ask)) =
+            (&session.openbook_market, session.best_bid, session.best_ask)
+        else {
+            return Ok(None);
+        };
+        let (Some(bid), Some(ask)) = (best_bid, best_ask) else {
+            return Err(SolanaError::PoolParsingError(
+                "OpenBook book side is empty".to_string(),
+            ));
+        };
+
+        let quote = Quote::new(
+            market.price_from_lots(bid.price_lots),
+            market.price_from_lots(ask.price_lots),
+        )
+        .with_sizes(
+            market.size_from_lots(bid.quantity_lots),
+            market.size_from_lots(ask.quantity_lots),
+        );
+        self.check_price_bounds(session.pair, quote.bid)?;
+        self.check_price_bounds(session.pair, quote.ask)?;
+
+        Ok(Some(
+            PriceUpdate::new(PriceSource::OpenBook, session.pair, quote.mid())
+                .with_slot(slot)
+                .with_quote(quote),
+        ))
+    }
+
+    /// Build a concentrated liquidity snapshot from the contiguous tick arrays around the price
+    fn clmm_liquidity(
+        pair: TradingPair,
+        pool: &ClmmPoolState,
+        tick_arrays: &BTreeMap<i32, Vec<(i32, i128)>>,
+    ) -> Result<Option<ConcentratedLiquidity>, SolanaError> {
+        let span = pool.tick_array_span();
+        let current = pool.tick_array_start_index(pool.tick_current);
+        if !tick_arrays.contains_key(&current) {
+            return Ok(None);
+        }
+
+        let mut lower = current;
+        while tick_arrays.contains_key(&(lower - span)) {
+            lower -= span;
+        }
+        let mut upper = current;
+        while tick_arrays.contains_key(&(upper + span)) {
+            upper += span;
+        }
+
+        let ticks = tick_arrays
+            .range(lower..=upper)
+            .flat_map(|(_, ticks)| ticks.iter().copied())
+            .collect();
+
+        Ok(Some(ConcentratedLiquidity {
+            sqrt_price: pool.sqrt_price_x64 as f64 / 2f64.powi(64),
+            liquidity: pool.liquidity,
+            tick_current: pool.tick_current,
+            base_is_token0: pool.base_is_token0(&pair.spec().base_mint, &pair.spec().quote_mint)?,
+            base_decimals: pair.spec().base_decimals,
+            ticks,
+            covered_range: (lower, upper + span),
+        }))
+    }
+
+    /// Drop tick arrays too far from the current price to matter
+    fn prune_tick_arrays(session: &mut PoolSession) {
+        let Some(pool) = &session.clmm_pool else {
+            return;
+        };
+        let span = pool.tick_array_span();
+        let current = pool.tick_array_start_index(pool.tick_current);
+        let window = (current - TICK_ARRAY_WINDOW * span)..=(current + TICK_ARRAY_WINDOW * span);
+        session
+            .clmm_tick_arrays
+            .retain(|start, _| window.contains(start));
+    }
+
+    /// Sanity check - price should be within the pair's bounds, or the current default
+    /// ones
+    fn check_price_bounds(&self, pair: TradingPair, price: f64) -> Result<(), SolanaError> {
+        let default_bounds = match &self.live_settings {
+            Some(settings) => settings.borrow().price_bounds,
+            None => self.config.price_bounds,
+        };
+        let bounds = pair.spec().price_bounds.unwrap_or(default_bounds);
+        if !(bounds.min_price..=bounds.max_price).contains(&price) {
+            return Err(SolanaError::PoolParsingError(format!(
+                "Calculated price {} outside bounds [{}, {}]",
+                price, bounds.min_price, bounds.max_price
+            )));
+        }
+        Ok(())
+    }
+
+    /// Extract and base64-decode account data from a notification
+    fn decode_account_data(account: &AccountValue) -> Result<Vec<u8>, SolanaError> {
+        let account_data = account
+            .data
+            .as_ref()
+            .and_then(|data| {
+                // Account data can be returned as [data, encoding] array or as a string
+                if let Some(array) = data.as_array() {
+                    array.first().and_then(|v| v.as_str())
+                } else {
+                    data.as_str()
+                }
+            })
+            .ok_or(SolanaError::InvalidAccountData)?;
+
+        BASE64_STANDARD
+            .decode(account_data)
+            .map_err(|e| SolanaError::PoolParsingError(format!("Base64 decode error: {}", e)))
+    }
+
+    /// Get the Raydium AMM v4 pool address for trading pair, if one is defined
+    fn get_pool_address(pair: TradingPair) -> Option<&'static str> {
+        pair.spec().amm_pool.as_deref()
+    }
+
+    /// Get the Orca Whirlpool address for trading pair, if one is defined
+    fn get_whirlpool_address(pair: TradingPair) -> Option<&'static str> {
+        pair.spec().whirlpool.as_deref()
+    }
+
+    /// Get the Raydium CLMM pool address for trading pair, if one is defined
+    fn get_clmm_address(pair: TradingPair) -> Option<&'static str> {
+        pair.spec().clmm_pool.as_deref()
+    }
+
+    /// Get the OpenBook v2 market address for trading pair, if one is defined
+    fn get_openbook_market(pair: TradingPair) -> Option<&'static str> {
+        pair.spec().openbook_market.as_deref()
+    }
+
+    /// Get current reconnection attempt count
+    #[allow(dead_code)]
+    pub fn reconnect_attempts(&self) -> usize {
+        self.reconnect_handler.attempt_count()
+    }
+
+    /// Get the primary trading pair
+    #[allow(dead_code)]
+    pub fn trading_pair(&self) -> TradingPair {
+        self.trading_pairs[0]
+    }
+
+    /// Get all monitored trading pairs
+    #[allow(dead_code)]
+    pub fn trading_pairs(&self) -> &[TradingPair] {
+        &self.trading_pairs
+    }
+
+    /// Get current provider name (for testing)
+    #[allow(dead_code)]
+    pub fn current_provider_name(&self) -> &str {
+        if self.current_provider_index < self.config.rpc_providers.len() {
+            &self.config.rpc_providers[self.current_provider_index].name
+        } else {
+            "No provider selected"
+        }
+    }
+
+    /// Get reconnection elapsed time (for testing)
+    #[allow(dead_code)]
+    pub fn reconnect_elapsed_time(&self) -> Option<Duration> {
+        self.reconnect_handler.elapsed_time()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PairSpec;
+    use crate::pair::{USDC_MINT, USDT_MINT, WSOL_MINT};
+    use crate::websocket::reconnect::ReconnectConfig;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_solana_config_creation() {
+        let config = SolanaConfig::default();
+        assert!(!config.rpc_providers.is_empty());
+        assert_eq!(config.connection_timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_solana_client_creation() {
+        let config = SolanaConfig::default();
+        let client = SolanaClient::new(config, TradingPair::SOL_USDT);
+        assert!(client.is_ok());
+
+        let default_client = SolanaClient::with_default(TradingPair::SOL_USDC);
+        assert!(default_client.is_ok());
+    }
+
+    #[test]
+    fn test_empty_providers_error() {
+        let config = SolanaConfig::new(vec![], Duration::from_secs(10));
+        let client = SolanaClient::new(config, TradingPair::SOL_USDT);
+        assert!(matches!(client, Err(SolanaError::NoProvidersAvailable)));
+    }
+
+    #[test]
+    fn test_pool_addresses() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        assert!(client.pool_address(TradingPair::SOL_USDT).is_some());
+        assert_eq!(
+            client.pool_address(TradingPair::SOL_USDT).unwrap(),
+            "7XawhbbxtsRcQA8KTkHT9f9nc6d69UwqCDh6U5EEbEmX"
+        );
+
+        let client2 = SolanaClient::with_default(TradingPair::SOL_USDC).unwrap();
+        assert!(client2.pool_address(TradingPair::SOL_USDC).is_some());
+        assert_eq!(
+            client2.pool_address(TradingPair::SOL_USDC).unwrap(),
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"
+        );
+    }
+
+    #[test]
+    fn test_account_subscribe_message_creation() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        let msg = client.create_account_subscribe_message().unwrap();
+
+        assert_eq!(msg.jsonrpc, "2.0");
+        assert_eq!(msg.method, "accountSubscribe");
+        assert_eq!(msg.id, 1);
+
+        // Verify that encoding is set to "base64"
+        let params = msg.params.as_array().unwrap();
+        let opts = params[1].as_object().unwrap();
+        assert_eq!(opts.get("encoding").unwrap(), "base64");
+    }
+
+    #[test]
+    fn test_provider_switching() {
+        let providers = vec![
+            RpcProvider {
+                name: "Provider1".to_string(),
+                websocket_url: "wss://provider1.com".parse().unwrap(),
+                priority: 1,
+                provider_type: crate::config::RpcProviderType::Public,
+            },
+            RpcProvider {
+                name: "Provider2".to_string(),
+                websocket_url: "wss://provider2.com".parse().unwrap(),
+                priority: 2,
+                provider_type: crate::config::RpcProviderType::Public,
+            },
+        ];
+
+        let config = SolanaConfig::new(providers, Duration::from_secs(10));
+        let mut client = SolanaClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        assert_eq!(client.current_provider_name(), "Provider1");
+        assert!(client.try_next_provider());
+        assert_eq!(client.current_provider_name(), "Provider2");
+        assert!(!client.try_next_provider());
+    }
+
+    #[test]
+    fn test_config_with_reconnect_settings() {
+        let reconnect_config =
+            ReconnectConfig::new(Duration::from_millis(500), Duration::from_secs(30), 1.5);
+
+        let solana_config = SolanaConfig::default().with_reconnect_config(reconnect_config);
+
+        let client = SolanaClient::new(solana_config, TradingPair::SOL_USDT).unwrap();
+
+        assert_eq!(client.reconnect_attempts(), 0);
+        assert!(client.reconnect_elapsed_time().is_none());
+    }
+
+    #[test]
+    fn test_account_address_configuration() {
+        let custom_address = "CustomPoolAddress123456789".to_string();
+        let config = SolanaConfig::default().with_account_address(custom_address.clone());
+
+        assert_eq!(config.account_address, Some(custom_address));
+    }
+
+    fn mint(address: &str) -> [u8; 32] {
+        bs58::decode(address)
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    fn test_pool_state(pair: TradingPair) -> RaydiumPoolState {
+        RaydiumPoolState {
+            status: 6,
+            state: 1,
+            base_decimals: 9,
+            quote_decimals: 6,
+            base_vault: [1u8; 32],
+            quote_vault: [2u8; 32],
+            base_mint: mint(&pair.spec().base_mint),
+            quote_mint: mint(&pair.spec().quote_mint),
+            ..Default::default()
+        }
+    }
+
+    fn notification_frame(subscription: u64, slot: u64, data: &[u8]) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "subscription": subscription,
+                "result": {
+                    "context": { "slot": slot },
+                    "value": {
+                        "data": [BASE64_STANDARD.encode(data), "base64"],
+                        "executable": false,
+                        "lamports": 1000000,
+                        "owner": "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+                        "rentEpoch": 300
+                    }
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn token_account_data(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    fn confirmation_frame(request_id: u64, subscription: u64) -> String {
+        format!(
+            r#"{{"jsonrpc":"2.0","result":{},"id":{}}}"#,
+            subscription, request_id
+        )
+    }
+
+    #[test]
+    fn test_price_from_vault_reserves() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        let mut session = PoolSession::new(client.trading_pair());
+        session.subscribe(
+            client.pool_address(client.trading_pair()).unwrap(),
+            AccountRole::Pool,
+        );
+        assert_eq!(session.take_outgoing().len(), 1);
+
+        client
+            .parse_account_message(&mut session, &confirmation_frame(1, 100))
+            .unwrap();
+
+        // Pool state triggers vault subscriptions but no price yet
+        let pool_data = borsh::to_vec(&test_pool_state(TradingPair::SOL_USDT)).unwrap();
+        let update = client
+            .parse_account_message(&mut session, &notification_frame(100, 10, &pool_data))
+            .unwrap();
+        assert!(update.is_none());
+
+        let outgoing = session.take_outgoing();
+        assert_eq!(outgoing.len(), 2);
+        let vault_address = outgoing[0].params.as_array().unwrap()[0].clone();
+        assert_eq!(
+            vault_address,
+            test_pool_state(TradingPair::SOL_USDT).base_vault_address()
+        );
+
+        client
+            .parse_account_message(&mut session, &confirmation_frame(2, 200))
+            .unwrap();
+        client
+            .parse_account_message(&mut session, &confirmation_frame(3, 300))
+            .unwrap();
+
+        // 1,000 SOL and 200,000 USDT in the vaults
+        let base_frame = notification_frame(200, 11, &token_account_data(1_000_000_000_000));
+        assert!(client
+            .parse_account_message(&mut session, &base_frame)
+            .unwrap()
+            .is_none());
+
+        let quote_frame = notification_frame(300, 12, &token_account_data(200_000_000_000));
+        let price_update = client
+            .parse_account_message(&mut session, &quote_frame)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(price_update.source, PriceSource::Solana);
+        assert_eq!(price_update.pair, TradingPair::SOL_USDT);
+        assert_eq!(price_update.slot, Some(12));
+        assert!((price_update.price - 200.0).abs() < 1e-9);
+
+        // Quoting 1 SOL against the curve brackets the spot price
+        let quote = price_update.quote.unwrap();
+        assert!((quote.ask - 200_000.0 / 999.0).abs() < 1e-9);
+        assert!((quote.bid - 200_000.0 / 1_001.0).abs() < 1e-9);
+
+        let Some(LiquidityProfile::ConstantProduct(pool)) = price_update.liquidity else {
+            panic!("expected constant-product reserves");
+        };
+        assert_eq!(pool.base_reserve, 1_000.0);
+        assert_eq!(pool.quote_reserve, 200_000.0);
+    }
+
+    #[test]
+    fn test_unknown_subscription_ignored() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        let mut session = PoolSession::new(client.trading_pair());
+
+        let frame = notification_frame(999, 1, &token_account_data(1));
+        assert!(matches!(
+            client.parse_account_message(&mut session, &frame),
+            Err(SolanaError::InvalidAccountData)
+        ));
+    }
+
+    #[test]
+    fn test_shared_session_routes_by_pair() {
+        let pairs = vec![TradingPair::SOL_USDT, TradingPair::SOL_USDC];
+        let client = SolanaClient::for_pairs(SolanaConfig::default(), pairs.clone()).unwrap();
+        let sessions = pairs
+            .iter()
+            .map(|&pair| {
+                let mut session = PoolSession::new(pair);
+                session.subscribe(client.pool_address(pair).unwrap(), AccountRole::Pool);
+                session
+            })
+            .collect();
+        let mut shared = SharedSession::new(sessions);
+
+        // Both sessions number their request 1; the socket sees distinct ids
+        let outgoing = shared.take_outgoing();
+        let ids: Vec<u64> = outgoing.iter().map(|request| request.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        client
+            .parse_shared_message(&mut shared, &confirmation_frame(2, 700), SystemTime::now())
+            .unwrap();
+        client
+            .parse_shared_message(&mut shared, &confirmation_frame(1, 600), SystemTime::now())
+            .unwrap();
+
+        // A pool update on the SOL/USDC subscription queues vaults in that session only
+        let pool_data = borsh::to_vec(&test_pool_state(TradingPair::SOL_USDC)).unwrap();
+        client
+            .parse_shared_message(
+                &mut shared,
+                &notification_frame(700, 10, &pool_data),
+                SystemTime::now(),
+            )
+            .unwrap();
+        assert!(shared.sessions[0].take_outgoing().is_empty());
+        assert_eq!(shared.sessions[1].take_outgoing().len(), 2);
+
+        let frame = notification_frame(999, 11, &pool_data);
+        assert!(client
+            .parse_shared_message(&mut shared, &frame, SystemTime::now())
+            .is_err());
+
+        // A SOL/USDT pool is not priced as SOL/USDC
+        let pool_data = borsh::to_vec(&test_pool_state(TradingPair::SOL_USDT)).unwrap();
+        let frame = notification_frame(700, 12, &pool_data);
+        assert!(matches!(
+            client.parse_shared_message(&mut shared, &frame, SystemTime::now()),
+            Err(SolanaError::PoolParsingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_replay_recorded_frames() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        let initial = client.open_shared().unwrap().take_outgoing();
+        assert_eq!(initial[0].method, "accountSubscribe");
+        let vault_id = initial.len() as u64 + 1;
+
+        let mut state = SolanaReplay::default();
+        let at = |ms: u64| UNIX_EPOCH + Duration::from_millis(ms);
+        let mut replay = |f: String, received_ms: u64| {
+            client.replay_frame(&mut state, &Frame::Solana { f }, at(received_ms))
+        };
+
+        let pool_data = borsh::to_vec(&test_pool_state(TradingPair::SOL_USDT)).unwrap();
+        let frames = [
+            (confirmation_frame(1, 100), 1_000_000),
+            (notification_frame(100, 10, &pool_data), 1_000_100),
+            (confirmation_frame(vault_id, 200), 1_000_200),
+            (confirmation_frame(vault_id + 1, 300), 1_000_300),
+            (
+                notification_frame(200, 11, &token_account_data(1_000_000_000_000)),
+                1_000_600,
+            ),
+        ];
+        for (frame, received_ms) in frames {
+            assert!(replay(frame, received_ms).unwrap().is_none());
+        }
+        let quote_frame = notification_frame(300, 12, &token_account_data(200_000_000_000));
+        let update = replay(quote_frame.clone(), 1_000_950).unwrap().unwrap();
+        assert_eq!(update.slot, Some(12));
+        assert!((update.price - 200.0).abs() < 1e-9);
+        // Slot 10 arrived fastest: slot 12 is two nominal slots later
+        assert_eq!(update.timestamp, at(1_000_900));
+        assert_eq!(update.received_at, at(1_000_950));
+
+        // A reconnect drops the old subscriptions
+        client
+            .replay_frame(&mut state, &Frame::SolanaConnected, at(1_001_000))
+            .unwrap();
+        assert!(client
+            .replay_frame(&mut state, &Frame::Solana { f: quote_frame }, at(1_001_000))
+            .is_err());
+    }
+
+    #[test]
+    fn test_address_override_applies_to_primary_pair() {
+        let config = SolanaConfig {
+            account_address: Some("Override111".to_string()),
+            ..SolanaConfig::default()
+        };
+        let client =
+            SolanaClient::for_pairs(config, vec![TradingPair::SOL_USDT, TradingPair::SOL_USDC])
+                .unwrap();
+
+        assert_eq!(
+            client.pool_address(TradingPair::SOL_USDT).unwrap(),
+            "Override111"
+        );
+        assert_eq!(
+            client.pool_address(TradingPair::SOL_USDC).unwrap(),
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"
+        );
+        assert!(SolanaClient::for_pairs(SolanaConfig::default(), vec![]).is_err());
+    }
+
+    #[test]
+    fn test_whirlpool_subscription_and_price() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDC).unwrap();
+        assert_eq!(
+            client.whirlpool_address(client.trading_pair()).as_deref(),
+            Some("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ")
+        );
+
+        let mut session = PoolSession::new(client.trading_pair());
+        session.subscribe(
+            client.whirlpool_address(client.trading_pair()).unwrap(),
+            AccountRole::Whirlpool,
+        );
+        client
+            .parse_account_message(&mut session, &confirmation_frame(1, 500))
+            .unwrap();
+
+        let mut whirlpool = WhirlpoolState {
+            // sqrt(150 * 1e6 / 1e9) in Q64.64
+            sqrt_price: ((0.15f64).sqrt() * 2f64.powi(64)) as u128,
+            ..Default::default()
+        };
+        whirlpool.token_mint_a = mint(WSOL_MINT);
+        whirlpool.token_mint_b = mint(USDC_MINT);
+
+        let frame = notification_frame(500, 42, &borsh::to_vec(&whirlpool).unwrap());
+        let update = client
+            .parse_account_message(&mut session, &frame)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(update.source, PriceSource::Orca);
+        assert_eq!(update.slot, Some(42));
+        assert!((update.price - 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_defined_pair_uses_its_mint_decimals_and_bounds() {
+        const BONK_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+        let spec: PairSpec = format!(
+            "name=bonk-usdc-solana-test,base=bonk,quote=usdc,base_mint={},quote_mint={},base_decimals=5,quote_decimals=6,whirlpool={},min_price=0.0000001,max_price=1",
+            BONK_MINT,
+            crate::pair::USDC_MINT,
+            "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"
+        )
+        .parse()
+        .unwrap();
+        let pair = TradingPair::define(spec).unwrap();
+        let client = SolanaClient::with_default(pair).unwrap();
+        assert!(client.pool_address(pair).is_none());
+
+        let mut whirlpool = WhirlpoolState {
+            // sqrt(0.00002 * 1e6 / 1e5) in Q64.64
+            sqrt_price: ((0.0002f64).sqrt() * 2f64.powi(64)) as u128,
+            ..Default::default()
+        };
+        whirlpool.token_mint_a = mint(BONK_MINT);
+        whirlpool.token_mint_b = mint(USDC_MINT);
+
+        // Outside the default SOL bounds, inside the pair's own
+        let update = client
+            .whirlpool_price(pair, &borsh::to_vec(&whirlpool).unwrap(), 7)
+            .unwrap();
+        assert_eq!(update.pair, pair);
+        assert!((update.price - 0.00002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_price_bounds_follow_live_settings() {
+        let config = crate::test_utils::config::create_test_config();
+        let (settings, receiver) = watch::channel(LiveSettings::from_config(&config));
+        let pair = TradingPair::SOL_USDT;
+        let client = SolanaClient::with_default(pair)
+            .unwrap()
+            .with_live_settings(receiver);
+
+        let mut whirlpool = WhirlpoolState {
+            // sqrt(150 * 1e6 / 1e9) in Q64.64
+            sqrt_price: ((0.15f64).sqrt() * 2f64.powi(64)) as u128,
+            ..Default::default()
+        };
+        whirlpool.token_mint_a = mint(WSOL_MINT);
+        whirlpool.token_mint_b = mint(USDT_MINT);
+        let data = borsh::to_vec(&whirlpool).unwrap();
+        assert!(client.whirlpool_price(pair, &data, 7).is_ok());
+
+        // A reload lowering the maximum price rejects the same pool state
+        settings.send_modify(|settings| {
+            settings.price_bounds = PriceBounds::new(1.0, 100.0).unwrap();
+        });
+        assert!(matches!(
+            client.whirlpool_price(pair, &data, 8),
+            Err(SolanaError::PoolParsingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_whirlpool_address_per_pair() {
+        let client = SolanaClient::with_default(TradingPair::SOL_USDT).unwrap();
+        assert!(client.whirlpool_address(client.trading_pair()).is_none());
+
+        let config = SolanaConfig::default().with_whirlpool_address("CustomWhirlpool".to_string());
+        let client = SolanaClient::new(config, TradingPair::SOL_USDT).unwrap();
+        assert_eq!(
+            client.whirlpool_address(client.trading_pair()).as_deref(),
+            Some("CustomWhirlpool")
+        );
+    }
+
+    fn program_notification_frame(subscription: u64, slot: u64, data: &[u8]) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "programNotification",
+            "params": {
+                "subscription": subscription,
+                "result": {
+                    "context": { "slot": slot },
+                    "value": {
+                        "pubkey": "TickArray1111111111111111111111111111111111",
+                        "account": {
+                            "data": [BASE64_STANDARD.encode(data), "base64"],
+                            "executable": false,
+                            "lamports": 1000000,
+                            "owner": CLMM_PROGRAM_ID,
+                            "rentEpoch": 300
+                        }
+                    }
+                }
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_clmm_price_with_tick_array_depth() {
+        let pool_address = bs58::encode([7u8; 32]).into_string();
+        let config = SolanaConfig::default().with_clmm_address(pool_address.clone());
+        let client = SolanaClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        let mut session = PoolSession::new(client.trading_pair());
+        SolanaClient::subscribe_clmm(&mut session, pool_address.clone());
+        let outgoing = session.take_outgoing();
+        assert_eq!(outgoing.len(), 2);
+        assert_eq!(outgoing[1].method, "programSubscribe");
+        let filters = &outgoing[1].params[1]["filters"];
+        assert_eq!(filters[1]["memcmp"]["bytes"], pool_address.as_str());
+
+        client
+            .parse_account_message(&mut session, &confirmation_frame(1, 700))
+            .unwrap();
+        client
+            .parse_account_message(&mut session, &confirmation_frame(2, 701))
+            .unwrap();
+
+        // SOL is token0 at 150 USDT; tick spacing 10 => 600 ticks per array
+        let mut pool = ClmmPoolState {
+            mint_decimals_0: 9,
+            mint_decimals_1: 6,
+            tick_spacing: 10,
+            liquidity: 1_000_000_000_000,
+            sqrt_price_x64: ((0.15f64).sqrt() * 2f64.powi(64)) as u128,
+            tick_current: -18_971,
+            ..Default::default()
+        };
+        pool.token_mint_0 = mint(WSOL_MINT);
+        pool.token_mint_1 = mint(USDT_MINT);
+        let mut pool_data = borsh::to_vec(&pool).unwrap();
+        pool_data.resize(1544, 0);
+
+        // Price is known before any tick array, but without depth
+        let update = client
+            .parse_account_message(&mut session, &notification_frame(700, 20, &pool_data))
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.source, PriceSource::RaydiumClmm);
+        assert!((update.price - 150.0).abs() < 1e-6);
+        assert!(update.liquidity.is_none());
+        let quote = update.quote.unwrap();
+        assert!(quote.bid < update.price && update.price < quote.ask);
+
+        let mut ticks: [clmm::TickState; clmm::TICKS_PER_ARRAY as usize] =
+            std::array::from_fn(|_| clmm::TickState::default());
+        ticks[0].tick = -19_000;
+        ticks[0].liquidity_net = 1_000_000_000_000;
+        ticks[0].liquidity_gross = 1_000_000_000_000;
+        let tick_array = TickArrayState {
+            discriminator: [0; 8],
+            pool_id: [7; 32],
+            start_tick_index: -19_200,
+            ticks,
+            initialized_tick_count: 1,
+            recent_epoch: 0,
+            padding: [0; 107],
+        };
+        let frame = program_notification_frame(701, 21, &borsh::to_vec(&tick_array).unwrap());
+        let update = client
+            .parse_account_message(&mut session, &frame)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(update.slot, Some(21));
+        let Some(LiquidityProfile::Concentrated(liquidity)) = update.liquidity else {
+            panic!("expected concentrated liquidity");
+        };
+        assert!(liquidity.base_is_token0);
+        assert_eq!(liquidity.ticks, vec![(-19_000, 1_000_000_000_000)]);
+        assert_eq!(liquidity.covered_range, (-19_200, -18_600));
+    }
+
+    #[test]
+    fn test_tick_array_for_other_pool_ignored() {
+        let config = SolanaConfig::default().with_clmm_address("OtherPool".to_string());
+        let client = SolanaClient::new(config, TradingPair::SOL_USDC).unwrap();
+        let mut session = PoolSession::new(client.trading_pair());
+        session.subscribe_program(
+            CLMM_PROGRAM_ID,
+            serde_json::json!([]),
+            AccountRole::ClmmTickArrays,
+        );
+        client
+            .parse_account_message(&mut session, &confirmation_frame(1, 5))
+            .unwrap();
+
+        let tick_array = TickArrayState {
+            discriminator: [0; 8],
+            pool_id: [7; 32],
+            start_tick_index: 0,
+            ticks: std::array::from_fn(|_| clmm::TickState::default()),
+            initialized_tick_count: 0,
+            recent_epoch: 0,
+            padding: [0; 107],
+        };
+        let frame = program_notification_frame(5, 1, &borsh::to_vec(&tick_array).unwrap());
+        assert!(matches!(
+            client.parse_account_message(&mut session, &frame),
+            Err(SolanaError::InvalidAccountData)
+        ));
+    }
+
+    #[test]
+    fn test_openbook_top_of_book() {
+        let config = SolanaConfig::default().with_openbook_market("Market".to_string());
+        let client = SolanaClient::new(config, TradingPair::SOL_USDT).unwrap();
+
+        let mut session = PoolSession::new(client.trading_pair());
+        session.subscribe(
+            client
+                .openbook_market_address(client.trading_pair())
+                .unwrap(),
+            AccountRole::OpenBookMarket,
+        );
+        client
+            .parse_account_message(&mut session, &confirmation_frame(1, 800))
+            .unwrap();
+
+        // Market decode subscribes to both book sides
+        let market_data = borsh::to_vec(&openbook::test_market()).unwrap();
+        assert!(client
+            .parse_account_message(&mut session, &notification_frame(800, 30, &market_data))
+            .unwrap()
+            .is_none());
+        let outgoing = session.take_outgoing();
+        assert_eq!(outgoing.len(), 3);
+        assert_eq!(
+            outgoing[1].params[0],
+            bs58::encode([1u8; 32]).into_string().as_str()
+        );
+
+        client
+            .parse_account_message(&mut session, &confirmation_frame(2, 801))
+            .unwrap();
+        client
+            .parse_account_message(&mut session, &confirmation_frame(3, 802))
+            .unwrap();
+
+        // Bid 149.9 x 2 SOL, ask 150.1 x 3 SOL (1 lot = 0.001 SOL)
+        let bids = openbook::single_order_book_side(149_900, 2_000);
+        assert!(client
+            .parse_account_message(&mut session, &notification_frame(801, 31, &bids))
+            .unwrap()
+            .is_none());
+
+        let asks = openbook::single_order_book_side(150_100, 3_000);
+        let update = client
+            .parse_account_message(&mut session, &notification_frame(802, 32, &asks))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(update.source, PriceSource::OpenBook);
+        assert!((update.price - 150.0).abs() < 1e-9);
+        let quote = update.quote.unwrap();
+        assert!((quote.bid - 149.9).abs() < 1e-9);
+        assert_eq!(quote.bid_size, Some(2.0));
+        assert!((quote.ask_size.unwrap() - 3.0).abs() < 1e-12);
+        assert!(update.liquidity.is_none());
+    }
+}
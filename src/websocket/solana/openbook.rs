@@ -0,0 +1,356 @@
+use super::SolanaError;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Leading fields of an OpenBook v2 `Market` account
+///
+/// Only the fields up to the base and quote mints are decoded.
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct OpenBookMarket {
+    /// Anchor account discriminator
+    pub discriminator: [u8; 8],
+    /// PDA bump
+    pub bump: u8,
+    /// Base token decimals
+    pub base_decimals: u8,
+    /// Quote token decimals
+    pub quote_decimals: u8,
+    /// Padding
+    pub padding1: [u8; 5],
+    /// Market authority
+    pub market_authority: [u8; 32],
+    /// Market expiry (unix seconds, 0 = never)
+    pub time_expiry: i64,
+    /// Fee collection admin
+    pub collect_fee_admin: [u8; 32],
+    /// Optional open orders admin (zeroed if unset)
+    pub open_orders_admin: [u8; 32],
+    /// Optional consume events admin (zeroed if unset)
+    pub consume_events_admin: [u8; 32],
+    /// Optional close market admin (zeroed if unset)
+    pub close_market_admin: [u8; 32],
+    /// Market name, NUL padded
+    pub name: [u8; 16],
+    /// Bids book side account
+    pub bids: [u8; 32],
+    /// Asks book side account
+    pub asks: [u8; 32],
+    /// Event heap account
+    pub event_heap: [u8; 32],
+    /// Oracle A (zeroed if unset)
+    pub oracle_a: [u8; 32],
+    /// Oracle B (zeroed if unset)
+    pub oracle_b: [u8; 32],
+    /// Oracle configuration, kept raw
+    pub oracle_config: [u8; 88],
+    /// Quote lot size in quote atoms
+    pub quote_lot_size: i64,
+    /// Base lot size in base atoms
+    pub base_lot_size: i64,
+    /// Sequence number of the next order
+    pub seq_num: u64,
+    /// Market creation time (unix seconds)
+    pub registration_time: i64,
+    /// Maker fee, kept raw
+    pub maker_fee: i64,
+    /// Taker fee, kept raw
+    pub taker_fee: i64,
+    /// Fee accounting and volume counters, kept raw
+    pub fees_and_volumes: [u8; 80],
+    /// Base token mint
+    pub base_mint: [u8; 32],
+    /// Quote token mint
+    pub quote_mint: [u8; 32],
+}
+
+impl OpenBookMarket {
+    /// Size of the decoded leading fields in bytes
+    pub const HEADER_LEN: usize = 640;
+
+    /// Decode the market from raw account data
+    pub fn decode(data: &[u8]) -> Result<Self, SolanaError> {
+        let bytes = data.get(..Self::HEADER_LEN).ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for OpenBook market".to_string())
+        })?;
+        let market = Self::try_from_slice(bytes).map_err(|e| {
+            SolanaError::PoolParsingError(format!("OpenBook market decode error: {}", e))
+        })?;
+
+        if market.base_lot_size <= 0 || market.quote_lot_size <= 0 {
+            return Err(SolanaError::PoolParsingError(
+                "OpenBook market has invalid lot sizes".to_string(),
+            ));
+        }
+        Ok(market)
+    }
+
+    /// Check that the market trades `base_mint` against `quote_mint`
+    ///
+    /// Lot conversions assume the pair's base is the market's base, so a market listed
+    /// the other way around is refused rather than priced inverted.
+    pub fn check_mints(&self, base_mint: &str, quote_mint: &str) -> Result<(), SolanaError> {
+        let market_base = bs58::encode(self.base_mint).into_string();
+        let market_quote = bs58::encode(self.quote_mint).into_string();
+        if market_base != base_mint || market_quote != quote_mint {
+            return Err(SolanaError::PoolParsingError(format!(
+                "OpenBook market trades {}/{}, not {}/{}",
+                market_base, market_quote, base_mint, quote_mint
+            )));
+        }
+        Ok(())
+    }
+
+    /// Bids account address in base58 form
+    pub fn bids_address(&self) -> String {
+        bs58::encode(self.bids).into_string()
+    }
+
+    /// Asks account address in base58 form
+    pub fn asks_address(&self) -> String {
+        bs58::encode(self.asks).into_string()
+    }
+
+    /// Convert a price in lots to quote tokens per base token
+    pub fn price_from_lots(&self, price_lots: u64) -> f64 {
+        price_lots as f64 * self.quote_lot_size as f64 * 10f64.powi(self.base_decimals as i32)
+            / (self.base_lot_size as f64 * 10f64.powi(self.quote_decimals as i32))
+    }
+
+    /// Convert a quantity in base lots to base tokens
+    pub fn size_from_lots(&self, base_lots: i64) -> f64 {
+        base_lots as f64 * self.base_lot_size as f64 / 10f64.powi(self.base_decimals as i32)
+    }
+}
+
+/// Side of an order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSideKind {
+    Bids,
+    Asks,
+}
+
+/// Best resting order on one side of the book, in lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookLevel {
+    pub price_lots: u64,
+    pub quantity_lots: i64,
+}
+
+/// Offset of the fixed-price order tree root (after the discriminator)
+const FIXED_ROOT_OFFSET: usize = 8;
+/// Offset of the node array: discriminator, 6 roots, reserved bytes and the nodes header
+const NODES_OFFSET: usize = 8 + 6 * 8 + 256 + 16 + 512;
+/// Size of a single tree node
+const NODE_SIZE: usize = 88;
+/// Number of nodes in a book side
+const MAX_NODES: usize = 1024;
+
+const INNER_NODE_TAG: u8 = 1;
+const LEAF_NODE_TAG: u8 = 2;
+
+/// Size of an OpenBook v2 `BookSide` account in bytes
+pub const BOOK_SIDE_LEN: usize = NODES_OFFSET + MAX_NODES * NODE_SIZE;
+
+/// Find the best fixed-price order on an OpenBook v2 book side
+///
+/// The book is a crit-bit tree keyed by `(price_lots << 64) | seq_num`; the best
+/// bid is the largest key and the best ask is the smallest.
+pub fn best_level(data: &[u8], side: BookSideKind) -> Result<Option<BookLevel>, SolanaError> {
+    if data.len() < BOOK_SIDE_LEN {
+        return Err(SolanaError::PoolParsingError(
+            "Account data too short for OpenBook book side".to_string(),
+        ));
+    }
+
+    let root = read_u32(data, FIXED_ROOT_OFFSET);
+    let leaf_count = read_u32(data, FIXED_ROOT_OFFSET + 4);
+    if leaf_count == 0 {
+        return Ok(None);
+    }
+
+    // Bids follow the higher child, asks the lower one
+    let child = match side {
+        BookSideKind::Bids => 1,
+        BookSideKind::Asks => 0,
+    };
+
+    let mut handle = root as usize;
+    for _ in 0..MAX_NODES {
+        if handle >= MAX_NODES {
+            break;
+        }
+        let node = &data[NODES_OFFSET + handle * NODE_SIZE..][..NODE_SIZE];
+        match node[0] {
+            INNER_NODE_TAG => handle = read_u32(node, 24 + child * 4) as usize,
+            LEAF_NODE_TAG => {
+                let key = u128::from_le_bytes(node[8..24].try_into().unwrap_or_default());
+                let quantity = i64::from_le_bytes(node[56..64].try_into().unwrap_or_default());
+                return Ok(Some(BookLevel {
+                    price_lots: (key >> 64) as u64,
+                    quantity_lots: quantity,
+                }));
+            }
+            tag => {
+                return Err(SolanaError::PoolParsingError(format!(
+                    "Unexpected OpenBook node tag {}",
+                    tag
+                )))
+            }
+        }
+    }
+
+    Err(SolanaError::PoolParsingError(
+        "Malformed OpenBook order tree".to_string(),
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap_or_default())
+}
+
+/// Book side account holding a single fixed-price order
+#[cfg(test)]
+pub(super) fn single_order_book_side(price_lots: u64, quantity_lots: i64) -> Vec<u8> {
+    let mut data = vec![0u8; BOOK_SIDE_LEN];
+    data[FIXED_ROOT_OFFSET + 4..FIXED_ROOT_OFFSET + 8].copy_from_slice(&1u32.to_le_bytes());
+
+    let node = &mut data[NODES_OFFSET..][..NODE_SIZE];
+    node[0] = LEAF_NODE_TAG;
+    node[8..24].copy_from_slice(&((price_lots as u128) << 64).to_le_bytes());
+    node[56..64].copy_from_slice(&quantity_lots.to_le_bytes());
+    data
+}
+
+/// SOL/USDT market with bids at `[1; 32]` and asks at `[2; 32]`
+#[cfg(test)]
+pub(super) fn test_market() -> OpenBookMarket {
+    let mint = |address: &str| -> [u8; 32] {
+        bs58::decode(address)
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap()
+    };
+    OpenBookMarket {
+        discriminator: [0; 8],
+        bump: 0,
+        base_decimals: 9,
+        quote_decimals: 6,
+        padding1: [0; 5],
+        market_authority: [0; 32],
+        time_expiry: 0,
+        collect_fee_admin: [0; 32],
+        open_orders_admin: [0; 32],
+        consume_events_admin: [0; 32],
+        close_market_admin: [0; 32],
+        name: [0; 16],
+        bids: [1; 32],
+        asks: [2; 32],
+        event_heap: [0; 32],
+        oracle_a: [0; 32],
+        oracle_b: [0; 32],
+        oracle_config: [0; 88],
+        quote_lot_size: 1,
+        base_lot_size: 1_000_000,
+        seq_num: 0,
+        registration_time: 0,
+        maker_fee: 0,
+        taker_fee: 0,
+        fees_and_volumes: [0; 80],
+        base_mint: mint(crate::pair::WSOL_MINT),
+        quote_mint: mint(crate::pair::USDT_MINT),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_inner(data: &mut [u8], handle: usize, key: u128, children: [u32; 2]) {
+        let node = &mut data[NODES_OFFSET + handle * NODE_SIZE..][..NODE_SIZE];
+        node[0] = INNER_NODE_TAG;
+        node[8..24].copy_from_slice(&key.to_le_bytes());
+        node[24..28].copy_from_slice(&children[0].to_le_bytes());
+        node[28..32].copy_from_slice(&children[1].to_le_bytes());
+    }
+
+    fn write_leaf(data: &mut [u8], handle: usize, price_lots: u64, seq: u64, quantity: i64) {
+        let node = &mut data[NODES_OFFSET + handle * NODE_SIZE..][..NODE_SIZE];
+        node[0] = LEAF_NODE_TAG;
+        let key = ((price_lots as u128) << 64) | seq as u128;
+        node[8..24].copy_from_slice(&key.to_le_bytes());
+        node[56..64].copy_from_slice(&quantity.to_le_bytes());
+    }
+
+    /// Book side with three orders at 149, 150 and 151 lots
+    fn test_book_side() -> Vec<u8> {
+        let mut data = vec![0u8; BOOK_SIDE_LEN];
+        data[FIXED_ROOT_OFFSET..FIXED_ROOT_OFFSET + 4].copy_from_slice(&0u32.to_le_bytes());
+        data[FIXED_ROOT_OFFSET + 4..FIXED_ROOT_OFFSET + 8].copy_from_slice(&3u32.to_le_bytes());
+
+        write_inner(&mut data, 0, 0, [1, 2]);
+        write_leaf(&mut data, 1, 149, 1, 5);
+        write_inner(&mut data, 2, 0, [3, 4]);
+        write_leaf(&mut data, 3, 150, 2, 7);
+        write_leaf(&mut data, 4, 151, 3, 9);
+        data
+    }
+
+    #[test]
+    fn test_best_bid_and_ask() {
+        let data = test_book_side();
+
+        let bid = best_level(&data, BookSideKind::Bids).unwrap().unwrap();
+        assert_eq!(bid.price_lots, 151);
+        assert_eq!(bid.quantity_lots, 9);
+
+        let ask = best_level(&data, BookSideKind::Asks).unwrap().unwrap();
+        assert_eq!(ask.price_lots, 149);
+        assert_eq!(ask.quantity_lots, 5);
+    }
+
+    #[test]
+    fn test_single_order_book_side() {
+        let data = single_order_book_side(42, 3);
+        let level = best_level(&data, BookSideKind::Asks).unwrap().unwrap();
+        assert_eq!(level.price_lots, 42);
+        assert_eq!(level.quantity_lots, 3);
+    }
+
+    #[test]
+    fn test_empty_and_truncated_book() {
+        let data = vec![0u8; BOOK_SIDE_LEN];
+        assert!(best_level(&data, BookSideKind::Bids).unwrap().is_none());
+        assert!(best_level(&data[..100], BookSideKind::Asks).is_err());
+    }
+
+    #[test]
+    fn test_market_layout_and_lot_conversion() {
+        let market = test_market();
+        let data = borsh::to_vec(&market).unwrap();
+        assert_eq!(data.len(), OpenBookMarket::HEADER_LEN);
+        assert_eq!(&data[200..232], &[1u8; 32]);
+        assert_eq!(&data[232..264], &[2u8; 32]);
+
+        let decoded = OpenBookMarket::decode(&data).unwrap();
+        assert_eq!(
+            decoded.bids_address(),
+            bs58::encode([1u8; 32]).into_string()
+        );
+
+        assert_eq!(&data[576..608], &decoded.base_mint);
+        assert!(decoded
+            .check_mints(crate::pair::WSOL_MINT, crate::pair::USDT_MINT)
+            .is_ok());
+        assert!(decoded
+            .check_mints(crate::pair::USDT_MINT, crate::pair::WSOL_MINT)
+            .is_err());
+        assert!(decoded
+            .check_mints(crate::pair::WSOL_MINT, crate::pair::USDC_MINT)
+            .is_err());
+
+        // 1 lot = 0.001 SOL priced in 0.000001 USDT units
+        assert!((decoded.price_from_lots(150_000) - 150.0).abs() < 1e-9);
+        assert!((decoded.size_from_lots(2_500) - 2.5).abs() < 1e-12);
+    }
+}
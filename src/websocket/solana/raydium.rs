@@ -0,0 +1,341 @@
+use super::{base_is_first_mint, SolanaError};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Raydium AMM v4 pool state (LIQUIDITY_STATE_LAYOUT_V4, 752 bytes)
+///
+/// The pool state only describes the pool; live reserves are held in the
+/// base/quote vault token accounts and must be read from there.
+#[derive(Debug, Clone, Default, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct RaydiumPoolState {
+    /// Pool status (should be 6 for active pools)
+    pub status: u64,
+    /// Pool nonce for PDA derivation
+    pub nonce: u64,
+    /// Max order volume
+    pub max_order: u64,
+    /// Pool depth
+    pub depth: u64,
+    /// Base token decimals
+    pub base_decimals: u64,
+    /// Quote token decimals
+    pub quote_decimals: u64,
+    /// Pool state (should be 1 for initialized)
+    pub state: u64,
+    /// Reset flag
+    pub reset_flag: u64,
+    /// Minimum size
+    pub min_size: u64,
+    /// Volume multiplier
+    pub vol_max_cut_ratio: u64,
+    /// Amount wave ratio
+    pub amount_wave_ratio: u64,
+    /// Base lot size
+    pub base_lot_size: u64,
+    /// Quote lot size
+    pub quote_lot_size: u64,
+    /// Minimum price multiplier
+    pub min_price_multiplier: u64,
+    /// Maximum price multiplier
+    pub max_price_multiplier: u64,
+    /// System decimals value
+    pub system_decimals_value: u64,
+    /// Minimum separate numerator
+    pub min_separate_numerator: u64,
+    /// Minimum separate denominator
+    pub min_separate_denominator: u64,
+    /// Trade fee numerator
+    pub trade_fee_numerator: u64,
+    /// Trade fee denominator
+    pub trade_fee_denominator: u64,
+    /// Pnl numerator
+    pub pnl_numerator: u64,
+    /// Pnl denominator
+    pub pnl_denominator: u64,
+    /// Swap fee numerator
+    pub swap_fee_numerator: u64,
+    /// Swap fee denominator
+    pub swap_fee_denominator: u64,
+    /// Base need take pnl (owed to the protocol, still held in the base vault)
+    pub base_need_take_pnl: u64,
+    /// Quote need take pnl (owed to the protocol, still held in the quote vault)
+    pub quote_need_take_pnl: u64,
+    /// Quote total pnl
+    pub quote_total_pnl: u64,
+    /// Base total pnl
+    pub base_total_pnl: u64,
+    /// Pool open time (unix seconds)
+    pub pool_open_time: u64,
+    /// Punish quote amount
+    pub punish_pc_amount: u64,
+    /// Punish base amount
+    pub punish_coin_amount: u64,
+    /// Orderbook init time
+    pub orderbook_to_init_time: u64,
+    /// Swap base in amount
+    pub swap_base_in_amount: u128,
+    /// Swap quote out amount
+    pub swap_quote_out_amount: u128,
+    /// Swap base to quote fee
+    pub swap_base2quote_fee: u64,
+    /// Swap quote in amount
+    pub swap_quote_in_amount: u128,
+    /// Swap base out amount
+    pub swap_base_out_amount: u128,
+    /// Swap quote to base fee
+    pub swap_quote2base_fee: u64,
+    /// Base vault key (32 bytes)
+    pub base_vault: [u8; 32],
+    /// Quote vault key (32 bytes)
+    pub quote_vault: [u8; 32],
+    /// Base mint key (32 bytes)
+    pub base_mint: [u8; 32],
+    /// Quote mint key (32 bytes)
+    pub quote_mint: [u8; 32],
+    /// LP mint key (32 bytes)
+    pub lp_mint: [u8; 32],
+    /// OpenBook open orders key (32 bytes)
+    pub open_orders: [u8; 32],
+    /// Market key (32 bytes)
+    pub market_id: [u8; 32],
+    /// Market program key (32 bytes)
+    pub market_program_id: [u8; 32],
+    /// Target orders key (32 bytes)
+    pub target_orders: [u8; 32],
+    /// Withdraw queue key (32 bytes)
+    pub withdraw_queue: [u8; 32],
+    /// LP vault key (32 bytes)
+    pub lp_vault: [u8; 32],
+    /// Owner key (32 bytes)
+    pub owner: [u8; 32],
+    /// LP reserve
+    pub lp_reserve: u64,
+    /// Padding to match expected layout
+    pub padding: [u64; 3],
+}
+
+impl RaydiumPoolState {
+    /// Size of the on-chain account in bytes
+    pub const LEN: usize = 752;
+
+    /// Decode a pool state from raw account data
+    pub fn decode(data: &[u8]) -> Result<Self, SolanaError> {
+        let bytes = data.get(..Self::LEN).ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for pool state".to_string())
+        })?;
+        Self::try_from_slice(bytes)
+            .map_err(|e| SolanaError::PoolParsingError(format!("Pool state decode error: {}", e)))
+    }
+
+    /// Tradable reserves in whole tokens, excluding pnl owed to the protocol
+    fn reserves(&self, base_vault_amount: u64, quote_vault_amount: u64) -> (f64, f64) {
+        let base_reserve = base_vault_amount.saturating_sub(self.base_need_take_pnl);
+        let quote_reserve = quote_vault_amount.saturating_sub(self.quote_need_take_pnl);
+
+        // Convert token amounts to f64 accounting for decimals
+        (
+            base_reserve as f64 / 10f64.powi(self.base_decimals as i32),
+            quote_reserve as f64 / 10f64.powi(self.quote_decimals as i32),
+        )
+    }
+
+    /// Whether the pair's base token is the pool's base (coin) token, given the pair's
+    /// base and quote mints
+    ///
+    /// User-defined pairs may point at a pool that lists the pair's quote token as its
+    /// base, in which case the vaults are read the other way around.
+    pub fn base_is_coin(&self, base_mint: &str, quote_mint: &str) -> Result<bool, SolanaError> {
+        base_is_first_mint(
+            "Raydium pool",
+            &self.base_mint,
+            &self.quote_mint,
+            base_mint,
+            quote_mint,
+        )
+    }
+
+    /// Tradable reserves of the pair's base and quote tokens from the pool's vault
+    /// balances, in whole tokens
+    pub fn pair_reserves(
+        &self,
+        base_mint: &str,
+        quote_mint: &str,
+        base_vault_amount: u64,
+        quote_vault_amount: u64,
+    ) -> Result<(f64, f64), SolanaError> {
+        let (coin, pc) = self.reserves(base_vault_amount, quote_vault_amount);
+        if self.base_is_coin(base_mint, quote_mint)? {
+            Ok((coin, pc))
+        } else {
+            Ok((pc, coin))
+        }
+    }
+
+    /// Calculate price of the pair's base token in its quote token from vault balances
+    /// Price = (quote_vault - quote_need_take_pnl) / (base_vault - base_need_take_pnl),
+    /// with the vaults swapped when the pool lists the pair the other way around
+    pub fn calculate_price(
+        &self,
+        base_mint: &str,
+        quote_mint: &str,
+        base_vault_amount: u64,
+        quote_vault_amount: u64,
+    ) -> Result<f64, SolanaError> {
+        let (base_amount, quote_amount) =
+            self.pair_reserves(base_mint, quote_mint, base_vault_amount, quote_vault_amount)?;
+
+        if base_amount == 0.0 {
+            return Err(SolanaError::PoolParsingError(
+                "Base token reserve is zero".to_string(),
+            ));
+        }
+
+        Ok(quote_amount / base_amount)
+    }
+
+    /// Swap fee as a fraction of the input amount (trade_fee_numerator / denominator)
+    pub fn fee_rate(&self) -> f64 {
+        if self.trade_fee_denominator == 0 {
+            return 0.0;
+        }
+        self.trade_fee_numerator as f64 / self.trade_fee_denominator as f64
+    }
+
+    /// Validate that this is an active pool
+    pub fn is_active(&self) -> bool {
+        self.status == 6 && self.state == 1
+    }
+
+    /// Base vault address in base58 form
+    pub fn base_vault_address(&self) -> String {
+        bs58::encode(self.base_vault).into_string()
+    }
+
+    /// Quote vault address in base58 form
+    pub fn quote_vault_address(&self) -> String {
+        bs58::encode(self.quote_vault).into_string()
+    }
+}
+
+/// Offset of the `amount` field in an SPL token account (after mint and owner)
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// Read the token balance from raw SPL token account data
+pub fn decode_token_account_amount(data: &[u8]) -> Result<u64, SolanaError> {
+    let bytes = data
+        .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for token account".to_string())
+        })?;
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{USDC_MINT, USDT_MINT, WSOL_MINT};
+
+    fn mint(address: &str) -> [u8; 32] {
+        bs58::decode(address)
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    fn test_pool_state() -> RaydiumPoolState {
+        RaydiumPoolState {
+            status: 6,
+            state: 1,
+            base_decimals: 9,
+            quote_decimals: 6,
+            base_vault: [1u8; 32],
+            quote_vault: [2u8; 32],
+            base_mint: mint(WSOL_MINT),
+            quote_mint: mint(USDT_MINT),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pool_state_layout_size() {
+        let data = borsh::to_vec(&test_pool_state()).unwrap();
+        assert_eq!(data.len(), RaydiumPoolState::LEN);
+
+        // Vault keys sit at the documented V4 offsets
+        assert_eq!(&data[336..368], &[1u8; 32]);
+        assert_eq!(&data[368..400], &[2u8; 32]);
+    }
+
+    #[test]
+    fn test_fee_rate() {
+        let pool_state = RaydiumPoolState {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            ..test_pool_state()
+        };
+        assert!((pool_state.fee_rate() - 0.0025).abs() < 1e-12);
+        assert_eq!(test_pool_state().fee_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_token_account_amount_decoding() {
+        assert_eq!(
+            decode_token_account_amount(&{
+                let mut data = vec![0u8; 165];
+                data[64..72].copy_from_slice(&42u64.to_le_bytes());
+                data
+            })
+            .unwrap(),
+            42
+        );
+        assert!(decode_token_account_amount(&[0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_need_take_pnl_excluded_from_reserves() {
+        let pool_state = RaydiumPoolState {
+            base_need_take_pnl: 100_000_000_000,
+            quote_need_take_pnl: 10_000_000_000,
+            ..test_pool_state()
+        };
+
+        // (210,000 - 10,000) USDT / (1,100 - 100) SOL = 200
+        let price = pool_state
+            .calculate_price(WSOL_MINT, USDT_MINT, 1_100_000_000_000, 210_000_000_000)
+            .unwrap();
+        assert!((price - 200.0).abs() < 1e-9);
+
+        assert!(pool_state
+            .calculate_price(WSOL_MINT, USDT_MINT, 100_000_000_000, 210_000_000_000)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pool_orientation_follows_the_pair() {
+        // A pool listing USDT as its base token: 200,000 USDT against 1,000 SOL
+        let pool_state = RaydiumPoolState {
+            base_decimals: 6,
+            quote_decimals: 9,
+            base_mint: mint(USDT_MINT),
+            quote_mint: mint(WSOL_MINT),
+            ..test_pool_state()
+        };
+        let (base, quote) = pool_state
+            .pair_reserves(WSOL_MINT, USDT_MINT, 200_000_000_000, 1_000_000_000_000)
+            .unwrap();
+        assert_eq!((base, quote), (1_000.0, 200_000.0));
+        let price = pool_state
+            .calculate_price(WSOL_MINT, USDT_MINT, 200_000_000_000, 1_000_000_000_000)
+            .unwrap();
+        assert!((price - 200.0).abs() < 1e-9);
+
+        // A pool for another quote token is refused
+        assert!(matches!(
+            pool_state.base_is_coin(WSOL_MINT, USDC_MINT),
+            Err(SolanaError::PoolParsingError(_))
+        ));
+    }
+}
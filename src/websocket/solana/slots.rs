@@ -0,0 +1,104 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Nominal Solana slot duration
+const SLOT_MS: i64 = 400;
+
+/// Slots after which the estimate moves to the fastest arrival of the last window
+///
+/// Real slots run a little slower than nominal, so an old anchor drifts early; a short
+/// window bounds the drift to a fraction of a slot.
+const WINDOW_SLOTS: u64 = 10;
+
+/// Maps Solana slots to wall-clock time
+///
+/// Notifications arrive some time after their slot. Assuming nominal slot times, each
+/// notification implies when slot 0 would have started; the earliest implied origin
+/// is the lowest-latency observation and anchors the mapping.
+#[derive(Debug, Default)]
+pub struct SlotClock {
+    /// Wall-clock milliseconds of slot 0 at the nominal rate
+    origin_ms: Option<i64>,
+    /// First slot of the current window
+    window_start: u64,
+    /// Earliest origin implied during the current window
+    window_origin_ms: i64,
+}
+
+impl SlotClock {
+    /// Record that a notification for `slot` was received at `received_at`
+    pub fn observe(&mut self, slot: u64, received_at: SystemTime) {
+        let origin = millis(received_at) - slot as i64 * SLOT_MS;
+        let Some(current) = self.origin_ms else {
+            self.origin_ms = Some(origin);
+            self.window_start = slot;
+            self.window_origin_ms = origin;
+            return;
+        };
+
+        // An earlier arrival than estimated means the estimate was late
+        self.origin_ms = Some(current.min(origin));
+        self.window_origin_ms = self.window_origin_ms.min(origin);
+        if slot >= self.window_start + WINDOW_SLOTS {
+            self.origin_ms = Some(self.window_origin_ms);
+            self.window_start = slot;
+            self.window_origin_ms = origin;
+        }
+    }
+
+    /// Estimated wall-clock time of `slot`, once a slot has been observed
+    pub fn time_of(&self, slot: u64) -> Option<SystemTime> {
+        let ms = self.origin_ms? + slot as i64 * SLOT_MS;
+        Some(UNIX_EPOCH + Duration::from_millis(ms.max(0) as u64))
+    }
+}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_slot_times_follow_fastest_arrival() {
+        let mut clock = SlotClock::default();
+        assert!(clock.time_of(100).is_none());
+
+        clock.observe(100, at(1_000_150));
+        assert_eq!(clock.time_of(100), Some(at(1_000_150)));
+        assert_eq!(clock.time_of(102), Some(at(1_000_950)));
+
+        // A slower notification keeps the estimate, a faster one tightens it
+        clock.observe(101, at(1_000_700));
+        assert_eq!(clock.time_of(101), Some(at(1_000_550)));
+        clock.observe(102, at(1_000_850));
+        assert_eq!(clock.time_of(102), Some(at(1_000_850)));
+    }
+
+    #[test]
+    fn test_slow_slots_move_the_estimate_each_window() {
+        let mut clock = SlotClock::default();
+        // Slots of 440ms, each received 50ms after it started
+        for slot in 0..=30 {
+            clock.observe(slot, at(1_000_050 + slot * 440));
+        }
+
+        // The estimate trails by at most two windows of drift
+        let received = at(1_000_050 + 30 * 440);
+        let estimate = clock.time_of(30).unwrap();
+        let lag = received.duration_since(estimate).unwrap();
+        assert!(
+            lag <= Duration::from_millis(2 * WINDOW_SLOTS * 40),
+            "{:?}",
+            lag
+        );
+        assert!(lag < Duration::from_millis(30 * 40));
+    }
+}
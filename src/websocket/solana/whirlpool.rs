@@ -0,0 +1,203 @@
+use super::{base_is_first_mint, price_from_sqrt_price_x64, SolanaError};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Reward configuration embedded in a Whirlpool account
+#[derive(Debug, Clone, Default, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct WhirlpoolRewardInfo {
+    /// Reward token mint
+    pub mint: [u8; 32],
+    /// Reward token vault
+    pub vault: [u8; 32],
+    /// Authority allowed to set reward emissions
+    pub authority: [u8; 32],
+    /// Q64.64 emissions per second
+    pub emissions_per_second_x64: u128,
+    /// Q64.64 global reward growth
+    pub growth_global_x64: u128,
+}
+
+/// Orca Whirlpool (concentrated liquidity) account state, 653 bytes
+///
+/// Price is encoded as the Q64.64 square root of token B atoms per token A atom.
+#[derive(Debug, Clone, Default, BorshDeserialize, BorshSerialize)]
+#[allow(dead_code)]
+pub struct WhirlpoolState {
+    /// Anchor account discriminator
+    pub discriminator: [u8; 8],
+    /// Whirlpools config account
+    pub whirlpools_config: [u8; 32],
+    /// PDA bump
+    pub whirlpool_bump: [u8; 1],
+    /// Tick spacing
+    pub tick_spacing: u16,
+    /// Tick spacing seed
+    pub tick_spacing_seed: [u8; 2],
+    /// Fee rate in hundredths of a basis point (e.g. 400 = 0.04%)
+    pub fee_rate: u16,
+    /// Protocol share of the fee in basis points
+    pub protocol_fee_rate: u16,
+    /// Liquidity currently in range
+    pub liquidity: u128,
+    /// Q64.64 square root of the current price
+    pub sqrt_price: u128,
+    /// Current tick index
+    pub tick_current_index: i32,
+    /// Protocol fees owed in token A
+    pub protocol_fee_owed_a: u64,
+    /// Protocol fees owed in token B
+    pub protocol_fee_owed_b: u64,
+    /// Token A mint (32 bytes)
+    pub token_mint_a: [u8; 32],
+    /// Token A vault (32 bytes)
+    pub token_vault_a: [u8; 32],
+    /// Q64.64 global fee growth for token A
+    pub fee_growth_global_a: u128,
+    /// Token B mint (32 bytes)
+    pub token_mint_b: [u8; 32],
+    /// Token B vault (32 bytes)
+    pub token_vault_b: [u8; 32],
+    /// Q64.64 global fee growth for token B
+    pub fee_growth_global_b: u128,
+    /// Last reward update (unix seconds)
+    pub reward_last_updated_timestamp: u64,
+    /// Reward emissions configuration
+    pub reward_infos: [WhirlpoolRewardInfo; 3],
+}
+
+impl WhirlpoolState {
+    /// Size of the on-chain account in bytes
+    pub const LEN: usize = 653;
+
+    /// Decode a Whirlpool account from raw account data
+    pub fn decode(data: &[u8]) -> Result<Self, SolanaError> {
+        let bytes = data.get(..Self::LEN).ok_or_else(|| {
+            SolanaError::PoolParsingError("Account data too short for Whirlpool".to_string())
+        })?;
+        Self::try_from_slice(bytes)
+            .map_err(|e| SolanaError::PoolParsingError(format!("Whirlpool decode error: {}", e)))
+    }
+
+    /// Price of token A in token B, adjusted for decimals
+    pub fn price_a_in_b(&self, decimals_a: u8, decimals_b: u8) -> Result<f64, SolanaError> {
+        price_from_sqrt_price_x64(self.sqrt_price, decimals_a, decimals_b)
+    }
+
+    /// Whether the pair's base token is token A, given the pair's base and quote mints
+    pub fn base_is_token_a(&self, base_mint: &str, quote_mint: &str) -> Result<bool, SolanaError> {
+        base_is_first_mint(
+            "Whirlpool",
+            &self.token_mint_a,
+            &self.token_mint_b,
+            base_mint,
+            quote_mint,
+        )
+    }
+
+    /// Price of `base_mint` in `quote_mint`, adjusted for decimals
+    ///
+    /// Whirlpools order mints by address, so the base token may be either side.
+    pub fn price_of(
+        &self,
+        base_mint: &str,
+        quote_mint: &str,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> Result<f64, SolanaError> {
+        if self.base_is_token_a(base_mint, quote_mint)? {
+            self.price_a_in_b(base_decimals, quote_decimals)
+        } else {
+            let price_b_in_a = self.price_a_in_b(quote_decimals, base_decimals)?;
+            Ok(1.0 / price_b_in_a)
+        }
+    }
+
+    /// Trading fee as a percentage
+    #[allow(dead_code)]
+    pub fn fee_percentage(&self) -> f64 {
+        self.fee_rate as f64 / 10_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{USDC_MINT, USDT_MINT};
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn mint(address: &str) -> [u8; 32] {
+        bs58::decode(address)
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Q64.64 square root for a given raw (atom-denominated) price
+    fn sqrt_price_x64(raw_price: f64) -> u128 {
+        (raw_price.sqrt() * 2f64.powi(64)) as u128
+    }
+
+    fn sol_usdc_whirlpool(price: f64) -> WhirlpoolState {
+        WhirlpoolState {
+            fee_rate: 400,
+            // 1 SOL = 1e9 atoms, 1 USDC = 1e6 atoms
+            sqrt_price: sqrt_price_x64(price * 1e6 / 1e9),
+            token_mint_a: mint(SOL_MINT),
+            token_mint_b: mint(USDC_MINT),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_whirlpool_layout_size() {
+        let data = borsh::to_vec(&WhirlpoolState::default()).unwrap();
+        assert_eq!(data.len(), WhirlpoolState::LEN);
+    }
+
+    #[test]
+    fn test_whirlpool_decode_roundtrip() {
+        let state = sol_usdc_whirlpool(150.0);
+        let data = borsh::to_vec(&state).unwrap();
+
+        let decoded = WhirlpoolState::decode(&data).unwrap();
+        assert_eq!(decoded.sqrt_price, state.sqrt_price);
+        assert_eq!(decoded.fee_rate, 400);
+        assert!((decoded.fee_percentage() - 0.04).abs() < 1e-12);
+
+        assert!(WhirlpoolState::decode(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn test_whirlpool_price_base_as_token_a() {
+        let state = sol_usdc_whirlpool(150.0);
+        let price = state.price_of(SOL_MINT, USDC_MINT, 9, 6).unwrap();
+        assert!((price - 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_whirlpool_price_base_as_token_b() {
+        let mut state = sol_usdc_whirlpool(150.0);
+        std::mem::swap(&mut state.token_mint_a, &mut state.token_mint_b);
+        // USDC atoms are now token A: 1 USDC atom = 1e3 / 150 SOL atoms
+        state.sqrt_price = sqrt_price_x64(1e9 / 1e6 / 150.0);
+
+        let price = state.price_of(SOL_MINT, USDC_MINT, 9, 6).unwrap();
+        assert!((price - 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_whirlpool_unknown_mint() {
+        let state = sol_usdc_whirlpool(150.0);
+        assert!(state.price_of("UnknownMint", USDC_MINT, 9, 6).is_err());
+        // The base token alone is not enough: SOL/USDC is not priced as SOL/USDT
+        assert!(state.price_of(SOL_MINT, USDT_MINT, 9, 6).is_err());
+    }
+
+    #[test]
+    fn test_zero_sqrt_price_rejected() {
+        let state = WhirlpoolState::default();
+        assert!(state.price_a_in_b(9, 6).is_err());
+    }
+}
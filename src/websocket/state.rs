@@ -0,0 +1,217 @@
+use log::{debug, warn};
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+
+/// Transitions buffered per subscriber before the oldest are dropped
+const TRANSITION_CAPACITY: usize = 64;
+
+/// Connection state of a WebSocket client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionState {
+    /// Opening a connection
+    #[default]
+    Connecting,
+    /// Connected and subscribed, waiting for the first price update
+    Subscribed,
+    /// Receiving price updates
+    Streaming,
+    /// Waiting before reconnect attempt `attempt` after the connection was lost or failed
+    Backoff { attempt: usize },
+    /// Stopped: the server closed the stream or reconnecting gave up
+    Failed,
+}
+
+impl ConnectionState {
+    /// Every state, for exporting one series per state
+    pub const NAMES: [&'static str; 5] =
+        ["connecting", "subscribed", "streaming", "backoff", "failed"];
+
+    /// Short name of the state, without the attempt number
+    pub fn name(self) -> &'static str {
+        match self {
+            ConnectionState::Connecting => "connecting",
+            ConnectionState::Subscribed => "subscribed",
+            ConnectionState::Streaming => "streaming",
+            ConnectionState::Backoff { .. } => "backoff",
+            ConnectionState::Failed => "failed",
+        }
+    }
+
+    /// Whether the client holds a subscribed connection
+    pub fn is_connected(self) -> bool {
+        matches!(
+            self,
+            ConnectionState::Subscribed | ConnectionState::Streaming
+        )
+    }
+
+    /// Whether a client in this state may move to `next`
+    ///
+    /// Failed is final. Any other state may start a new connection, back off or fail;
+    /// a connection is subscribed while connecting and streams once subscribed.
+    pub fn can_transition_to(self, next: ConnectionState) -> bool {
+        match (self, next) {
+            (ConnectionState::Failed, _) => false,
+            (
+                _,
+                ConnectionState::Connecting
+                | ConnectionState::Backoff { .. }
+                | ConnectionState::Failed,
+            ) => true,
+            (ConnectionState::Connecting, ConnectionState::Subscribed) => true,
+            (ConnectionState::Subscribed, ConnectionState::Streaming) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::Subscribed => write!(f, "subscribed, waiting for the first update"),
+            ConnectionState::Streaming => write!(f, "streaming"),
+            ConnectionState::Backoff { attempt } => {
+                write!(f, "backing off before reconnect attempt {}", attempt)
+            }
+            ConnectionState::Failed => write!(f, "failed, not reconnecting"),
+        }
+    }
+}
+
+/// A change of a client's connection state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTransition {
+    /// Source name of the client, e.g. `binance`
+    pub source: &'static str,
+    pub from: ConnectionState,
+    pub to: ConnectionState,
+    /// How long the client was in `from`
+    pub in_previous: Duration,
+    /// When `to` was entered
+    pub at: Instant,
+}
+
+/// Tracks a client's connection state and publishes every transition
+///
+/// The current state is published on a watch channel and each transition, with its
+/// timing, on a broadcast channel.
+#[derive(Debug)]
+pub struct ConnectionStateMachine {
+    source: &'static str,
+    state: watch::Sender<ConnectionState>,
+    transitions: broadcast::Sender<StateTransition>,
+    entered: Mutex<Instant>,
+}
+
+impl ConnectionStateMachine {
+    /// State machine for the client of `source`, connecting from now
+    pub fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            state: watch::channel(ConnectionState::default()).0,
+            transitions: broadcast::channel(TRANSITION_CAPACITY).0,
+            entered: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Move to `next` and publish the transition
+    ///
+    /// Staying in the current state is a no-op, and a transition the state machine does
+    /// not allow is logged and ignored. Returns whether the state changed.
+    pub fn transition(&self, next: ConnectionState) -> bool {
+        let current = self.get();
+        if current == next {
+            return false;
+        }
+        if !current.can_transition_to(next) {
+            warn!(
+                "Ignoring {} connection transition from {} to {}",
+                self.source, current, next
+            );
+            return false;
+        }
+
+        let at = Instant::now();
+        let in_previous = {
+            let mut entered = self.lock_entered();
+            let in_previous = at.saturating_duration_since(*entered);
+            *entered = at;
+            in_previous
+        };
+        self.state.send_replace(next);
+        // Sending only fails when nobody is subscribed
+        let _ = self.transitions.send(StateTransition {
+            source: self.source,
+            from: current,
+            to: next,
+            in_previous,
+            at,
+        });
+        debug!(
+            "{} connection {} -> {} after {:?}",
+            self.source, current, next, in_previous
+        );
+        true
+    }
+
+    /// Current state
+    pub fn get(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Receiver of the current state
+    pub fn subscribe(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Receiver of every later transition
+    pub fn transitions(&self) -> broadcast::Receiver<StateTransition> {
+        self.transitions.subscribe()
+    }
+
+    fn lock_entered(&self) -> MutexGuard<'_, Instant> {
+        self.entered.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions_are_published_with_timing() {
+        let machine = ConnectionStateMachine::new("binance");
+        let mut transitions = machine.transitions();
+        let state = machine.subscribe();
+
+        assert!(machine.transition(ConnectionState::Subscribed));
+        assert!(!machine.transition(ConnectionState::Subscribed));
+        assert!(machine.transition(ConnectionState::Streaming));
+        assert_eq!(*state.borrow(), ConnectionState::Streaming);
+
+        let first = transitions.try_recv().unwrap();
+        assert_eq!(first.source, "binance");
+        assert_eq!(first.from, ConnectionState::Connecting);
+        assert_eq!(first.to, ConnectionState::Subscribed);
+        let second = transitions.try_recv().unwrap();
+        assert_eq!(second.from, ConnectionState::Subscribed);
+        assert_eq!(second.in_previous, second.at - first.at);
+        assert!(transitions.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_invalid_transitions_are_ignored() {
+        let machine = ConnectionStateMachine::new("solana");
+        assert!(!machine.transition(ConnectionState::Streaming));
+        assert_eq!(machine.get(), ConnectionState::Connecting);
+
+        assert!(machine.transition(ConnectionState::Backoff { attempt: 1 }));
+        assert!(!machine.transition(ConnectionState::Subscribed));
+        assert!(machine.transition(ConnectionState::Failed));
+        assert!(!machine.transition(ConnectionState::Connecting));
+        assert_eq!(machine.get(), ConnectionState::Failed);
+    }
+}